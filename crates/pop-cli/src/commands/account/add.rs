@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{intro, outro, password, set_theme};
+use console::style;
+use pop_contracts::{accounts_file, read_suri, Account, Accounts, Scheme, PASSWORD_ENV};
+use serde_json::json;
+use std::{env, fs, path::PathBuf};
+
+use super::scheme_parser;
+use crate::{
+	output::{self, clear_screen},
+	style::Theme,
+};
+
+#[derive(Args)]
+pub struct AddAccountCommand {
+	/// The name of the account, which can be used in place of a secret key URI with `--suri`.
+	name: String,
+	/// Import the account from a polkadot-js JSON keystore. The account remains encrypted with the
+	/// password of the keystore.
+	#[arg(long, conflicts_with = "suri")]
+	json: Option<PathBuf>,
+	/// Secret key URI of the account, such as a mnemonic optionally followed by a derivation path.
+	/// To keep the secret out of the shell history, use `env:VAR` to read it from an environment
+	/// variable or `file:path` to read it from a file. If not specified, it will be prompted for.
+	#[clap(name = "suri", long, short)]
+	suri: Option<String>,
+	/// The cryptographic scheme of the account added from a secret key URI.
+	#[arg(long, default_value = "sr25519", value_parser = scheme_parser(), conflicts_with = "json")]
+	scheme: Scheme,
+}
+
+impl AddAccountCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Adding an account", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let mut accounts = Accounts::load(&accounts_file()?)?;
+		let account = self.add(&mut accounts, env::var(PASSWORD_ENV).ok().as_deref())?;
+		accounts.save()?;
+		output::set_result(json!({ "name": account.name(), "address": account.address }))?;
+		outro(format!("Account `{}` added: {}", account.name(), account.address))?;
+		Ok(())
+	}
+
+	// Adds the account to the account store, prompting for any secrets not provided, including the
+	// password of the account unless specified.
+	fn add(&self, accounts: &mut Accounts, specified: Option<&str>) -> anyhow::Result<Account> {
+		if accounts.get(&self.name).is_some() {
+			anyhow::bail!("An account named `{}` already exists.", self.name);
+		}
+		let account = match &self.json {
+			Some(keystore) => {
+				let password =
+					account_password(specified, "Enter the password of the keystore", false)?;
+				Account::import(&self.name, &fs::read_to_string(keystore)?, &password)?
+			},
+			None => {
+				let suri = match &self.suri {
+					Some(suri) => read_suri(suri)?,
+					None => {
+						output::ensure_interactive(
+							"the secret key URI of the account (use `--suri`)",
+						)?;
+						password("Enter the mnemonic or secret key URI of the account")
+							.mask('▪')
+							.interact()?
+					},
+				};
+				let password =
+					account_password(specified, "Enter a password to encrypt the account", true)?;
+				Account::new(&self.name, &suri, self.scheme, &password)?
+			},
+		};
+		accounts.add(account.clone())?;
+		Ok(account)
+	}
+}
+
+// Returns the password specified, such as by the `POP_ACCOUNT_PASSWORD` environment variable, or
+// otherwise prompts for it.
+fn account_password(
+	specified: Option<&str>,
+	prompt: &str,
+	confirm: bool,
+) -> anyhow::Result<String> {
+	if let Some(password) = specified {
+		return Ok(password.to_string());
+	}
+	output::ensure_interactive(&format!("a password (set `{PASSWORD_ENV}`)"))?;
+	let entered = password(prompt).mask('▪').interact()?;
+	if confirm && password("Confirm the password").mask('▪').interact()? != entered {
+		anyhow::bail!("The passwords do not match.");
+	}
+	Ok(entered)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		commands::account::{AccountArgs, AccountCommands},
+		Cli,
+		Commands::Account as AccountCommand,
+	};
+	use clap::Parser;
+
+	fn add_command(args: &[&str]) -> AddAccountCommand {
+		let cli = Cli::parse_from([&["pop", "account", "add"], args].concat());
+		let AccountCommand(AccountArgs { command: AccountCommands::Add(command) }) = cli.command
+		else {
+			panic!("unable to parse command")
+		};
+		command
+	}
+
+	#[test]
+	fn add_account_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut accounts = Accounts::load(&temp_dir.path().join("accounts.json"))?;
+		let suri = temp_dir.path().join("suri");
+		fs::write(&suri, "//Alice")?;
+		let command = add_command(&["alice", "--suri", &format!("file:{}", suri.display())]);
+		let account = command.add(&mut accounts, Some("secret"))?;
+		assert_eq!(account.address, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+		assert!(command.add(&mut accounts, Some("secret")).is_err());
+
+		// Export the account as a keystore, to be imported under another name.
+		let keystore = temp_dir.path().join("alice.json");
+		fs::write(&keystore, serde_json::to_string(&account)?)?;
+		let command = add_command(&["imported", "--json", keystore.to_str().unwrap()]);
+		assert!(command.add(&mut accounts, Some("wrong")).is_err());
+		assert_eq!(command.add(&mut accounts, Some("secret"))?.address, account.address);
+		assert_eq!(accounts.list().len(), 2);
+		Ok(())
+	}
+
+	#[test]
+	fn scheme_can_be_specified() {
+		assert_eq!(add_command(&["alice"]).scheme, Scheme::Sr25519);
+		assert_eq!(add_command(&["alice", "--scheme", "ecdsa"]).scheme, Scheme::Ecdsa);
+		assert!(Cli::try_parse_from(["pop", "account", "add", "alice", "--scheme", "bls"]).is_err());
+	}
+}
@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use console::style;
+use pop_contracts::{accounts_file, Accounts};
+use serde_json::json;
+
+use crate::{
+	output::{self, clear_screen},
+	style::Theme,
+};
+
+#[derive(Args)]
+pub struct ListAccountsCommand {}
+
+impl ListAccountsCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Listing accounts", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let path = accounts_file()?;
+		let accounts = Accounts::load(&path)?;
+		for account in accounts.list() {
+			log::info(format!("{}: {}", account.name(), account.address))?;
+		}
+		output::set_result(
+			accounts
+				.list()
+				.iter()
+				.map(|account| json!({ "name": account.name(), "address": account.address }))
+				.collect(),
+		)?;
+		if accounts.list().is_empty() {
+			outro(format!(
+				"No accounts found. Add one using {}.",
+				style("pop account add").bold()
+			))?;
+		} else {
+			outro(format!("Accounts stored in {}.", path.display()))?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{
+	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+	Args, Subcommand,
+};
+use cliclack::password;
+use pop_contracts::{find_account, Scenario, Scheme, PASSWORD_ENV};
+use std::{env, str::FromStr};
+use strum::VariantArray;
+
+pub(crate) mod add;
+pub(crate) mod list;
+pub(crate) mod remove;
+pub(crate) mod transfer;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct AccountArgs {
+	#[command(subcommand)]
+	pub command: AccountCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum AccountCommands {
+	/// Add an account to the account store, from a mnemonic or a polkadot-js JSON keystore.
+	#[clap(alias = "a")]
+	Add(add::AddAccountCommand),
+	/// List the accounts in the account store.
+	#[clap(alias = "l")]
+	List(list::ListAccountsCommand),
+	/// Remove an account from the account store.
+	#[clap(alias = "r")]
+	Remove(remove::RemoveAccountCommand),
+	/// Transfer a balance from an account to another.
+	#[clap(alias = "t")]
+	Transfer(transfer::TransferCommand),
+}
+
+/// Parses the cryptographic scheme of an account, listing the supported schemes as possible values.
+pub(crate) fn scheme_parser() -> impl TypedValueParser<Value = Scheme> {
+	crate::enum_variants!(Scheme)
+}
+
+/// Prompts for the password of the stored account named by the secret key URI, unless provided by
+/// the `POP_ACCOUNT_PASSWORD` environment variable, so that the account can be unlocked. Returns
+/// `None` when the secret key URI does not name a stored account.
+///
+/// # Arguments
+///
+/// * `suri` - the secret key URI, account name or source of the secret key URI.
+/// * `scheme` - the cryptographic scheme of the account, when specified by a secret key URI.
+pub(crate) fn account_password(suri: &str, scheme: Scheme) -> anyhow::Result<Option<String>> {
+	let Some(account) = find_account(suri, scheme)? else {
+		return Ok(None);
+	};
+	if let Ok(password) = env::var(PASSWORD_ENV) {
+		return Ok(Some(password));
+	}
+	crate::output::ensure_interactive(&format!(
+		"the password of account `{}` (set `{PASSWORD_ENV}`)",
+		account.name()
+	))?;
+	let password = password(format!("Enter the password of account `{}`", account.name()))
+		.mask('▪')
+		.interact()?;
+	Ok(Some(password))
+}
+
+/// Obtains the passwords of any stored accounts signing the steps of a scenario, so that they can
+/// be unlocked.
+///
+/// # Arguments
+///
+/// * `scenario` - the scenario to be run.
+pub(crate) fn unlock_scenario(scenario: &mut Scenario) -> anyhow::Result<()> {
+	let signers: Vec<_> = std::iter::once((scenario.suri.clone(), scenario.scheme))
+		.chain(scenario.steps.iter().filter_map(|step| {
+			step.suri.clone().map(|suri| (suri, step.scheme.unwrap_or(scenario.scheme)))
+		}))
+		.collect();
+	for (suri, scheme) in signers {
+		if scenario.passwords.contains_key(&suri) {
+			continue;
+		}
+		if let Some(password) = account_password(&suri, scheme)? {
+			scenario.passwords.insert(suri, password);
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{confirm, intro, outro, outro_cancel, set_theme};
+use console::style;
+use pop_contracts::{accounts_file, Accounts};
+use serde_json::json;
+
+use crate::{
+	output::{self, clear_screen},
+	style::Theme,
+};
+
+#[derive(Args)]
+pub struct RemoveAccountCommand {
+	/// The name of the account to remove.
+	name: String,
+	/// Remove the account without asking for confirmation.
+	#[clap(short('y'), long)]
+	skip_confirm: bool,
+}
+
+impl RemoveAccountCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Removing an account", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let mut accounts = Accounts::load(&accounts_file()?)?;
+		let Some(account) = accounts.get(&self.name) else {
+			outro_cancel(format!("No account named `{}` found.", self.name))?;
+			return Ok(());
+		};
+		if !self.skip_confirm {
+			output::ensure_interactive("confirmation to remove the account (use `-y` to skip it)")?;
+		}
+		if !self.skip_confirm
+			&& !confirm(format!(
+				"Would you like to remove account `{}` ({})? Its secret cannot be recovered unless backed up elsewhere.",
+				self.name, account.address
+			))
+			.initial_value(false)
+			.interact()?
+		{
+			outro_cancel("Account not removed.")?;
+			return Ok(());
+		}
+		accounts.remove(&self.name)?;
+		accounts.save()?;
+		output::set_result(json!({ "name": self.name }))?;
+		outro(format!("Account `{}` removed.", self.name))?;
+		Ok(())
+	}
+}
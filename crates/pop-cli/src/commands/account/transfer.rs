@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{confirm, intro, outro, outro_cancel, set_theme};
+use console::style;
+use pop_contracts::{transfer_balance, Scheme, TransferOpts, WaitFor};
+use serde_json::json;
+
+use crate::{
+	commands::{
+		account::{account_password, scheme_parser},
+		call::contract::{show_status, TransactionArgs},
+	},
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::Theme,
+};
+
+#[derive(Args)]
+pub struct TransferCommand {
+	/// The address of the account receiving the balance.
+	dest: String,
+	/// The balance to transfer, e.g. `1000` or `1.5UNIT`.
+	value: String,
+	/// Websocket endpoint of a node.
+	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
+	url: url::Url,
+	/// Secret key URI for the account transferring the balance, or the name of an account added
+	/// using `pop account add`.
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	/// - read from an environment variable "env:VAR"
+	/// - read from a file "file:path"
+	#[clap(name = "suri", long, short, default_value = "//Alice")]
+	suri: String,
+	/// The cryptographic scheme of the account, when specified by a secret key URI. Accounts added
+	/// using `pop account add` use the scheme they were added with.
+	#[arg(long, default_value = "sr25519", value_parser = scheme_parser())]
+	scheme: Scheme,
+	/// Submit the transfer without asking for confirmation.
+	#[clap(short('y'), long)]
+	skip_confirm: bool,
+	#[command(flatten)]
+	transaction: TransactionArgs,
+}
+
+impl TransferCommand {
+	/// Uses the endpoint and account configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = url;
+		}
+		if let Some(suri) = config.get("suri", |s| &s.suri) {
+			self.suri = suri;
+		}
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Transferring a balance", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		if !self.skip_confirm {
+			output::ensure_interactive(
+				"confirmation to submit the transfer (use `-y` to skip it)",
+			)?;
+		}
+		if !self.skip_confirm &&
+			!confirm(format!("Would you like to transfer {} to {}?", self.value, self.dest))
+				.initial_value(true)
+				.interact()?
+		{
+			outro_cancel("Transfer cancelled.")?;
+			return Ok(());
+		}
+		let password = account_password(&self.suri, self.scheme)?;
+		let transfer_opts = TransferOpts {
+			dest: self.dest.clone(),
+			value: self.value.clone(),
+			url: self.url.clone(),
+			suri: self.suri.clone(),
+			scheme: self.scheme,
+			password,
+		};
+		let spinner = cliclack::spinner();
+		spinner.start("Transferring the balance...");
+		let result = match transfer_balance(
+			&transfer_opts,
+			&self.transaction.tx_opts(),
+			show_status(&spinner),
+		)
+		.await
+		{
+			Ok(result) => result,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Transfer failed.")?;
+				return Err(e);
+			},
+		};
+		spinner.stop(match self.transaction.tx_opts().wait_for {
+			WaitFor::InBlock => "Transfer included in a block",
+			WaitFor::Finalized => "Transfer finalized",
+		});
+		// Balances are output as strings, as they may exceed the range of JSON numbers.
+		output::set_result(json!({
+			"dest": self.dest,
+			"value": result.value.to_string(),
+			"extrinsic_hash": result.extrinsic_hash,
+			"block_hash": result.block_hash,
+		}))?;
+		outro(format!(
+			"Transferred {} to {} in block {}",
+			self.value, self.dest, result.block_hash
+		))?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{commands::account::AccountCommands, Cli, Commands::Account};
+	use clap::Parser;
+
+	fn transfer_command(args: &[&str]) -> TransferCommand {
+		let cli = Cli::parse_from([&["pop", "account", "transfer"], args].concat());
+		let Account(account) = cli.command else { panic!("unable to parse command") };
+		let AccountCommands::Transfer(command) = account.command else {
+			panic!("unable to parse command")
+		};
+		command
+	}
+
+	#[test]
+	fn transfer_args_are_parsed() {
+		let command =
+			transfer_command(&["5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty", "1UNIT"]);
+		assert_eq!(command.dest, "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty");
+		assert_eq!(command.value, "1UNIT");
+		assert_eq!(command.scheme, Scheme::Sr25519);
+
+		let command = transfer_command(&[
+			"5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty",
+			"1000",
+			"--suri",
+			"//Bob",
+			"--scheme",
+			"ecdsa",
+			"--wait",
+			"finalized",
+		]);
+		assert_eq!(command.suri, "//Bob");
+		assert_eq!(command.scheme, Scheme::Ecdsa);
+		assert_eq!(command.transaction.tx_opts().wait_for, WaitFor::Finalized);
+	}
+}
@@ -3,11 +3,14 @@
 use std::path::PathBuf;
 
 use clap::Args;
-use cliclack::{clear_screen, intro, log, outro, set_theme};
+use cliclack::{intro, log, outro, set_theme};
 use console::style;
 
-use crate::style::Theme;
-use pop_contracts::build_smart_contract;
+use crate::{
+	output::{self, clear_screen},
+	style::Theme,
+};
+use pop_contracts::{build_smart_contract, BuildArtifacts};
 
 #[derive(Args)]
 pub struct BuildContractCommand {
@@ -16,7 +19,7 @@ pub struct BuildContractCommand {
 	/// The default compilation includes debug functionality, increasing contract size and gas usage.
 	/// For production, always build in release mode to exclude debug features.
 	#[clap(long = "release")]
-	build_release: bool,
+	pub(crate) build_release: bool,
 }
 
 impl BuildContractCommand {
@@ -25,9 +28,91 @@ impl BuildContractCommand {
 		intro(format!("{}: Building your contract", style(" Pop CLI ").black().on_magenta()))?;
 		set_theme(Theme);
 
-		let result_build = build_smart_contract(&self.path, self.build_release)?;
+		let artifacts = build_smart_contract(&self.path, self.build_release)?;
+		output::set_result(serde_json::to_value(&artifacts)?)?;
 		outro("Build completed successfully!")?;
-		log::success(result_build.to_string())?;
+		log::success(display_build(&artifacts))?;
 		Ok(())
 	}
 }
+
+/// Formats the artifacts of a contract build for display.
+///
+/// # Arguments
+///
+/// * `artifacts` - the artifacts of the build.
+pub(crate) fn display_build(artifacts: &BuildArtifacts) -> String {
+	let mut display = String::new();
+	if let (Some(original_size), Some(code_size)) = (artifacts.original_size, artifacts.code_size) {
+		display.push_str(&format!(
+			"Original wasm size: {}, Optimized: {}\n\n",
+			style(format!("{:.1}K", original_size as f64 / 1000.0)).bold(),
+			style(format!("{:.1}K", code_size as f64 / 1000.0)).bold(),
+		));
+	}
+	let mode = match artifacts.release {
+		true => "RELEASE",
+		false => "DEBUG",
+	};
+	display.push_str(&format!("The contract was built in {} mode.\n\n", style(mode).bold()));
+	if let Some(code_hash) = &artifacts.code_hash {
+		display.push_str(&format!("Code hash: {code_hash}\n\n"));
+	}
+	display.push_str(&format!(
+		"Your contract artifacts are ready. You can find them in:\n{}\n",
+		style(artifacts.target_directory.display()).bold()
+	));
+	let files = [
+		(&artifacts.bundle, "code + metadata"),
+		(&artifacts.code, "the contract's code"),
+		(&artifacts.metadata, "the contract's metadata"),
+	];
+	for (path, description) in files {
+		if let Some(name) = path.as_ref().and_then(|p| p.file_name()) {
+			display.push_str(&format!(
+				"\n  - {} ({description})",
+				style(name.to_string_lossy()).bold()
+			));
+		}
+	}
+	display
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn display_build_works() {
+		let target_directory = PathBuf::from("flipper/target/ink");
+		let artifacts = BuildArtifacts {
+			release: true,
+			code: Some(target_directory.join("flipper.wasm")),
+			metadata: Some(target_directory.join("flipper.json")),
+			bundle: Some(target_directory.join("flipper.contract")),
+			target_directory,
+			original_size: Some(10500),
+			code_size: Some(4210),
+			code_hash: Some("0x01".to_string()),
+		};
+		assert_eq!(
+			display_build(&artifacts),
+			"Original wasm size: 10.5K, Optimized: 4.2K\n\n\
+			 The contract was built in RELEASE mode.\n\n\
+			 Code hash: 0x01\n\n\
+			 Your contract artifacts are ready. You can find them in:\nflipper/target/ink\n\n  \
+			 - flipper.contract (code + metadata)\n  \
+			 - flipper.wasm (the contract's code)\n  \
+			 - flipper.json (the contract's metadata)"
+		);
+		let artifacts = BuildArtifacts {
+			target_directory: PathBuf::from("flipper/target/ink"),
+			..Default::default()
+		};
+		assert_eq!(
+			display_build(&artifacts),
+			"The contract was built in DEBUG mode.\n\n\
+			 Your contract artifacts are ready. You can find them in:\nflipper/target/ink\n"
+		);
+	}
+}
@@ -1,6 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use super::{detect_project, project_not_found};
+use crate::output;
 use clap::{Args, Subcommand};
+use serde_json::Value;
+use std::path::PathBuf;
 
 #[cfg(feature = "contract")]
 pub(crate) mod contract;
@@ -11,7 +15,46 @@ pub(crate) mod parachain;
 #[command(args_conflicts_with_subcommands = true)]
 pub(crate) struct BuildArgs {
 	#[command(subcommand)]
-	pub command: BuildCommands,
+	pub command: Option<BuildCommands>,
+	/// Directory path for your project, when building without specifying whether it is a contract
+	/// or a parachain [default: current directory]
+	#[arg(short = 'p', long)]
+	pub(crate) path: Option<PathBuf>,
+	/// Build contracts in release mode. Parachains are always built in release mode.
+	#[arg(long)]
+	pub(crate) release: bool,
+}
+
+impl BuildArgs {
+	/// Builds the project, as detected from its manifest: every contract of a workspace, followed
+	/// by its parachain.
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		let project = detect_project(&self.path)?;
+		let mut builds = Vec::new();
+		#[cfg(feature = "contract")]
+		for path in project.contracts {
+			let command =
+				contract::BuildContractCommand { path: Some(path), build_release: self.release };
+			command.execute()?;
+			builds.extend(output::take_result());
+		}
+		#[cfg(feature = "parachain")]
+		if let Some(path) = project.parachain {
+			// Only the packages of the parachain are built, the contracts having been built above.
+			let packages = project
+				.parachain_packages
+				.iter()
+				.filter_map(|package| pop_common::project::package_name(package).transpose())
+				.collect::<Result<_, _>>()?;
+			parachain::BuildParachainCommand { path: Some(path), packages }.execute()?;
+			builds.extend(output::take_result());
+		}
+		match builds.len() {
+			0 => Err(project_not_found(&self.path, "build")),
+			1 => output::set_result(builds.remove(0)),
+			_ => output::set_result(Value::Array(builds)),
+		}
+	}
 }
 
 #[derive(Subcommand)]
@@ -1,9 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::style::{style, Theme};
+use crate::{
+	output::{self, clear_screen},
+	style::{style, Theme},
+};
 use clap::Args;
-use cliclack::{clear_screen, intro, log::warning, outro, set_theme};
+use cliclack::{intro, log::warning, outro, set_theme};
 use pop_parachains::build_parachain;
+use serde_json::json;
 use std::path::PathBuf;
 
 #[derive(Args)]
@@ -14,6 +18,9 @@ pub struct BuildParachainCommand {
 		help = "Directory path for your project, [default: current directory]"
 	)]
 	pub(crate) path: Option<PathBuf>,
+	/// The packages to build, when building the parachain of a workspace which also has contracts.
+	#[arg(skip)]
+	pub(crate) packages: Vec<String>,
 }
 
 impl BuildParachainCommand {
@@ -23,7 +30,8 @@ impl BuildParachainCommand {
 		set_theme(Theme);
 
 		warning("NOTE: this may take some time...")?;
-		build_parachain(&self.path)?;
+		build_parachain(&self.path, &self.packages)?;
+		output::set_result(json!({ "path": self.path.clone().unwrap_or_else(|| "./".into()) }))?;
 
 		outro("Build Completed Successfully!")?;
 		Ok(())
@@ -1,28 +1,45 @@
 // SPDX-License-Identifier: GPL-3.0
 
 use anyhow::anyhow;
-use clap::Args;
-use cliclack::{clear_screen, intro, log, outro, outro_cancel, set_theme};
-use console::style;
+use clap::{
+	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+	Args,
+};
+use cliclack::{confirm, input, intro, log, outro, outro_cancel, set_theme, ProgressBar};
+use console::{style, Term};
 use pop_contracts::{
-	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, set_up_call, CallOpts,
+	apply_weight_margin, call_smart_contract, dry_run_call, dry_run_estimate_call,
+	estimate_call_fee, format_balance, get_messages, set_up_call, CallOpts, ExtrinsicEvent, Scheme,
+	TxEvent, TxOpts, WaitFor,
 };
+use serde_json::json;
 use sp_weights::Weight;
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr, time::Duration};
+use strum::VariantArray;
 
-use crate::style::Theme;
+use crate::{
+	commands::{
+		account::{account_password, scheme_parser},
+		common::contract::{prompt_function_args, select_function},
+	},
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::Theme,
+};
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 pub struct CallContractCommand {
 	/// Path to the contract build folder.
 	#[arg(short = 'p', long)]
 	path: Option<PathBuf>,
-	/// The address of the contract to call.
+	/// The address of the contract to call, or the alias or name of a contract deployed using
+	/// `pop up contract`.
 	#[clap(name = "contract", long, env = "CONTRACT")]
 	contract: String,
-	/// The name of the contract message to call.
+	/// The name of the contract message to call. If empty, the messages of the contract will be
+	/// listed to select from.
 	#[clap(long, short)]
-	message: String,
+	message: Option<String>,
 	/// The constructor arguments, encoded as strings.
 	#[clap(long, num_args = 0..)]
 	args: Vec<String>,
@@ -38,82 +55,452 @@ pub struct CallContractCommand {
 	/// If not specified it will perform a dry-run to estimate the proof size required.
 	#[clap(long)]
 	proof_size: Option<u64>,
+	/// Maximum storage deposit which may be charged for the call, e.g. `1000` or `1.5UNIT`. If
+	/// not specified, the call may use all of the free balance of the account.
+	#[clap(long)]
+	storage_deposit_limit: Option<String>,
+	/// The percentage by which the gas and proof size estimated by a dry run are increased, so
+	/// that the call does not run out of gas when the state of the chain changes before it is
+	/// included in a block.
+	#[clap(long, default_value_t = DEFAULT_WEIGHT_MARGIN)]
+	weight_margin: u32,
 	/// Websocket endpoint of a node.
 	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
 	url: url::Url,
-	/// Secret key URI for the account deploying the contract.
+	/// Secret key URI for the account calling the contract, or the name of an account added using
+	/// `pop account add`.
 	///
 	/// e.g.
 	/// - for a dev account "//Alice"
 	/// - with a password "//Alice///SECRET_PASSWORD"
+	/// - read from an environment variable "env:VAR"
+	/// - read from a file "file:path"
+	///
+	/// [default: the signer configured for the network in `pop.toml`]
 	#[clap(name = "suri", long, short)]
-	suri: String,
+	suri: Option<String>,
+	/// The cryptographic scheme of the account, when specified by a secret key URI. Accounts added
+	/// using `pop account add` use the scheme they were added with.
+	#[arg(long, default_value = "sr25519", value_parser = scheme_parser())]
+	scheme: Scheme,
 	/// Submit an extrinsic for on-chain execution.
 	#[clap(short('x'), long)]
 	execute: bool,
+	/// Submit the extrinsic without asking for confirmation of its estimated cost.
+	#[clap(short('y'), long)]
+	skip_confirm: bool,
+	#[command(flatten)]
+	transaction: TransactionArgs,
+}
+
+/// Options controlling the submission of an extrinsic.
+#[derive(Args, Clone, Debug, Default, PartialEq)]
+pub(crate) struct TransactionArgs {
+	/// The status the extrinsic must reach before the command completes. Chains without finality,
+	/// such as a contracts node producing blocks instantly, never finalize the extrinsic.
+	#[arg(long = "wait", default_value = "in-block", value_parser = wait_for_parser())]
+	wait_for: WaitFor,
+	/// The nonce of the extrinsic, such as to submit several extrinsics without waiting for each
+	/// to be included [default: the next nonce of the account].
+	#[arg(long)]
+	nonce: Option<u64>,
+	/// A tip paid to the block author to prioritise the extrinsic, in the smallest unit of the
+	/// token of the chain.
+	#[arg(long, default_value_t = 0)]
+	tip: u128,
+	/// The number of blocks for which the extrinsic is valid, rounded to a power of two
+	/// [default: valid until included].
+	#[arg(long)]
+	mortality: Option<u64>,
+	/// The number of seconds to wait for the extrinsic to be finalized once it is included in a
+	/// block, when waiting for finalization [default: 120].
+	#[arg(long, value_name = "SECONDS")]
+	finalization_timeout: Option<u64>,
+}
+
+impl TransactionArgs {
+	/// Options for submitting the extrinsic, as specified by the command arguments.
+	pub(crate) fn tx_opts(&self) -> TxOpts {
+		TxOpts {
+			wait_for: self.wait_for,
+			nonce: self.nonce,
+			tip: self.tip,
+			mortality: self.mortality,
+			finalization_timeout: self.finalization_timeout.map(Duration::from_secs),
+		}
+	}
+}
+
+fn wait_for_parser() -> impl TypedValueParser<Value = WaitFor> {
+	crate::enum_variants!(WaitFor)
 }
 
 impl CallContractCommand {
+	/// Uses the endpoint and account configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = url;
+		}
+		if let Some(suri) = config.get("suri", |s| &s.suri) {
+			self.suri = Some(suri);
+		}
+	}
+
 	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
 		clear_screen()?;
 		intro(format!("{}: Calling a contract", style(" Pop CLI ").black().on_magenta()))?;
 		set_theme(Theme);
 
+		let call_config = if self.message.is_none() {
+			// If the user doesn't specify a message, guide them to select one from the contract.
+			guide_user_to_call_contract(self)?
+		} else {
+			self.clone()
+		};
+		let message = call_config
+			.message
+			.clone()
+			.expect("message can not be none as fallback above is interactive input; qed");
+
+		let suri = call_config.suri.clone().ok_or_else(|| {
+			anyhow!("No account was specified to call the contract: use `--suri` or configure a `suri` in pop.toml")
+		})?;
+		let password = account_password(&suri, call_config.scheme)?;
 		let call_exec = set_up_call(CallOpts {
-			path: self.path.clone(),
-			contract: self.contract.clone(),
-			message: self.message.clone(),
-			args: self.args.clone(),
-			value: self.value.clone(),
-			gas_limit: self.gas_limit,
-			proof_size: self.proof_size,
-			url: self.url.clone(),
-			suri: self.suri.clone(),
-			execute: self.execute,
+			path: call_config.path.clone(),
+			contract: call_config.contract.clone(),
+			message,
+			args: call_config.args.clone(),
+			value: call_config.value.clone(),
+			gas_limit: call_config.gas_limit,
+			proof_size: call_config.proof_size,
+			storage_deposit_limit: call_config.storage_deposit_limit.clone(),
+			url: call_config.url.clone(),
+			suri,
+			scheme: call_config.scheme,
+			password,
+			execute: call_config.execute,
 		})
 		.await?;
 
-		if !self.execute {
+		if !call_config.execute {
 			let spinner = cliclack::spinner();
 			spinner.start("Calling the contract...");
 			let call_dry_run_result = dry_run_call(&call_exec).await?;
-			log::info(format!("Result: {}", call_dry_run_result))?;
+			spinner.clear();
+			output::set_result(serde_json::to_value(&call_dry_run_result)?)?;
+			if !call_dry_run_result.debug_message.is_empty() {
+				log::info(format!("Debug output: {}", call_dry_run_result.debug_message.trim()))?;
+			}
+			match call_dry_run_result.result {
+				Ok(value) => log::info(format!("Result: {}", value))?,
+				Err(error) => {
+					outro_cancel(format!("Call failed. {error}"))?;
+					return Err(anyhow!("the call reverted: {error}"));
+				},
+			}
 			log::warning("Your call has not been executed.")?;
 			log::warning(format!(
                     "To submit the transaction and execute the call on chain, add {} flag to the command.",
                     "-x/--execute"
             ))?;
 		} else {
-			let weight_limit;
-			if self.gas_limit.is_some() && self.proof_size.is_some() {
-				weight_limit =
-					Weight::from_parts(self.gas_limit.unwrap(), self.proof_size.unwrap());
-			} else {
-				let spinner = cliclack::spinner();
-				spinner.start("Doing a dry run to estimate the gas...");
-				weight_limit = match dry_run_gas_estimate_call(&call_exec).await {
-					Ok(w) => {
-						log::info(format!("Gas limit {:?}", w))?;
-						w
-					},
-					Err(e) => {
-						spinner.error(format!("{e}"));
-						outro_cancel("Deployment failed.")?;
-						return Ok(());
+			let spinner = cliclack::spinner();
+			spinner.start("Doing a dry run to estimate the cost...");
+			let (weight_limit, storage_deposit) =
+				match (call_config.gas_limit, call_config.proof_size) {
+					(Some(gas_limit), Some(proof_size)) =>
+						(Weight::from_parts(gas_limit, proof_size), None),
+					_ => match dry_run_estimate_call(&call_exec).await {
+						Ok((weight, storage_deposit)) => (
+							weight_limit(
+								weight,
+								call_config.weight_margin,
+								call_config.gas_limit,
+								call_config.proof_size,
+							),
+							Some(storage_deposit),
+						),
+						Err(e) => {
+							spinner.error(format!("{e}"));
+							outro_cancel("Call failed.")?;
+							return Err(e);
+						},
 					},
 				};
+			let fee = match estimate_call_fee(&call_exec, weight_limit).await {
+				Ok(fee) => fee,
+				Err(e) => {
+					spinner.error(format!("{e}"));
+					outro_cancel("Call failed.")?;
+					return Err(e);
+				},
+			};
+			spinner.clear();
+			if !confirm_cost(
+				Some(weight_limit),
+				storage_deposit,
+				fee,
+				&call_config.url,
+				call_config.skip_confirm,
+			)
+			.await?
+			{
+				outro_cancel("Call cancelled.")?;
+				return Ok(());
 			}
 			let spinner = cliclack::spinner();
 			spinner.start("Calling the contract...");
 
-			let call_result = call_smart_contract(call_exec, weight_limit, &self.url)
-				.await
-				.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+			let call_result = call_smart_contract(
+				call_exec,
+				weight_limit,
+				&call_config.url,
+				&call_config.transaction.tx_opts(),
+				show_status(&spinner),
+			)
+			.await
+			.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+			spinner.stop(match call_config.transaction.wait_for {
+				WaitFor::InBlock => "Call included in a block",
+				WaitFor::Finalized => "Call finalized",
+			});
 
-			log::info(call_result)?;
+			log::info(display_events(&call_result.events))?;
+			// Balances are output as strings, as they may exceed the range of JSON numbers.
+			output::set_result(json!({
+				"block_hash": call_result.block_hash,
+				"gas_limit": weight_limit,
+				"gas_used": call_result.gas_used,
+				"storage_deposit": call_result.storage_deposit.to_string(),
+				"estimated_storage_deposit": storage_deposit.map(|deposit| deposit.to_string()),
+				"fee": fee.to_string(),
+				"events": call_result.events,
+			}))?;
 		}
 
 		outro("Call completed successfully!")?;
 		Ok(())
 	}
 }
+
+/// The default percentage by which the gas and proof size estimated by a dry run are increased.
+pub(crate) const DEFAULT_WEIGHT_MARGIN: u32 = 10;
+
+/// The weight limit of an extrinsic: the estimate of a dry run increased by a margin, except where
+/// the gas limit or proof size are specified.
+///
+/// # Arguments
+///
+/// * `estimate` - the weight estimated by a dry run.
+/// * `margin` - the margin, as a percentage of the estimated weight.
+/// * `gas_limit` - the gas limit specified, if any.
+/// * `proof_size` - the proof size specified, if any.
+pub(crate) fn weight_limit(
+	estimate: Weight,
+	margin: u32,
+	gas_limit: Option<u64>,
+	proof_size: Option<u64>,
+) -> Weight {
+	let weight = apply_weight_margin(estimate, margin);
+	Weight::from_parts(
+		gas_limit.unwrap_or(weight.ref_time()),
+		proof_size.unwrap_or(weight.proof_size()),
+	)
+}
+
+/// Shows the estimated cost of submitting an extrinsic, then asks the user to confirm that it
+/// should be submitted.
+///
+/// # Arguments
+///
+/// * `weight_limit` - the weight limit of the extrinsic, if limited.
+/// * `storage_deposit` - the estimated storage deposit, if estimated by a dry run.
+/// * `fee` - the estimated fee.
+/// * `url` - the websocket endpoint of the chain.
+/// * `skip_confirm` - whether to submit the extrinsic without asking for confirmation.
+pub(crate) async fn confirm_cost(
+	weight_limit: Option<Weight>,
+	storage_deposit: Option<u128>,
+	fee: u128,
+	url: &url::Url,
+	skip_confirm: bool,
+) -> anyhow::Result<bool> {
+	let mut cost = Vec::new();
+	if let Some(weight_limit) = weight_limit {
+		cost.push(format!(
+			"Gas limit: ref_time {}, proof_size {}",
+			weight_limit.ref_time(),
+			weight_limit.proof_size()
+		));
+	}
+	if let Some(storage_deposit) = storage_deposit {
+		cost.push(format!(
+			"Estimated storage deposit: {}",
+			format_balance(storage_deposit, url).await?
+		));
+	}
+	cost.push(format!("Estimated fee: {}", format_balance(fee, url).await?));
+	log::info(cost.join("\n"))?;
+	if skip_confirm {
+		return Ok(true);
+	}
+	output::ensure_interactive("confirmation to submit the transaction (use `-y` to skip it)")?;
+	Ok(confirm("Do you want to submit the transaction?")
+		.initial_value(true)
+		.interact()?)
+}
+
+/// Reports each change in the status of a submitted extrinsic through a spinner, or as a line of
+/// JSON on stderr when outputting JSON, so that stdout remains the JSON document of the result.
+///
+/// # Arguments
+///
+/// * `spinner` - the spinner shown while the extrinsic is submitted.
+pub(crate) fn show_status(spinner: &ProgressBar) -> impl Fn(&TxEvent) + '_ {
+	move |event| match output::is_json() {
+		true => {
+			let _ = Term::stderr().write_line(&json!(event).to_string());
+		},
+		false => spinner.set_message(event.to_string()),
+	}
+}
+
+/// Formats the events raised by an extrinsic for display, listing the fields of the events
+/// emitted by contracts.
+///
+/// # Arguments
+///
+/// * `events` - the events raised by the extrinsic.
+pub(crate) fn display_events(events: &[ExtrinsicEvent]) -> String {
+	let mut display = String::from("Events");
+	for event in events {
+		display.push_str(&format!("\n  {} ➜ {}", event.pallet, event.name));
+		if event.is("Contracts", "ContractEmitted") {
+			for field in &event.fields {
+				display.push_str(&format!("\n      {}: {}", field.name, field.value));
+			}
+		}
+	}
+	display
+}
+
+/// Guides the user to select a message of the contract and provide its arguments.
+///
+/// Read-only messages are dry-run, whereas messages which mutate the state of the contract are
+/// submitted for on-chain execution.
+fn guide_user_to_call_contract(
+	command: &CallContractCommand,
+) -> anyhow::Result<CallContractCommand> {
+	output::ensure_interactive("the message to call (use `--message`)")?;
+	let messages = get_messages(&command.path)?;
+	let message = select_function("Select the message to call:", &messages)?;
+	let args = prompt_function_args(message, &command.args)?;
+	let value = match message.payable {
+		true => input("Value to transfer to the call:")
+			.placeholder("0")
+			.default_input("0")
+			.interact()?,
+		false => command.value.clone(),
+	};
+	Ok(CallContractCommand {
+		message: Some(message.label.clone()),
+		args,
+		value,
+		execute: message.mutates,
+		..command.clone()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{commands::call::CallCommands, Cli, Commands::Call};
+	use clap::Parser;
+	use pop_contracts::EventField;
+
+	#[test]
+	fn display_events_works() {
+		let event = |pallet: &str, name: &str, fields: Vec<EventField>| ExtrinsicEvent {
+			pallet: pallet.to_string(),
+			name: name.to_string(),
+			fields,
+		};
+		let field = |name: &str, value| EventField { name: name.to_string(), value };
+		let events = [
+			event("Contracts", "Called", vec![field("caller", json!("5Grw"))]),
+			event(
+				"Contracts",
+				"ContractEmitted",
+				vec![
+					field("contract", json!("5C4h")),
+					field("data", json!({ "Flipped": { "value": true } })),
+				],
+			),
+		];
+		assert_eq!(
+			display_events(&events),
+			"Events\n  Contracts ➜ Called\n  Contracts ➜ ContractEmitted\n      contract: \"5C4h\"\n      \
+			 data: {\"Flipped\":{\"value\":true}}"
+		);
+		assert_eq!(display_events(&[]), "Events");
+	}
+
+	#[test]
+	fn transaction_args_are_parsed() {
+		let parse = |args: &[&str]| {
+			let cli = Cli::parse_from(
+				["pop", "call", "contract", "--contract", "main", "--suri", "//Alice", "-x"]
+					.iter()
+					.chain(args),
+			);
+			let Call(args) = cli.command else { panic!("unable to parse command") };
+			let CallCommands::Contract(command) = args.command;
+			command.transaction.tx_opts()
+		};
+		assert_eq!(parse(&[]), TxOpts::default());
+		assert_eq!(
+			parse(&[
+				"--wait",
+				"finalized",
+				"--nonce",
+				"7",
+				"--tip",
+				"1000",
+				"--mortality",
+				"64",
+				"--finalization-timeout",
+				"30"
+			]),
+			TxOpts {
+				wait_for: WaitFor::Finalized,
+				nonce: Some(7),
+				tip: 1000,
+				mortality: Some(64),
+				finalization_timeout: Some(Duration::from_secs(30)),
+			}
+		);
+		assert!(Cli::try_parse_from([
+			"pop",
+			"call",
+			"contract",
+			"--contract",
+			"main",
+			"--suri",
+			"//Alice",
+			"--wait",
+			"included"
+		])
+		.is_err());
+	}
+
+	#[test]
+	fn weight_limit_works() {
+		let estimate = Weight::from_parts(1_000, 200);
+		assert_eq!(weight_limit(estimate, 0, None, None), estimate);
+		assert_eq!(weight_limit(estimate, 10, None, None), Weight::from_parts(1_100, 220));
+		// Any value specified is used as is.
+		assert_eq!(weight_limit(estimate, 10, Some(500), None), Weight::from_parts(500, 220));
+		assert_eq!(weight_limit(estimate, 10, None, Some(100)), Weight::from_parts(1_100, 100));
+	}
+}
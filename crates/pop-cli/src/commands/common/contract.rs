@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use cliclack::input;
+use pop_contracts::ContractFunction;
+use std::{net::TcpListener, process::Child};
+
+/// Prompts the user to select a contract function, showing its docs, mutability and whether it is
+/// payable.
+///
+/// # Arguments
+///
+/// * `prompt` - the prompt to display.
+/// * `functions` - the functions to select from.
+pub(crate) fn select_function<'a>(
+	prompt: &str,
+	functions: &'a [ContractFunction],
+) -> anyhow::Result<&'a ContractFunction> {
+	let mut select = cliclack::select(prompt.to_string());
+	for (i, function) in functions.iter().enumerate() {
+		if function.default || (i == 0 && !functions.iter().any(|f| f.default)) {
+			select = select.initial_value(i);
+		}
+		let mut flags = vec![if function.mutates { "mutates" } else { "read-only" }];
+		if function.payable {
+			flags.push("payable");
+		}
+		select = select.item(
+			i,
+			&function.label,
+			format!("{} [{}]", function.docs, flags.join(", ")).trim().to_string(),
+		);
+	}
+	Ok(&functions[select.interact()?])
+}
+
+/// Prompts the user for the value of each argument of a contract function, by its declared type,
+/// which was not already specified.
+///
+/// # Arguments
+///
+/// * `function` - the function whose arguments are prompted for.
+/// * `specified` - the values of the leading arguments, as specified on the command line.
+pub(crate) fn prompt_function_args(
+	function: &ContractFunction,
+	specified: &[String],
+) -> anyhow::Result<Vec<String>> {
+	let mut args = specified.to_vec();
+	for arg in function.args.iter().skip(specified.len()) {
+		args.push(
+			input(format!("Enter the value for `{}` ({}):", arg.label, arg.type_name))
+				.interact()?,
+		);
+	}
+	Ok(args)
+}
+
+/// A node started for the duration of a command, such as for tests, which is stopped when dropped
+/// so that it is torn down however the command finishes.
+pub(crate) struct TestNode(pub(crate) Child);
+
+impl Drop for TestNode {
+	fn drop(&mut self) {
+		let _ = self.0.kill();
+		let _ = self.0.wait();
+	}
+}
+
+/// Returns a port which is currently free, so that a node started by a command does not conflict
+/// with any node already running.
+pub(crate) fn free_port() -> anyhow::Result<u16> {
+	Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn free_port_works() -> anyhow::Result<()> {
+		let port = free_port()?;
+		assert!(TcpListener::bind(("127.0.0.1", port)).is_ok());
+		Ok(())
+	}
+}
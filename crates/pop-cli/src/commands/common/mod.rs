@@ -0,0 +1,4 @@
+// SPDX-License-Identifier: GPL-3.0
+
+#[cfg(feature = "contract")]
+pub(crate) mod contract;
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use console::style;
+use pop_contracts::{Deployments, DEPLOYMENTS_FILE};
+use std::path::PathBuf;
+
+use crate::{
+	output::{self, clear_screen},
+	style::Theme,
+};
+
+#[derive(Args)]
+pub struct ListContractsCommand {
+	/// Path to the contract project.
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// Only list the contracts deployed to the network at this websocket endpoint.
+	#[clap(name = "url", long, value_parser)]
+	url: Option<url::Url>,
+}
+
+impl ListContractsCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Listing deployed contracts", style(" Pop CLI ").black().on_magenta()))?;
+		set_theme(Theme);
+
+		let deployments = Deployments::load(&self.path)?;
+		// The networks deployed to, in the order they were first deployed to.
+		let mut networks = Vec::new();
+		for deployment in deployments.list() {
+			if !networks.contains(&&deployment.url) &&
+				(self.url.is_none() || self.url.as_ref() == Some(&deployment.url))
+			{
+				networks.push(&deployment.url);
+			}
+		}
+		for url in &networks {
+			let contracts: Vec<_> = deployments
+				.list()
+				.iter()
+				.filter(|d| &&d.url == url)
+				.map(|d| {
+					format!(
+						"{}{}: {}\n{}",
+						d.name,
+						d.alias.as_ref().map(|a| format!(" ({a})")).unwrap_or_default(),
+						d.address,
+						style(format!(
+							"{}({}) by {} in block {}",
+							d.constructor,
+							d.args.join(", "),
+							d.deployer,
+							match d.block_number {
+								Some(number) => format!("#{number} ({})", d.block_hash),
+								None => d.block_hash.clone(),
+							}
+						))
+						.dim()
+					)
+				})
+				.collect();
+			log::info(format!("{}\n{}", style(url).bold(), contracts.join("\n")))?;
+		}
+
+		output::set_result(serde_json::to_value(
+			deployments
+				.list()
+				.iter()
+				.filter(|d| networks.contains(&&d.url))
+				.collect::<Vec<_>>(),
+		)?)?;
+		if networks.is_empty() {
+			outro(format!("No deployments found in {DEPLOYMENTS_FILE}."))?;
+		} else {
+			outro(format!("Deployments recorded in {DEPLOYMENTS_FILE}."))?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod list;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct ContractsArgs {
+	#[command(subcommand)]
+	pub command: ContractsCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ContractsCommands {
+	/// List the contracts deployed from a contract project
+	#[clap(alias = "l")]
+	List(list::ListContractsCommand),
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{intro, log, outro};
+use pop_contracts::stop_contracts_nodes;
+use serde_json::json;
+
+use crate::{
+	output::{self, clear_screen},
+	style::style,
+};
+
+#[derive(Args)]
+pub struct DownContractsNodeCommand {
+	/// Only stop the node listening on this port.
+	#[clap(long)]
+	port: Option<u16>,
+}
+
+impl DownContractsNodeCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Stop contracts nodes", style(" Pop CLI ").black().on_magenta()))?;
+		let stopped = stop_contracts_nodes(&crate::cache()?, self.port)?;
+		for node in &stopped {
+			log::success(format!(
+				"Stopped the node with process ID {} listening at {}",
+				node.pid,
+				node.url()
+			))?;
+		}
+		output::set_result(
+			stopped
+				.iter()
+				.map(|node| json!({ "pid": node.pid, "url": node.url() }))
+				.collect(),
+		)?;
+		match stopped.len() {
+			0 => outro("No running contracts nodes found.")?,
+			count => outro(format!("{count} contracts node(s) stopped."))?,
+		}
+		Ok(())
+	}
+}
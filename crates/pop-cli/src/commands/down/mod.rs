@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod contracts_node;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct DownArgs {
+	#[command(subcommand)]
+	pub command: DownCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum DownCommands {
+	/// Stop the contracts nodes started in the background
+	#[clap(alias = "n")]
+	ContractsNode(contracts_node::DownContractsNodeCommand),
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use console::style;
+use pop_contracts::{query_events, subscribe_events, ContractEvent, EventFilter};
+use std::path::PathBuf;
+
+use crate::{
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::Theme,
+};
+
+#[derive(Args)]
+pub struct EventsContractCommand {
+	/// Path to the contract build folder, used to decode the events.
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// Only show the events emitted by this contract: its address, or the alias or name of a
+	/// contract deployed using `pop up contract`.
+	#[clap(name = "contract", long, env = "CONTRACT")]
+	contract: Option<String>,
+	/// Only show the events with this name.
+	#[clap(long)]
+	event: Option<String>,
+	/// Scan the blocks from this block number, instead of subscribing to new blocks.
+	#[clap(long)]
+	from: Option<u32>,
+	/// Scan the blocks up to and including this block number [default: latest block].
+	#[clap(long, requires = "from")]
+	to: Option<u32>,
+	/// Listen for events in new best blocks, which are reported sooner than in finalized blocks but
+	/// may be reverted.
+	#[clap(long, conflicts_with = "from")]
+	best: bool,
+	/// Websocket endpoint of a node.
+	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
+	url: url::Url,
+	/// Print each event as a line of JSON. Implied when subscribing with `--output json`, whereas
+	/// the events found by a scan are output as a single JSON document.
+	#[clap(long)]
+	json: bool,
+}
+
+impl EventsContractCommand {
+	/// Uses the endpoint configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = url;
+		}
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		if !self.json() {
+			clear_screen()?;
+			intro(format!("{}: Contract events", style(" Pop CLI ").black().on_magenta()))?;
+			set_theme(Theme);
+		}
+		let filter = EventFilter { contract: self.contract.clone(), event: self.event.clone() };
+
+		match self.from {
+			Some(from) => {
+				let events = query_events(&self.path, &self.url, from, self.to, &filter).await?;
+				if output::is_json() {
+					return output::set_result(serde_json::to_value(&events)?);
+				}
+				for event in &events {
+					self.print(event)?;
+				}
+				if !self.json {
+					outro(format!("{} events found.", events.len()))?;
+				}
+			},
+			None => {
+				if !self.json() {
+					let blocks = if self.best { "best" } else { "finalized" };
+					log::info(format!(
+						"Listening for events in {blocks} blocks on {}...",
+						self.url
+					))?;
+				}
+				subscribe_events(&self.path, &self.url, &filter, self.best, |event| {
+					self.print(&event)
+				})
+				.await?;
+			},
+		}
+		Ok(())
+	}
+
+	// Whether events are printed as lines of JSON.
+	fn json(&self) -> bool {
+		self.json || output::is_json()
+	}
+
+	// Prints an event, either as a line of JSON or human-readable.
+	fn print(&self, event: &ContractEvent) -> anyhow::Result<()> {
+		if self.json() {
+			output::stream(serde_json::to_value(event)?)?;
+		} else {
+			log::info(format_event(event))?;
+		}
+		Ok(())
+	}
+}
+
+// Formats an event to be human-readable.
+fn format_event(event: &ContractEvent) -> String {
+	let data = match &event.data {
+		serde_json::Value::Object(fields) => fields
+			.iter()
+			.map(|(name, value)| format!("{name}: {value}"))
+			.collect::<Vec<_>>()
+			.join(", "),
+		data => data.to_string(),
+	};
+	format!(
+		"{} {{ {} }}\n{}",
+		event.name.as_deref().unwrap_or("<unknown event>"),
+		data,
+		style(format!(
+			"emitted by {} in block #{} ({})",
+			event.contract, event.block_number, event.block_hash
+		))
+		.dim()
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		commands::events::{EventsArgs, EventsCommands},
+		Cli,
+		Commands::Events,
+	};
+	use clap::Parser;
+	use serde_json::json;
+
+	fn events_command(args: &[&str]) -> Result<EventsContractCommand, clap::Error> {
+		let cli = Cli::try_parse_from([&["pop", "events", "contract"], args].concat())?;
+		let Events(EventsArgs { command: EventsCommands::Contract(command) }) = cli.command else {
+			panic!("unable to parse command")
+		};
+		Ok(command)
+	}
+
+	#[test]
+	fn events_command_parses_filters_and_range() -> anyhow::Result<()> {
+		let command = events_command(&[
+			"--contract",
+			"flipper",
+			"--event",
+			"Flipped",
+			"--from",
+			"10",
+			"--to",
+			"20",
+			"--json",
+		])?;
+		assert_eq!(command.contract.as_deref(), Some("flipper"));
+		assert_eq!(command.event.as_deref(), Some("Flipped"));
+		assert_eq!((command.from, command.to), (Some(10), Some(20)));
+		assert!(command.json);
+		assert!(!command.best);
+		assert_eq!(command.url.as_str(), "ws://localhost:9944/");
+		// The end of a range requires its start.
+		assert!(events_command(&["--to", "20"]).is_err());
+		// Best blocks are only followed when subscribing.
+		assert!(events_command(&["--best"])?.best);
+		assert!(events_command(&["--best", "--from", "10"]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn format_event_works() {
+		let event = ContractEvent {
+			block_number: 7,
+			block_hash: "0x01".to_string(),
+			contract: "5Ca".to_string(),
+			name: Some("Flipped".to_string()),
+			data: json!({ "value": true }),
+		};
+		assert!(format_event(&event).starts_with("Flipped { value: true }\n"));
+		assert!(format_event(&event).contains("emitted by 5Ca in block #7 (0x01)"));
+		let event = ContractEvent { name: None, data: json!("0x2a000000"), ..event };
+		assert!(format_event(&event).starts_with("<unknown event> { \"0x2a000000\" }\n"));
+	}
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod contract;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct EventsArgs {
+	#[command(subcommand)]
+	pub command: EventsCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum EventsCommands {
+	/// Subscribe to or query the events emitted by smart contracts
+	#[clap(alias = "c")]
+	Contract(contract::EventsContractCommand),
+}
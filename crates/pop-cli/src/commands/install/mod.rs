@@ -1,8 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::style::{style, Theme};
+use crate::{
+	output::{self, clear_screen},
+	style::{style, Theme},
+};
 use anyhow::Context;
 use clap::Args;
-use cliclack::{clear_screen, confirm, intro, log, outro, set_theme};
+use cliclack::{confirm, intro, log, outro, set_theme};
 use duct::cmd;
 use os_info::Type;
 use strum::Display;
@@ -233,6 +236,7 @@ async fn install_redhat(skip_confirm: bool) -> anyhow::Result<()> {
 }
 
 fn prompt_for_confirmation(message: &str) -> anyhow::Result<()> {
+	output::ensure_interactive("confirmation to install the packages (use `-y` to skip it)")?;
 	if !confirm(format!(
 		"📦 Do you want to proceed with the installation of the following packages: {} ?",
 		message
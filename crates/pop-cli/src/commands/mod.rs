@@ -1,8 +1,66 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use anyhow::anyhow;
+use pop_common::Project;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "contract")]
+pub(crate) mod account;
 pub(crate) mod build;
 pub(crate) mod call;
+pub(crate) mod common;
+#[cfg(feature = "contract")]
+pub(crate) mod contracts;
+#[cfg(feature = "contract")]
+pub(crate) mod down;
+#[cfg(feature = "contract")]
+pub(crate) mod events;
 pub(crate) mod install;
 pub(crate) mod new;
+#[cfg(feature = "contract")]
+pub(crate) mod profile;
+#[cfg(feature = "contract")]
+pub(crate) mod run;
+#[cfg(feature = "contract")]
+pub(crate) mod status;
+#[cfg(feature = "contract")]
+pub(crate) mod storage;
 pub(crate) mod test;
 pub(crate) mod up;
+#[cfg(feature = "contract")]
+pub(crate) mod upgrade;
+
+/// Detects the kinds of project at a path from its manifest, for commands run without specifying
+/// whether the project is a contract or a parachain. Only the kinds of project supported by the
+/// features enabled are detected.
+///
+/// # Arguments
+///
+/// * `path` - the path to the project [default: current directory].
+pub(crate) fn detect_project(path: &Option<PathBuf>) -> anyhow::Result<Project> {
+	#[allow(unused_mut)]
+	let mut project = Project::detect(path.as_deref().unwrap_or(Path::new("./")))?;
+	#[cfg(not(feature = "contract"))]
+	project.contracts.clear();
+	#[cfg(not(feature = "parachain"))]
+	{
+		project.parachain = None;
+		project.parachain_packages.clear();
+		project.network = None;
+	}
+	Ok(project)
+}
+
+/// The error reported when no project supported by a command is found at a path.
+///
+/// # Arguments
+///
+/// * `path` - the path to the project [default: current directory].
+/// * `command` - the name of the command, e.g. `build`.
+pub(crate) fn project_not_found(path: &Option<PathBuf>, command: &str) -> anyhow::Error {
+	let path = path.as_deref().unwrap_or(Path::new("./"));
+	anyhow!(
+		"No contract or parachain was found at {}: run `pop {command} contract` or `pop {command} parachain` instead",
+		path.display()
+	)
+}
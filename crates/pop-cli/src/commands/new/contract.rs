@@ -1,37 +1,70 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use std::{env::current_dir, fs, path::PathBuf};
+use std::{env::current_dir, fs, path::PathBuf, str::FromStr};
 
-use clap::Args;
-use cliclack::{clear_screen, confirm, intro, outro, outro_cancel, set_theme};
+use clap::{
+	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
+	Args,
+};
+use cliclack::{confirm, input, intro, log::warning, outro, outro_cancel, set_theme};
 use console::style;
+use serde_json::json;
+use strum::VariantArray;
 
-use crate::style::Theme;
-use pop_contracts::create_smart_contract;
+use crate::{
+	output::{self, clear_screen},
+	style::Theme,
+};
+use pop_contracts::{create_smart_contract, ContractTemplate};
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 pub struct NewContractCommand {
-	#[arg(help = "Name of the contract")]
-	pub(crate) name: String,
+	#[arg(help = "Name of the contract. If empty assistance in the process will be provided.")]
+	pub(crate) name: Option<String>,
 	#[arg(short = 'p', long, help = "Path for the contract project, [default: current directory]")]
 	pub(crate) path: Option<PathBuf>,
+	#[arg(
+		short = 't',
+		long,
+		help = "Template to use.",
+		value_parser = crate::enum_variants!(ContractTemplate)
+	)]
+	pub(crate) template: Option<ContractTemplate>,
 }
 
 impl NewContractCommand {
-	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+	pub(crate) async fn execute(&self) -> anyhow::Result<ContractTemplate> {
 		clear_screen()?;
+		set_theme(Theme);
+
+		let contract_config = if self.name.is_none() {
+			// If user doesn't select the name guide them to generate a contract.
+			guide_user_to_generate_contract(self)?
+		} else {
+			self.clone()
+		};
+		let name = contract_config
+			.name
+			.clone()
+			.expect("name can not be none as fallback above is interactive input; qed");
+		let template = contract_config.template.clone().unwrap_or_default();
+
 		intro(format!(
-			"{}: Generating new contract \"{}\"!",
+			"{}: Generating new contract \"{}\" using the {} template!",
 			style(" Pop CLI ").black().on_magenta(),
-			&self.name,
+			&name,
+			template.name(),
 		))?;
-		set_theme(Theme);
-		let contract_path = if let Some(ref path) = self.path {
-			path.join(&self.name)
+		let contract_path = if let Some(ref path) = contract_config.path {
+			path.join(&name)
 		} else {
-			current_dir()?.join(&self.name)
+			current_dir()?.join(&name)
 		};
 		if contract_path.exists() {
+			output::ensure_interactive(&format!(
+				"confirmation to remove the existing \"{}\" directory",
+				contract_path.display()
+			))?;
 			if !confirm(format!(
 				"\"{}\" directory already exists. Would you like to remove it?",
 				contract_path.display()
@@ -42,34 +75,118 @@ impl NewContractCommand {
 					"Cannot generate contract until \"{}\" directory is removed.",
 					contract_path.display()
 				))?;
-				return Ok(());
+				return Ok(template);
 			}
 			fs::remove_dir_all(contract_path.as_path())?;
 		}
 		fs::create_dir_all(contract_path.as_path())?;
 		let spinner = cliclack::spinner();
 		spinner.start("Generating contract...");
-		create_smart_contract(&self.name, contract_path.as_path())?;
+		if let Err(e) = create_smart_contract(&name, contract_path.as_path(), &template) {
+			spinner.error("Failed to generate contract");
+			fs::remove_dir_all(contract_path.as_path())?;
+			return Err(e.into());
+		}
 
 		spinner.stop("Smart contract created!");
+		if let Some(repository) = template.repository_url() {
+			// warn about audit status and licensing
+			warning(format!("NOTE: the resulting contract is not guaranteed to be audited or reviewed for security vulnerabilities.\n{}",
+				style(format!("Please consult the source repository at {repository} to assess production suitability and licensing restrictions."))
+					.dim()))?;
+		}
+		output::set_result(json!({
+			"name": name,
+			"path": contract_path,
+			"template": template.to_string(),
+		}))?;
 		outro(format!("cd into \"{}\" and enjoy hacking! 🚀", contract_path.display()))?;
-		Ok(())
+		Ok(template)
 	}
 }
 
+fn guide_user_to_generate_contract(
+	command: &NewContractCommand,
+) -> anyhow::Result<NewContractCommand> {
+	output::ensure_interactive("the name of the contract")?;
+	intro(format!("{}: Generate a contract", style(" Pop CLI ").black().on_magenta()))?;
+	// Any template or path specified as arguments are used rather than prompting for them.
+	let template = match &command.template {
+		Some(template) => template.clone(),
+		None => display_select_options()?.clone(),
+	};
+	let name: String = input("What is the name of your contract?")
+		.placeholder("my_contract")
+		.default_input("my_contract")
+		.interact()?;
+	let path = match &command.path {
+		Some(path) => path.clone(),
+		None => {
+			let path: String = input("Where should your project be created?")
+				.placeholder("./")
+				.default_input("./")
+				.interact()?;
+			PathBuf::from(path)
+		},
+	};
+	clear_screen()?;
+	Ok(NewContractCommand { name: Some(name), path: Some(path), template: Some(template) })
+}
+
+fn display_select_options() -> anyhow::Result<&'static ContractTemplate> {
+	let mut prompt = cliclack::select("Select a template:".to_string());
+	for (i, template) in ContractTemplate::templates().iter().enumerate() {
+		if i == 0 {
+			prompt = prompt.initial_value(template);
+		}
+		prompt = prompt.item(template, template.name(), template.description());
+	}
+	Ok(prompt.interact()?)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::{
+		commands::new::{NewArgs, NewCommands::Contract},
+		Cli,
+		Commands::New,
+	};
 	use anyhow::Result;
+	use clap::Parser;
 
 	#[tokio::test]
 	async fn test_new_contract_command_execute_success() -> Result<()> {
 		let temp_contract_dir = tempfile::tempdir().expect("Could not create temp dir");
 		let command = NewContractCommand {
-			name: "test_contract".to_string(),
+			name: Some("test_contract".to_string()),
 			path: Some(PathBuf::from(temp_contract_dir.path())),
+			template: None,
+		};
+		command.execute().await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_new_contract_command_with_template_executes() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let cli = Cli::parse_from([
+			"pop",
+			"new",
+			"contract",
+			"my_dao",
+			"--path",
+			dir.path().to_str().unwrap(),
+			"--template",
+			"dao",
+		]);
+		let New(NewArgs { command: Contract(command) }) = cli.command else {
+			panic!("unable to parse command")
 		};
+		assert_eq!(command.template, Some(ContractTemplate::DAO));
 		command.execute().await?;
+		let contract = fs::read_to_string(dir.path().join("my_dao/lib.rs"))?;
+		assert!(contract.contains("pub struct MyDao {"));
 		Ok(())
 	}
 }
@@ -9,6 +9,22 @@ pub mod pallet;
 #[cfg(feature = "parachain")]
 pub mod parachain;
 
+#[macro_export]
+macro_rules! enum_variants {
+	($e: ty) => {{
+		PossibleValuesParser::new(
+			<$e>::VARIANTS
+				.iter()
+				.map(|p| PossibleValue::new(p.as_ref()))
+				.collect::<Vec<_>>(),
+		)
+		.try_map(|s| {
+			<$e>::from_str(&s)
+				.map_err(|_| format!("could not convert from {s} to {}", stringify!($e)))
+		})
+	}};
+}
+
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
 pub struct NewArgs {
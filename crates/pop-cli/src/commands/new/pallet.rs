@@ -1,9 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::style::Theme;
+use crate::{
+	output::{self, clear_screen},
+	style::Theme,
+};
 use clap::Args;
-use cliclack::{clear_screen, confirm, intro, outro, outro_cancel, set_theme};
+use cliclack::{confirm, intro, outro, outro_cancel, set_theme};
 use console::style;
 use pop_parachains::{create_pallet_template, resolve_pallet_path, TemplatePalletConfig};
+use serde_json::json;
 use std::fs;
 
 #[derive(Args)]
@@ -31,6 +35,10 @@ impl NewPalletCommand {
 		let pallet_name = self.name.clone();
 		let pallet_path = target.join(pallet_name.clone());
 		if pallet_path.exists() {
+			output::ensure_interactive(&format!(
+				"confirmation to remove the existing \"{}\" directory",
+				pallet_path.display()
+			))?;
 			if !confirm(format!(
 				"\"{}\" directory already exists. Would you like to remove it?",
 				pallet_path.display()
@@ -43,7 +51,7 @@ impl NewPalletCommand {
 				))?;
 				return Ok(());
 			}
-			fs::remove_dir_all(pallet_path)?;
+			fs::remove_dir_all(&pallet_path)?;
 		}
 		let spinner = cliclack::spinner();
 		spinner.start("Generating pallet...");
@@ -57,6 +65,7 @@ impl NewPalletCommand {
 		)?;
 
 		spinner.stop("Generation complete");
+		output::set_result(json!({ "name": self.name, "path": pallet_path }))?;
 		outro(format!("cd into \"{}\" and enjoy hacking! 🚀", &self.name))?;
 		Ok(())
 	}
@@ -1,5 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::style::{style, Theme};
+use crate::{
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::{style, Theme},
+};
 use anyhow::Result;
 use clap::{
 	builder::{PossibleValue, PossibleValuesParser, TypedValueParser},
@@ -8,7 +12,7 @@ use clap::{
 use std::{fs, path::Path, str::FromStr};
 
 use cliclack::{
-	clear_screen, confirm, input, intro,
+	confirm, input, intro,
 	log::{self, success, warning},
 	outro, outro_cancel, set_theme,
 };
@@ -16,6 +20,7 @@ use pop_parachains::{
 	instantiate_template_dir, is_initial_endowment_valid, Config, Git, GitHub, Provider, Release,
 	Template,
 };
+use serde_json::json;
 use strum::VariantArray;
 
 const DEFAULT_INITIAL_ENDOWMENT: &str = "1u64 << 60";
@@ -56,22 +61,14 @@ pub struct NewParachainCommand {
 	pub(crate) initial_endowment: Option<String>,
 }
 
-#[macro_export]
-macro_rules! enum_variants {
-	($e: ty) => {{
-		PossibleValuesParser::new(
-			<$e>::VARIANTS
-				.iter()
-				.map(|p| PossibleValue::new(p.as_ref()))
-				.collect::<Vec<_>>(),
-		)
-		.try_map(|s| {
-			<$e>::from_str(&s).map_err(|e| format!("could not convert from {s} to provider"))
-		})
-	}};
-}
-
 impl NewParachainCommand {
+	/// Uses the release of the template configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(release_tag) = config.get("release_tag", |s| &s.release_tag) {
+			self.release_tag = Some(release_tag);
+		}
+	}
+
 	pub(crate) async fn execute(&self) -> Result<Template> {
 		clear_screen()?;
 		set_theme(Theme);
@@ -104,11 +101,17 @@ impl NewParachainCommand {
 		let tag_version = parachain_config.release_tag.clone();
 
 		generate_parachain_from_template(name, provider, &template, tag_version, config)?;
+		output::set_result(json!({
+			"name": name,
+			"provider": provider.name(),
+			"template": template.name(),
+		}))?;
 		Ok(template)
 	}
 }
 
 async fn guide_user_to_generate_parachain() -> Result<NewParachainCommand> {
+	output::ensure_interactive("the name of the parachain")?;
 	intro(format!("{}: Generate a parachain", style(" Pop CLI ").black().on_magenta()))?;
 
 	let mut prompt = cliclack::select("Select a template provider: ".to_string());
@@ -265,6 +268,10 @@ fn get_customization_value(
 fn check_destination_path(name_template: &String) -> Result<&Path> {
 	let destination_path = Path::new(name_template);
 	if destination_path.exists() {
+		output::ensure_interactive(&format!(
+			"confirmation to remove the existing \"{}\" directory",
+			destination_path.display()
+		))?;
 		if !confirm(format!(
 			"\"{}\" directory already exists. Would you like to remove it?",
 			destination_path.display()
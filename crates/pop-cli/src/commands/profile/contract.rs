@@ -0,0 +1,353 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::anyhow;
+use clap::Args;
+use cliclack::{intro, log, outro, outro_cancel, ProgressBar};
+use pop_contracts::{
+	build_smart_contract, contracts_node_log, is_chain_alive, profile_contract,
+	profile_sandboxed_contract, run_contracts_node, ContractsNodeOpts, Profile, ProfileOpts,
+	Scheme, Status,
+};
+use std::{collections::BTreeMap, env::current_dir, path::PathBuf};
+
+use crate::{
+	commands::{
+		account::{account_password, scheme_parser},
+		build::contract::display_build,
+		common::contract::{free_port, TestNode},
+		up::contracts_node::source_contracts_node,
+	},
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::style,
+};
+
+#[derive(Args)]
+pub struct ProfileContractCommand {
+	/// Path to the contract build folder.
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// The name of the constructor used to deploy the contract.
+	#[clap(long, default_value = "new")]
+	constructor: String,
+	/// The constructor arguments, encoded as strings [default: example values of the types of the
+	/// arguments].
+	#[clap(long, num_args = 0..)]
+	args: Vec<String>,
+	/// Transfers an initial balance to the instantiated contract, in the smallest unit of the token
+	/// when profiling within a sandbox.
+	#[clap(long, default_value = "0")]
+	value: String,
+	/// Only profile this message. Can be specified multiple times [default: all messages].
+	#[clap(long = "message")]
+	messages: Vec<String>,
+	/// The arguments used to call a message, as the name of the message and a JSON array of the
+	/// arguments encoded as strings, e.g. `transfer=["5GrwvaEF...", "100"]`. Can be specified
+	/// multiple times [default: example values of the types of the arguments].
+	#[clap(long = "message-args", value_parser = parse_message_args)]
+	message_args: Vec<(String, Vec<String>)>,
+	/// Websocket endpoint of a node. A temporary local node is started if the chain is not live.
+	#[clap(long, value_parser, default_value = "ws://localhost:9944")]
+	url: url::Url,
+	/// Secret key URI for the account deploying and calling the contract, or the name of an
+	/// account added using `pop account add`.
+	#[clap(long, short, default_value = "//Alice")]
+	suri: String,
+	/// The cryptographic scheme of the account, when specified by a secret key URI.
+	#[arg(long, default_value = "sr25519", value_parser = scheme_parser())]
+	scheme: Scheme,
+	/// Profile the contract within a sandboxed `pallet-contracts` runtime, without a node.
+	#[clap(long, conflicts_with_all = ["url", "suri"])]
+	sandbox: bool,
+	/// Save the profile to this file, such as for use as a baseline by later runs.
+	#[clap(long)]
+	save: Option<PathBuf>,
+	/// Compare the profile with a baseline saved previously, failing if any cost increased by
+	/// more than the threshold or if a function which succeeded within the baseline now fails.
+	#[clap(long)]
+	baseline: Option<PathBuf>,
+	/// The percentage by which a cost may increase compared to the baseline.
+	#[clap(long, default_value_t = 5.0, requires = "baseline")]
+	threshold: f64,
+	/// Use the cached version of the contracts node without asking whether to update it when a
+	/// newer version is available.
+	#[clap(short('y'), long)]
+	skip_confirm: bool,
+}
+
+impl ProfileContractCommand {
+	/// Uses the endpoint and account configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = url;
+		}
+		if let Some(suri) = config.get("suri", |s| &s.suri) {
+			self.suri = suri;
+		}
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Profile a smart contract", style(" Pop CLI ").black().on_magenta()))?;
+
+		// Check if build exists in the specified "Contract build folder"
+		let build_path =
+			self.path.clone().unwrap_or_else(|| PathBuf::from("./")).join("target/ink");
+		if !build_path.exists() {
+			log::warning("NOTE: contract has not yet been built.")?;
+			let artifacts = build_smart_contract(&self.path, true)?;
+			log::success(display_build(&artifacts))?;
+		}
+		let baseline = match &self.baseline {
+			Some(path) => Some(Profile::load(path)?),
+			None => None,
+		};
+
+		// Profile against a temporary local node if the chain is not live, which is stopped once
+		// profiled.
+		let mut opts = self.profile_opts();
+		let _node = match self.sandbox || is_chain_alive(self.url.clone()).await? {
+			true => None,
+			false => {
+				let cache = crate::cache()?;
+				let project = match &self.path {
+					Some(path) => path.clone(),
+					None => current_dir()?,
+				};
+				let Some(binary) =
+					source_contracts_node(&cache, &project, None, self.skip_confirm).await?
+				else {
+					outro_cancel("🚫 A live chain or the contracts node is required to profile")?;
+					return Err(anyhow!("the contracts node could not be sourced"));
+				};
+				let port = free_port()?;
+				let node_opts = ContractsNodeOpts {
+					port,
+					dev: true,
+					temporary: true,
+					log: Some(contracts_node_log(&cache, port)),
+				};
+				let spinner = cliclack::spinner();
+				spinner.start("Starting a temporary local node...");
+				let process = run_contracts_node(&binary.path, &node_opts).await?;
+				spinner.stop(format!("Temporary local node listening at {}", node_opts.url()));
+				opts.url = node_opts.url();
+				Some(TestNode(process))
+			},
+		};
+
+		if !self.sandbox {
+			opts.password = account_password(&self.suri, self.scheme)?;
+		}
+		let spinner = cliclack::spinner();
+		spinner.start("Profiling the contract...");
+		let status = ProgressReporter(spinner.clone());
+		let profile = match self.sandbox {
+			true => profile_sandboxed_contract(&opts, &status).map_err(anyhow::Error::from),
+			false => profile_contract(&opts, &status).await,
+		};
+		let profile = match profile {
+			Ok(profile) => profile,
+			Err(e) => {
+				spinner.error(format!("🚫 {e}"));
+				outro_cancel("Unable to profile the contract.")?;
+				return Err(e);
+			},
+		};
+		let target = match self.sandbox {
+			true => "within a sandbox".to_string(),
+			false => format!("on {}", opts.url),
+		};
+		spinner.stop(format!("Profiled {} messages {target}", profile.messages.len()));
+		log::info(table(&profile))?;
+		for message in profile.messages.iter().filter(|m| m.error.is_some()) {
+			log::warning(format!(
+				"The dry run of `{}` failed: {}",
+				message.name,
+				message.error.as_deref().unwrap_or_default()
+			))?;
+		}
+
+		output::set_result(serde_json::to_value(&profile)?)?;
+		if let Some(path) = &self.save {
+			profile.save(path)?;
+			log::info(format!("Profile saved to {}", path.display()))?;
+		}
+		if let Some(baseline) = baseline {
+			let regressions = profile.regressions(&baseline, self.threshold);
+			if !regressions.is_empty() {
+				let lines: Vec<_> = regressions.iter().map(|r| r.to_string()).collect();
+				log::error(format!(
+					"Costs increased by more than {}%, or functions failed, compared to the \
+					 baseline:\n{}",
+					self.threshold,
+					lines.join("\n")
+				))?;
+				outro_cancel("🚫 Profile regressed")?;
+				return Err(anyhow!("{} regression(s) compared to the baseline", regressions.len()));
+			}
+			log::success(format!(
+				"No cost increased by more than {}% compared to the baseline",
+				self.threshold
+			))?;
+		}
+		outro("Profiling complete")?;
+		Ok(())
+	}
+
+	// The options for profiling the contract, as specified by the command arguments.
+	fn profile_opts(&self) -> ProfileOpts {
+		ProfileOpts {
+			path: self.path.clone(),
+			constructor: self.constructor.clone(),
+			args: self.args.clone(),
+			value: self.value.clone(),
+			messages: self.messages.clone(),
+			message_args: self.message_args.iter().cloned().collect::<BTreeMap<_, _>>(),
+			url: self.url.clone(),
+			suri: self.suri.clone(),
+			scheme: self.scheme,
+			password: None,
+		}
+	}
+}
+
+// Parses the arguments of a message, as the name of the message and a JSON array of its arguments.
+fn parse_message_args(s: &str) -> Result<(String, Vec<String>), String> {
+	let (message, args) = s
+		.split_once('=')
+		.ok_or_else(|| format!("expected `<message>=<arguments>`, found `{s}`"))?;
+	let args: Vec<serde_json::Value> = serde_json::from_str(args)
+		.map_err(|e| format!("expected a JSON array of arguments: {e}"))?;
+	let args = args
+		.into_iter()
+		.map(|arg| match arg {
+			serde_json::Value::String(arg) => arg,
+			arg => arg.to_string(),
+		})
+		.collect();
+	Ok((message.trim().to_string(), args))
+}
+
+// A table of the costs of the constructor and messages profiled.
+fn table(profile: &Profile) -> String {
+	const HEADERS: [&str; 4] = ["Function", "ref_time", "proof_size", "storage_deposit"];
+	let mut rows = vec![HEADERS.map(|h| h.to_string())];
+	for function in [&profile.constructor].into_iter().chain(&profile.messages) {
+		rows.push(match &function.error {
+			Some(_) => [function.name.clone(), "failed".into(), "-".into(), "-".into()],
+			None => [
+				function.name.clone(),
+				function.ref_time.to_string(),
+				function.proof_size.to_string(),
+				function.storage_deposit.to_string(),
+			],
+		});
+	}
+	let widths: Vec<_> = (0..HEADERS.len())
+		.map(|i| rows.iter().map(|r| r[i].len()).max().unwrap_or(0))
+		.collect();
+	rows.iter()
+		.map(|row| {
+			format!(
+				"{:w0$}  {:>w1$}  {:>w2$}  {:>w3$}",
+				row[0],
+				row[1],
+				row[2],
+				row[3],
+				w0 = widths[0],
+				w1 = widths[1],
+				w2 = widths[2],
+				w3 = widths[3]
+			)
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+struct ProgressReporter(ProgressBar);
+
+impl Status for ProgressReporter {
+	fn update(&self, status: &str) {
+		self.0.set_message(status)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{commands::profile::ProfileCommands, Cli, Commands::Profile as ProfileCommand};
+	use clap::Parser;
+	use pop_contracts::FunctionProfile;
+
+	#[test]
+	fn parse_message_args_works() {
+		assert_eq!(
+			parse_message_args(r#"transfer=["5GrwvaEF", 100]"#),
+			Ok(("transfer".to_string(), vec!["5GrwvaEF".to_string(), "100".to_string()]))
+		);
+		assert_eq!(parse_message_args("flip=[]"), Ok(("flip".to_string(), vec![])));
+		assert!(parse_message_args("flip").is_err());
+		assert!(parse_message_args("flip=true").is_err());
+	}
+
+	#[test]
+	fn profile_contract_command_works() {
+		let cli = Cli::parse_from([
+			"pop",
+			"profile",
+			"contract",
+			"--message",
+			"flip",
+			"--message-args",
+			"flip=[]",
+			"--baseline",
+			"baseline.json",
+			"--threshold",
+			"10",
+		]);
+		let ProfileCommand(args) = cli.command else { panic!("unable to parse command") };
+		let ProfileCommands::Contract(command) = args.command;
+		assert_eq!(command.threshold, 10.0);
+		let opts = command.profile_opts();
+		assert_eq!(opts.constructor, "new");
+		assert_eq!(opts.messages, vec!["flip"]);
+		assert_eq!(opts.message_args.get("flip"), Some(&vec![]));
+		assert_eq!(opts.url.as_str(), "ws://localhost:9944/");
+		assert!(!command.sandbox);
+
+		let cli = Cli::parse_from(["pop", "profile", "contract", "--sandbox"]);
+		let ProfileCommand(args) = cli.command else { panic!("unable to parse command") };
+		let ProfileCommands::Contract(command) = args.command;
+		assert!(command.sandbox);
+		// A sandbox is used instead of a node and account.
+		let parse = |args: &[&str]| {
+			Cli::try_parse_from([&["pop", "profile", "contract", "--sandbox"], args].concat())
+		};
+		assert!(parse(&["--url", "ws://localhost:9944"]).is_err());
+		assert!(parse(&["--suri", "//Bob"]).is_err());
+	}
+
+	#[test]
+	fn table_works() {
+		let function = |name: &str, ref_time, error: Option<&str>| FunctionProfile {
+			name: name.to_string(),
+			args: vec![],
+			ref_time,
+			proof_size: 10,
+			storage_deposit: 0,
+			error: error.map(|e| e.to_string()),
+		};
+		let profile = Profile {
+			constructor: function("new", 1000, None),
+			messages: vec![function("flip", 200, None), function("get", 0, Some("reverted"))],
+		};
+		assert_eq!(
+			table(&profile),
+			"Function  ref_time  proof_size  storage_deposit\n\
+			 new           1000          10                0\n\
+			 flip           200          10                0\n\
+			 get         failed           -                -"
+		);
+	}
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod contract;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct ProfileArgs {
+	#[command(subcommand)]
+	pub command: ProfileCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ProfileCommands {
+	/// Profile the gas and storage deposit required by the messages of a smart contract
+	#[clap(alias = "c")]
+	Contract(contract::ProfileContractCommand),
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	commands::account::unlock_scenario,
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::style,
+};
+use clap::Args;
+use cliclack::{intro, log, outro, spinner, ProgressBar};
+use pop_contracts::{Scenario, Status, StepStatus};
+use std::{fs, path::PathBuf};
+
+#[derive(Args)]
+pub(crate) struct RunArgs {
+	/// Path to the scenario file, declaring the contract deployments and calls to run in order.
+	#[arg(default_value = "scenario.toml")]
+	path: PathBuf,
+	/// Websocket endpoint of a node, instead of the endpoint specified by the scenario.
+	#[clap(name = "url", long, value_parser)]
+	url: Option<url::Url>,
+	/// Path of the file to write the JSON report of the run to.
+	#[clap(long, default_value = "scenario-report.json")]
+	report: PathBuf,
+}
+
+impl RunArgs {
+	/// Uses the endpoint configured by the project in place of that of the scenario, unless
+	/// specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = Some(url);
+		}
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!(
+			"{}: Running scenario {}",
+			style(" Pop CLI ").black().on_magenta(),
+			self.path.display()
+		))?;
+
+		let mut scenario = self.scenario()?;
+		unlock_scenario(&mut scenario)?;
+
+		let spinner = spinner();
+		let report = scenario.run(&ProgressReporter(spinner.clone())).await;
+		spinner.clear();
+		for step in &report.steps {
+			let summary = format!("{} ({})", step.name, step.action);
+			match step.status {
+				StepStatus::Passed => log::success(summary)?,
+				StepStatus::Failed =>
+					log::error(format!("{summary}: {}", step.error.as_deref().unwrap_or_default()))?,
+				StepStatus::Skipped => log::warning(format!("{summary}: skipped"))?,
+			}
+		}
+
+		fs::write(&self.report, serde_json::to_string_pretty(&report)?)?;
+		output::set_result(serde_json::to_value(&report)?)?;
+		if !report.success {
+			anyhow::bail!("Scenario failed. Report written to {}.", self.report.display());
+		}
+		outro(format!("Scenario passed. Report written to {}.", self.report.display()))?;
+		Ok(())
+	}
+
+	/// Loads the scenario, using the endpoint specified by the command arguments if any.
+	fn scenario(&self) -> anyhow::Result<Scenario> {
+		let mut scenario = Scenario::load(&self.path)?;
+		if let Some(url) = &self.url {
+			scenario.url = url.clone();
+		}
+		Ok(scenario)
+	}
+}
+
+/// Reports the step being run to a progress bar.
+struct ProgressReporter(ProgressBar);
+
+impl Status for ProgressReporter {
+	fn update(&self, status: &str) {
+		self.0.start(status)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Cli, Commands::Run};
+	use clap::Parser;
+
+	#[test]
+	fn run_command_loads_scenario() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("scenario.toml");
+		fs::write(
+			&path,
+			"[[step]]\nname = \"flipper\"\naction = \"deploy\"\npath = \"./flipper\"\n",
+		)?;
+		let cli =
+			Cli::parse_from(["pop", "run", path.to_str().unwrap(), "--url", "ws://node:9944"]);
+		let Run(command) = cli.command else { panic!("unable to parse command") };
+		assert_eq!(command.report, PathBuf::from("scenario-report.json"));
+		let scenario = command.scenario()?;
+		assert_eq!(scenario.url.as_str(), "ws://node:9944/");
+		assert_eq!(scenario.steps.len(), 1);
+		Ok(())
+	}
+}
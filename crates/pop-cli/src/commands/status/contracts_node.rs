@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{intro, log, outro};
+use pop_contracts::{contracts_nodes, is_chain_alive};
+use serde_json::json;
+
+use crate::{
+	output::{self, clear_screen},
+	style::style,
+};
+
+#[derive(Args)]
+pub struct StatusContractsNodeCommand {}
+
+impl StatusContractsNodeCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Contracts nodes", style(" Pop CLI ").black().on_magenta()))?;
+		let nodes = contracts_nodes(&crate::cache()?)?;
+		let mut result = Vec::new();
+		for node in &nodes {
+			let alive = is_chain_alive(node.url()).await?;
+			let status = match alive {
+				true => "ready",
+				false => "not responding",
+			};
+			result.push(json!({
+				"pid": node.pid,
+				"url": node.url(),
+				"ready": alive,
+				"log": node.log,
+			}));
+			let logs = node
+				.log
+				.as_ref()
+				.map(|log| format!("\nLogs: {}", log.display()))
+				.unwrap_or_default();
+			log::info(format!(
+				"{} ({status})\nProcess ID: {}{}",
+				node.url(),
+				node.pid,
+				style(logs).dim()
+			))?;
+		}
+		output::set_result(result.into())?;
+		match nodes.is_empty() {
+			true => outro("No contracts nodes running. Use `pop up contracts-node` to start one.")?,
+			false => outro(format!("{} contracts node(s) running.", nodes.len()))?,
+		}
+		Ok(())
+	}
+}
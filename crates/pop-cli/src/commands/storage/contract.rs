@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{intro, log, outro, set_theme};
+use console::style;
+use pop_contracts::{get_contract_storage, ContractStorage, StorageNode};
+use std::path::PathBuf;
+
+use crate::{
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::Theme,
+};
+
+#[derive(Args)]
+pub struct StorageContractCommand {
+	/// Path to the contract build folder, used to decode the storage.
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// The address of the contract, or the alias or name of a contract deployed using
+	/// `pop up contract`.
+	#[clap(name = "contract", long, env = "CONTRACT")]
+	contract: String,
+	/// Look up a key within this mapping, with the names of its parent fields separated by `.`.
+	#[clap(long, requires = "key")]
+	mapping: Option<String>,
+	/// The key to look up within the mapping, e.g. an address.
+	#[clap(long, requires = "mapping")]
+	key: Option<String>,
+	/// Websocket endpoint of a node.
+	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
+	url: url::Url,
+}
+
+impl StorageContractCommand {
+	/// Uses the endpoint configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = url;
+		}
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		if !output::is_json() {
+			clear_screen()?;
+			intro(format!("{}: Contract storage", style(" Pop CLI ").black().on_magenta()))?;
+			set_theme(Theme);
+		}
+		let storage = get_contract_storage(&self.path, &self.contract, &self.url).await?;
+
+		if let (Some(mapping), Some(key)) = (&self.mapping, &self.key) {
+			let value = storage.lookup(mapping, key)?;
+			if output::is_json() {
+				output::set_result(serde_json::to_value(value)?)?;
+			} else {
+				match value {
+					Some(value) => outro(format!("{mapping}[{key}]: {value}"))?,
+					None => outro(format!("No value stored within `{mapping}` for {key}."))?,
+				}
+			}
+			return Ok(());
+		}
+
+		if output::is_json() {
+			return output::set_result(serde_json::to_value(&storage)?);
+		}
+		log::info(format_storage(&storage))?;
+		if !storage.raw.is_empty() {
+			let cells: Vec<_> = storage
+				.raw
+				.iter()
+				.map(|cell| format!("{} => {}", cell.key, cell.value))
+				.collect();
+			log::warning(format!(
+				"{} storage cells could not be decoded:\n{}",
+				storage.raw.len(),
+				cells.join("\n")
+			))?;
+		}
+		outro(format!("Storage of {} read from {}", self.contract, self.url))?;
+		Ok(())
+	}
+}
+
+// Formats the storage of a contract as a tree.
+fn format_storage(storage: &ContractStorage) -> String {
+	let mut lines = Vec::new();
+	match &storage.root {
+		StorageNode::Struct { name, fields } => {
+			lines.push(style(name).bold().to_string());
+			format_fields(
+				fields.iter().map(|f| (f.name.clone(), &f.value)).collect(),
+				"",
+				&mut lines,
+			);
+		},
+		node => lines.push(format_node(node)),
+	}
+	lines.join("\n")
+}
+
+// Formats the children of a node, indented by the prefix.
+fn format_fields(fields: Vec<(String, &StorageNode)>, prefix: &str, lines: &mut Vec<String>) {
+	let count = fields.len();
+	for (i, (name, node)) in fields.into_iter().enumerate() {
+		let (branch, indent) = if i + 1 == count { ("└─", "   ") } else { ("├─", "│  ") };
+		lines.push(format!("{prefix}{branch} {name}: {}", format_node(node)));
+		let prefix = format!("{prefix}{indent}");
+		match node {
+			StorageNode::Struct { fields, .. } => format_fields(
+				fields.iter().map(|f| (f.name.clone(), &f.value)).collect(),
+				&prefix,
+				lines,
+			),
+			StorageNode::Array { values } => format_fields(
+				values.iter().enumerate().map(|(i, value)| (format!("[{i}]"), value)).collect(),
+				&prefix,
+				lines,
+			),
+			StorageNode::Mapping { entries, .. } =>
+				for (i, entry) in entries.iter().enumerate() {
+					let branch = if i + 1 == entries.len() { "└─" } else { "├─" };
+					lines.push(format!("{prefix}{branch} {} => {}", entry.key, entry.value));
+				},
+			StorageNode::Value { .. } | StorageNode::Undecoded => {},
+		}
+	}
+}
+
+// Formats a node, without its children.
+fn format_node(node: &StorageNode) -> String {
+	match node {
+		StorageNode::Struct { name, .. } => name.clone(),
+		StorageNode::Array { values } => format!("[{} values]", values.len()),
+		StorageNode::Value { value } => value.to_string(),
+		StorageNode::Mapping { root_key, entries } => {
+			format!("Mapping ({} entries, root key {root_key})", entries.len())
+		},
+		StorageNode::Undecoded => style("<undecoded>").dim().to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		commands::storage::{StorageArgs, StorageCommands},
+		Cli,
+		Commands::Storage,
+	};
+	use clap::Parser;
+	use pop_contracts::{MappingEntry, StorageField};
+	use serde_json::json;
+
+	fn storage_command(args: &[&str]) -> Result<StorageContractCommand, clap::Error> {
+		let cli = Cli::try_parse_from(
+			[&["pop", "storage", "contract", "--contract", "flipper"], args].concat(),
+		)?;
+		let Storage(StorageArgs { command: StorageCommands::Contract(command) }) = cli.command
+		else {
+			panic!("unable to parse command")
+		};
+		Ok(command)
+	}
+
+	#[test]
+	fn storage_command_parses_lookup() -> anyhow::Result<()> {
+		let command = storage_command(&["--mapping", "flips", "--key", "5Ca"])?;
+		assert_eq!(command.contract, "flipper");
+		assert_eq!(command.mapping.as_deref(), Some("flips"));
+		assert_eq!(command.key.as_deref(), Some("5Ca"));
+		// A key can only be looked up within a mapping.
+		assert!(storage_command(&["--key", "5Ca"]).is_err());
+		assert!(storage_command(&["--mapping", "flips"]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn format_storage_works() {
+		console::set_colors_enabled(false);
+		let field = |name: &str, value| StorageField { name: name.to_string(), value };
+		let storage = ContractStorage {
+			root: StorageNode::Struct {
+				name: "Testing".to_string(),
+				fields: vec![
+					field("value", StorageNode::Value { value: json!(true) }),
+					field(
+						"flips",
+						StorageNode::Mapping {
+							root_key: "0xced244be".to_string(),
+							entries: vec![MappingEntry { key: json!("5Ca"), value: json!(3) }],
+						},
+					),
+				],
+			},
+			raw: vec![],
+		};
+		assert_eq!(
+			format_storage(&storage),
+			"Testing\n├─ value: true\n└─ flips: Mapping (1 entries, root key 0xced244be)\n   └─ \"5Ca\" => 3"
+		);
+	}
+}
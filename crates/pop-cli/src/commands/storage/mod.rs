@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod contract;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct StorageArgs {
+	#[command(subcommand)]
+	pub command: StorageCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum StorageCommands {
+	/// Inspect the storage of a deployed smart contract
+	#[clap(alias = "c")]
+	Contract(contract::StorageContractCommand),
+}
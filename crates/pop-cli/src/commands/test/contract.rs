@@ -1,40 +1,236 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use std::path::PathBuf;
+use std::{convert::Infallible, env::current_dir, path::PathBuf, str::FromStr};
 
+use crate::output::clear_screen;
+use anyhow::anyhow;
 use clap::Args;
-use cliclack::{clear_screen, intro, outro};
-use pop_contracts::{test_e2e_smart_contract, test_smart_contract};
+use cliclack::{intro, log, outro, outro_cancel};
+use pop_contracts::{
+	contracts_node_log, is_chain_alive, run_contracts_node, test_e2e_smart_contract,
+	test_sandbox_smart_contract, test_smart_contract, ContractsNodeOpts, TestHandle,
+};
+use url::Url;
 
-use crate::style::style;
+use super::{Output, TestOptions};
+use crate::{
+	commands::{
+		common::contract::{free_port, TestNode},
+		up::contracts_node::source_contracts_node,
+	},
+	style::style,
+};
 
 #[derive(Args)]
 pub(crate) struct TestContractCommand {
 	#[arg(short = 'p', long, help = "Path for the contract project [default: current directory]")]
-	path: Option<PathBuf>,
+	pub(crate) path: Option<PathBuf>,
 	#[arg(short = 'f', long = "features", help = "Features for the contract project")]
-	features: Option<String>,
+	pub(crate) features: Option<String>,
+	/// The node to run the end-to-end tests against: the path to a `substrate-contracts-node`
+	/// binary to be started, or the websocket endpoint of a running chain [default: the
+	/// contracts node, sourced and started automatically].
+	#[arg(long, conflicts_with = "sandbox")]
+	pub(crate) node: Option<Node>,
+	/// Run only the end-to-end tests which select ink!'s sandboxed runtime backend, using
+	/// `#[ink_e2e::test(backend(runtime_only))]`, without a node: those using a node are skipped.
+	/// The contract must enable the `sandbox` feature of `ink_e2e`.
+	#[arg(long)]
+	pub(crate) sandbox: bool,
+	/// Use the cached version of the contracts node without asking whether to update it when a
+	/// newer version is available.
+	#[clap(short('y'), long)]
+	pub(crate) skip_confirm: bool,
+	#[command(flatten)]
+	pub(crate) options: TestOptions,
 }
 
 impl TestContractCommand {
-	pub(crate) fn execute(&self) -> anyhow::Result<&str> {
+	pub(crate) async fn execute(&self) -> anyhow::Result<&str> {
 		clear_screen()?;
 
-		if self.features.is_some() && self.features.clone().unwrap().contains("e2e-tests") {
+		if self.sandbox {
+			intro(format!(
+				"{}: Starting sandboxed tests",
+				style(" Pop CLI ").black().on_magenta()
+			))?;
+
+			let report = test_sandbox_smart_contract(
+				&self.path,
+				&self.options.opts(&self.features),
+				&Output,
+			)?;
+			self.options.report(&report)?;
+			outro("Sandboxed testing complete")?;
+			Ok("sandbox")
+		} else if self.features.is_some() && self.features.clone().unwrap().contains("e2e-tests") {
 			intro(format!(
 				"{}: Starting end-to-end tests",
 				style(" Pop CLI ").black().on_magenta()
 			))?;
 
-			test_e2e_smart_contract(&self.path)?;
+			let Some((binary, url, node)) = self.launch_node().await? else {
+				outro_cancel("🚫 The contracts node is required to run the end-to-end tests")?;
+				return Err(anyhow!("the contracts node could not be sourced"));
+			};
+			let path = self.path.clone();
+			let opts = self.options.opts(&self.features);
+			let handle = TestHandle::default();
+			let tests = tokio::task::spawn_blocking({
+				let handle = handle.clone();
+				move || {
+					test_e2e_smart_contract(
+						&path,
+						&opts,
+						binary.as_deref(),
+						Some(&url),
+						&Output,
+						&handle,
+					)
+				}
+			});
+			let report = tokio::select! {
+				result = tests => result??,
+				_ = tokio::signal::ctrl_c() => {
+					// Stop the tests and the node before the tests have finished.
+					handle.stop()?;
+					drop(node);
+					outro_cancel("🚫 End-to-end testing interrupted")?;
+					return Err(anyhow!("end-to-end testing was interrupted"));
+				},
+			};
+			// Stop the node before presenting the results.
+			drop(node);
+			self.options.report(&report)?;
 			outro("End-to-end testing complete")?;
 			Ok("e2e")
 		} else {
 			intro(format!("{}: Starting unit tests", style(" Pop CLI ").black().on_magenta()))?;
 
-			test_smart_contract(&self.path)?;
+			let report =
+				test_smart_contract(&self.path, &self.options.opts(&self.features), &Output)?;
+			self.options.report(&report)?;
 			outro("Unit testing complete")?;
 			Ok("unit")
 		}
 	}
+
+	// Launches the node against which the end-to-end tests are run, returning the path of its
+	// binary, its endpoint and the node started (if any), which is stopped when dropped. Returns
+	// `None` if the contracts node could not be sourced.
+	async fn launch_node(
+		&self,
+	) -> anyhow::Result<Option<(Option<PathBuf>, Url, Option<TestNode>)>> {
+		let binary = match &self.node {
+			Some(Node::Url(url)) => {
+				if !is_chain_alive(url.clone()).await? {
+					return Err(anyhow!("the chain at {url} is not reachable"));
+				}
+				log::info(format!("Running the end-to-end tests against {url}"))?;
+				return Ok(Some((None, url.clone(), None)));
+			},
+			Some(Node::Binary(path)) => path.clone(),
+			None => {
+				let cache = crate::cache()?;
+				let project = match &self.path {
+					Some(path) => path.clone(),
+					None => current_dir()?,
+				};
+				match source_contracts_node(&cache, &project, None, self.skip_confirm).await? {
+					Some(binary) => binary.path,
+					None => return Ok(None),
+				}
+			},
+		};
+
+		let port = free_port()?;
+		let opts = ContractsNodeOpts {
+			port,
+			dev: true,
+			temporary: true,
+			log: Some(contracts_node_log(&crate::cache()?, port)),
+		};
+		let spinner = cliclack::spinner();
+		spinner.start("Starting the contracts node...");
+		let process = run_contracts_node(&binary, &opts).await?;
+		spinner.stop(format!("Contracts node listening at {}", opts.url()));
+		Ok(Some((Some(binary), opts.url(), Some(TestNode(process)))))
+	}
+}
+
+/// The node against which end-to-end tests are run.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Node {
+	/// The path to a `substrate-contracts-node` binary, which is started for the tests.
+	Binary(PathBuf),
+	/// The websocket endpoint of a running chain.
+	Url(Url),
+}
+
+impl FromStr for Node {
+	type Err = Infallible;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match Url::parse(s) {
+			Ok(url) if ["ws", "wss", "http", "https"].contains(&url.scheme()) => Ok(Node::Url(url)),
+			_ => Ok(Node::Binary(PathBuf::from(s))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{commands::test::TestCommands, Cli, Commands::Test};
+	use clap::Parser;
+
+	#[test]
+	fn node_is_parsed() -> anyhow::Result<()> {
+		assert_eq!(
+			"ws://localhost:9944".parse::<Node>()?,
+			Node::Url(Url::parse("ws://localhost:9944")?)
+		);
+		assert_eq!(
+			"./substrate-contracts-node".parse::<Node>()?,
+			Node::Binary(PathBuf::from("./substrate-contracts-node"))
+		);
+		assert_eq!(
+			"/usr/bin/substrate-contracts-node".parse::<Node>()?,
+			Node::Binary(PathBuf::from("/usr/bin/substrate-contracts-node"))
+		);
+
+		let cli = Cli::parse_from([
+			"pop",
+			"test",
+			"contract",
+			"--features",
+			"e2e-tests",
+			"--node",
+			"wss://rpc.example.com",
+		]);
+		let Test(args) = cli.command else { panic!("unable to parse command") };
+		let Some(TestCommands::Contract(command)) = args.command else {
+			panic!("unable to parse command")
+		};
+		assert_eq!(command.node, Some(Node::Url(Url::parse("wss://rpc.example.com")?)));
+		assert!(!command.skip_confirm && !command.sandbox);
+
+		let cli = Cli::parse_from(["pop", "test", "contract", "--sandbox"]);
+		let Test(args) = cli.command else { panic!("unable to parse command") };
+		let Some(TestCommands::Contract(command)) = args.command else {
+			panic!("unable to parse command")
+		};
+		assert!(command.sandbox);
+		// A sandbox cannot be used alongside a node.
+		assert!(Cli::try_parse_from([
+			"pop",
+			"test",
+			"contract",
+			"--sandbox",
+			"--node",
+			"ws://localhost:9944"
+		])
+		.is_err());
+		Ok(())
+	}
 }
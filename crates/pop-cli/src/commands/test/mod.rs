@@ -1,21 +1,299 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
 use clap::{Args, Subcommand};
+use cliclack::{log, outro_cancel};
+use pop_common::{project, Status, TestOpts, TestReport};
+use serde_json::Value;
+
+use super::{detect_project, project_not_found};
+use crate::output;
 
 #[cfg(feature = "contract")]
 pub mod contract;
+#[cfg(feature = "parachain")]
+pub mod parachain;
 
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
 pub(crate) struct TestArgs {
 	#[command(subcommand)]
-	pub command: TestCommands,
+	pub command: Option<TestCommands>,
+	/// Directory path for your project, when testing without specifying whether it is a contract
+	/// or a parachain [default: current directory]
+	#[arg(short = 'p', long)]
+	pub(crate) path: Option<PathBuf>,
+	/// Features for the project.
+	#[arg(short = 'f', long)]
+	pub(crate) features: Option<String>,
+	#[command(flatten)]
+	pub(crate) options: TestOptions,
+}
+
+impl TestArgs {
+	/// Runs the unit tests of the project, as detected from its manifest: those of every contract
+	/// of a workspace, followed by those of the packages of its parachain, so that the contracts
+	/// are not tested again as members of the workspace. Each package is only tested with the
+	/// features specified which it declares, so that those of one kind of project, such as the
+	/// `e2e-tests` feature of a contract, are not enabled for another.
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		let project = detect_project(&self.path)?;
+		let features = parse_features(&self.features);
+		let mut declared = Vec::new();
+		// The features specified which the package at a path declares, qualified by the name of
+		// the package when specified.
+		let mut scope = |path: &Path, package: Option<&str>| -> anyhow::Result<Vec<String>> {
+			let available = project::features(path)?;
+			let scoped = features
+				.iter()
+				.filter(|f| available.contains(f))
+				.map(|f| match package {
+					Some(package) => format!("{package}/{f}"),
+					None => f.clone(),
+				})
+				.collect();
+			declared.extend(available);
+			Ok(scoped)
+		};
+		let contracts = project
+			.contracts
+			.into_iter()
+			.map(|path| scope(&path, None).map(|features| (join(features), path)))
+			.collect::<anyhow::Result<Vec<_>>>()?;
+		let parachain = match project.parachain {
+			Some(path) => {
+				let (mut packages, mut scoped) = (Vec::new(), Vec::new());
+				for package in &project.parachain_packages {
+					if let Some(name) = project::package_name(package)? {
+						scoped.extend(scope(package, Some(&name))?);
+						packages.push(name);
+					}
+				}
+				Some((join(scoped), packages, path))
+			},
+			None => None,
+		};
+		if contracts.is_empty() && parachain.is_none() {
+			return Err(project_not_found(&self.path, "test"));
+		}
+		if let Some(feature) = features.iter().find(|f| !declared.contains(f)) {
+			return Err(anyhow!("The `{feature}` feature is not declared by the project"));
+		}
+
+		let mut reports = Vec::new();
+		#[cfg(feature = "contract")]
+		for (features, path) in contracts {
+			let command = contract::TestContractCommand {
+				path: Some(path),
+				features,
+				node: None,
+				sandbox: false,
+				skip_confirm: false,
+				options: self.options.clone(),
+			};
+			command.execute().await?;
+			reports.extend(output::take_result());
+		}
+		#[cfg(feature = "parachain")]
+		if let Some((features, packages, path)) = parachain {
+			let command = parachain::TestParachainCommand {
+				path: Some(path),
+				features,
+				options: self.options.clone(),
+				packages,
+			};
+			command.execute()?;
+			reports.extend(output::take_result());
+		}
+		match reports.len() {
+			0 => Err(project_not_found(&self.path, "test")),
+			1 => output::set_result(reports.remove(0)),
+			_ => output::set_result(Value::Array(reports)),
+		}
+	}
 }
 
 #[derive(Subcommand)]
 pub(crate) enum TestCommands {
+	/// Test a parachain
+	#[cfg(feature = "parachain")]
+	#[clap(alias = "p")]
+	Parachain(parachain::TestParachainCommand),
 	/// Test a smart contract
 	#[cfg(feature = "contract")]
 	#[clap(alias = "c")]
 	Contract(contract::TestContractCommand),
 }
+
+/// Options for running the tests of a project and reporting their results.
+#[derive(Args, Clone, Debug, Default)]
+pub(crate) struct TestOptions {
+	/// Only run the tests whose names contain this filter.
+	#[arg(long)]
+	filter: Option<String>,
+	/// Show the output of the tests as they run, rather than capturing it.
+	#[arg(long)]
+	nocapture: bool,
+	/// Build and run the tests in release mode.
+	#[arg(long)]
+	release: bool,
+	/// Write a report of the results to this file: as JUnit XML if it ends in `.xml`, or as JSON
+	/// otherwise.
+	#[arg(long)]
+	report: Option<PathBuf>,
+}
+
+impl TestOptions {
+	/// The options for running the tests, with the specified comma-separated features enabled.
+	pub(crate) fn opts(&self, features: &Option<String>) -> TestOpts {
+		TestOpts {
+			packages: Vec::new(),
+			features: parse_features(features),
+			filter: self.filter.clone(),
+			skip: Vec::new(),
+			nocapture: self.nocapture,
+			release: self.release,
+		}
+	}
+
+	/// Presents a summary of the results of the tests, writing the report if requested. Fails if
+	/// any test failed.
+	pub(crate) fn report(&self, report: &TestReport) -> anyhow::Result<()> {
+		if !report.tests.is_empty() {
+			log::info(table(report))?;
+		}
+		if let Some(path) = &self.report {
+			report.write(path)?;
+			log::info(format!("Report written to {}", path.display()))?;
+		}
+		output::set_result(serde_json::from_str(&report.to_json()?)?)?;
+		if !report.success() {
+			outro_cancel(format!("🚫 {}", report.summary()))?;
+			return Err(anyhow!("{} test(s) failed", report.failed()));
+		}
+		log::success(report.summary())?;
+		Ok(())
+	}
+}
+
+/// Prints the output of the tests as it is produced, to stderr when outputting JSON so that stdout
+/// only carries the result.
+pub(crate) struct Output;
+
+impl Status for Output {
+	fn update(&self, line: &str) {
+		match output::is_json() {
+			true => eprintln!("{line}"),
+			false => println!("{line}"),
+		}
+	}
+}
+
+// The features as a comma-separated list, if any.
+fn join(features: Vec<String>) -> Option<String> {
+	(!features.is_empty()).then(|| features.join(","))
+}
+
+// The features specified as a comma-separated list.
+fn parse_features(features: &Option<String>) -> Vec<String> {
+	features
+		.iter()
+		.flat_map(|f| f.split(','))
+		.map(|f| f.trim().to_string())
+		.filter(|f| !f.is_empty())
+		.collect()
+}
+
+// A table of the results of the tests, with a row for each test.
+fn table(report: &TestReport) -> String {
+	const HEADERS: [&str; 3] = ["Test", "Status", "Duration"];
+	let width = report
+		.tests
+		.iter()
+		.map(|t| t.name.len())
+		.max()
+		.unwrap_or(0)
+		.max(HEADERS[0].len());
+	let mut rows = vec![format!("{:width$}  {:8}  {}", HEADERS[0], HEADERS[1], HEADERS[2])];
+	for test in &report.tests {
+		rows.push(format!(
+			"{:width$}  {:8}  {:.3}s",
+			test.name,
+			test.status.as_ref(),
+			test.duration.as_secs_f64()
+		));
+	}
+	rows.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pop_common::{TestResult, TestStatus};
+	use std::time::Duration;
+
+	#[test]
+	fn opts_works() {
+		let options = TestOptions {
+			filter: Some("flip".to_string()),
+			nocapture: true,
+			release: false,
+			report: None,
+		};
+		assert_eq!(
+			options.opts(&Some("e2e-tests, std".to_string())),
+			TestOpts {
+				packages: Vec::new(),
+				features: vec!["e2e-tests".to_string(), "std".to_string()],
+				filter: Some("flip".to_string()),
+				skip: Vec::new(),
+				nocapture: true,
+				release: false,
+			}
+		);
+		assert!(options.opts(&None).features.is_empty());
+	}
+
+	#[tokio::test]
+	async fn execute_rejects_undeclared_features() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		std::fs::write(
+			path.join("Cargo.toml"),
+			"[package]\nname = \"flipper\"\n\n[dependencies]\nink = \"5.0.0\"\n\n[features]\ne2e-tests = []\n",
+		)?;
+		let args = TestArgs {
+			command: None,
+			path: Some(path.to_path_buf()),
+			features: Some("e2e-tests,runtime-benchmarks".to_string()),
+			options: TestOptions::default(),
+		};
+		assert_eq!(
+			args.execute().await.unwrap_err().to_string(),
+			"The `runtime-benchmarks` feature is not declared by the project"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn table_works() {
+		let test = |name: &str, status| TestResult {
+			suite: "unittests lib.rs".to_string(),
+			name: name.to_string(),
+			status,
+			duration: Duration::from_millis(12),
+			output: None,
+		};
+		let report = TestReport {
+			tests: vec![test("tests::it_works", TestStatus::Passed), test("a", TestStatus::Failed)],
+		};
+		assert_eq!(
+			table(&report),
+			"Test             Status    Duration\n\
+			 tests::it_works  passed    0.012s\n\
+			 a                failed    0.012s"
+		);
+	}
+}
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use std::path::PathBuf;
+
+use clap::Args;
+use cliclack::{intro, log::warning, outro};
+use pop_common::TestOpts;
+use pop_parachains::test_parachain;
+
+use super::{Output, TestOptions};
+use crate::{output::clear_screen, style::style};
+
+#[derive(Args)]
+pub(crate) struct TestParachainCommand {
+	#[arg(
+		short = 'p',
+		long = "path",
+		help = "Directory path for your project, [default: current directory]"
+	)]
+	pub(crate) path: Option<PathBuf>,
+	#[arg(short = 'f', long = "features", help = "Features for the parachain project")]
+	pub(crate) features: Option<String>,
+	#[command(flatten)]
+	pub(crate) options: TestOptions,
+	/// The packages to test, when testing the parachain of a workspace which also has contracts.
+	#[arg(skip)]
+	pub(crate) packages: Vec<String>,
+}
+
+impl TestParachainCommand {
+	pub(crate) fn execute(&self) -> anyhow::Result<&str> {
+		clear_screen()?;
+		intro(format!("{}: Starting parachain tests", style(" Pop CLI ").black().on_magenta()))?;
+
+		warning("NOTE: this may take some time...")?;
+		let opts =
+			TestOpts { packages: self.packages.clone(), ..self.options.opts(&self.features) };
+		let report = test_parachain(&self.path, &opts, &Output)?;
+		self.options.report(&report)?;
+		outro("Parachain testing complete")?;
+		Ok("parachain")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{commands::test::TestCommands, Cli, Commands::Test};
+	use clap::Parser;
+
+	#[test]
+	fn test_parachain_command_works() {
+		let cli = Cli::parse_from([
+			"pop",
+			"test",
+			"parachain",
+			"-p",
+			"./my-parachain",
+			"--filter",
+			"pallet",
+			"--release",
+			"--report",
+			"report.xml",
+		]);
+		let Test(args) = cli.command else { panic!("unable to parse command") };
+		let Some(TestCommands::Parachain(command)) = args.command else {
+			panic!("unable to parse command")
+		};
+		assert_eq!(command.path, Some(PathBuf::from("./my-parachain")));
+		assert_eq!(command.options.report, Some(PathBuf::from("report.xml")));
+		let opts = command.options.opts(&command.features);
+		assert_eq!(opts.filter.as_deref(), Some("pallet"));
+		assert!(opts.release && !opts.nocapture);
+	}
+}
@@ -1,26 +1,49 @@
 // SPDX-License-Identifier: GPL-3.0
 
 use anyhow::anyhow;
-use clap::Args;
-use cliclack::{clear_screen, confirm, intro, log, outro, outro_cancel};
+use clap::{Args, FromArgMatches};
+use cliclack::{confirm, input, intro, log, outro, outro_cancel, ProgressBar};
 use pop_contracts::{
-	build_smart_contract, dry_run_gas_estimate_instantiate, instantiate_smart_contract,
-	is_chain_alive, parse_hex_bytes, run_contracts_node, set_up_deployment, UpOpts,
+	build_smart_contract, built_code_hash, contracts_node_log, dry_run_estimate_instantiate,
+	dry_run_upload, estimate_instantiate_fee, get_constructors, instantiate_smart_contract,
+	is_chain_alive, parse_code_hash, parse_hex_bytes, record_deployment, set_up_deployment,
+	set_up_upload, start_contracts_node, upload_smart_contract, wait_for_changes, ContractInfo,
+	ContractsNodeOpts, Scenario, Scheme, SourceSnapshot, Status, StepStatus, UpOpts,
+	DEFAULT_POLL_INTERVAL, DEFAULT_PORT, DEPLOYMENTS_FILE,
 };
+use serde_json::json;
 use sp_core::Bytes;
 use sp_weights::Weight;
-use std::path::PathBuf;
+use std::{
+	env::current_dir,
+	ffi::OsStr,
+	path::{Path, PathBuf},
+};
 
-use crate::style::style;
+use super::contracts_node::source_contracts_node;
+use crate::{
+	commands::{
+		account::{account_password, scheme_parser, unlock_scenario},
+		build::contract::display_build,
+		call::contract::{
+			confirm_cost, show_status, weight_limit, TransactionArgs, DEFAULT_WEIGHT_MARGIN,
+		},
+		common::contract::{prompt_function_args, select_function},
+	},
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::style,
+};
 
 #[derive(Args)]
 pub struct UpContractCommand {
 	/// Path to the contract build folder.
 	#[arg(short = 'p', long)]
 	path: Option<PathBuf>,
-	/// The name of the contract constructor to call.
-	#[clap(name = "constructor", long, default_value = "new")]
-	constructor: String,
+	/// The name of the contract constructor to call. If empty, the constructors of the contract
+	/// will be listed to select from, which requires running interactively.
+	#[clap(name = "constructor", long)]
+	constructor: Option<String>,
 	/// The constructor arguments, encoded as strings.
 	#[clap(long, num_args = 0..)]
 	args: Vec<String>,
@@ -40,21 +63,86 @@ pub struct UpContractCommand {
 	/// instances of the same contract code from the same account.
 	#[clap(long, value_parser = parse_hex_bytes)]
 	salt: Option<Bytes>,
+	/// Maximum storage deposit which may be charged for the instantiation, e.g. `1000` or
+	/// `1.5UNIT`. If not specified, the instantiation may use all of the free balance of the
+	/// account.
+	#[clap(long)]
+	storage_deposit_limit: Option<String>,
+	/// The percentage by which the gas and proof size estimated by a dry run are increased, so
+	/// that the instantiation does not run out of gas when the state of the chain changes before
+	/// it is included in a block.
+	#[clap(long, default_value_t = DEFAULT_WEIGHT_MARGIN)]
+	weight_margin: u32,
 	/// Websocket endpoint of a node.
 	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
 	url: url::Url,
-	/// Secret key URI for the account deploying the contract.
+	/// Secret key URI for the account deploying the contract, or the name of an account added
+	/// using `pop account add`.
 	///
 	/// e.g.
 	/// - for a dev account "//Alice"
 	/// - with a password "//Alice///SECRET_PASSWORD"
+	/// - read from an environment variable "env:VAR"
+	/// - read from a file "file:path"
 	#[clap(name = "suri", long, short, default_value = "//Alice")]
 	suri: String,
-	/// Before start a local node, do not ask the user for confirmation.
+	/// The cryptographic scheme of the account, when specified by a secret key URI. Accounts added
+	/// using `pop account add` use the scheme they were added with.
+	#[arg(long, default_value = "sr25519", value_parser = scheme_parser())]
+	scheme: Scheme,
+	/// Do not ask the user for confirmation, before starting a local node or submitting the
+	/// deployment.
 	#[clap(short('y'), long)]
 	skip_confirm: bool,
+	/// Uploads the contract code to the chain without instantiating it, reporting the resulting
+	/// code hash.
+	#[clap(long, conflicts_with = "code_hash")]
+	upload_only: bool,
+	/// Instantiates the contract from the code hash of contract code already uploaded to the
+	/// chain, rather than uploading the contract code.
+	#[clap(long, value_parser = parse_code_hash)]
+	code_hash: Option<[u8; 32]>,
+	/// An alias for the deployed contract, which can be used to refer to it instead of its
+	/// address, e.g. `pop call contract --contract <alias>`.
+	#[clap(long, conflicts_with = "upload_only")]
+	alias: Option<String>,
+	/// Watches the contract for changes to its source files, rebuilding and redeploying it on
+	/// each change.
+	#[clap(long, conflicts_with = "upload_only")]
+	watch: bool,
+	/// A scenario file of calls replayed after each deployment while watching, e.g. to set up
+	/// state. Steps can call the latest deployment by the alias or name of the contract.
+	#[clap(long, requires = "watch")]
+	setup: Option<PathBuf>,
+	#[command(flatten)]
+	transaction: TransactionArgs,
 }
 impl UpContractCommand {
+	/// The command deploying the contract at a path, using the default values of its other
+	/// arguments.
+	///
+	/// # Arguments
+	///
+	/// * `path` - the path to the contract.
+	pub(crate) fn from_path(path: &Path) -> anyhow::Result<Self> {
+		let matches = Self::augment_args(clap::Command::new("contract")).try_get_matches_from([
+			OsStr::new("contract"),
+			OsStr::new("--path"),
+			path.as_os_str(),
+		])?;
+		Ok(Self::from_arg_matches(&matches)?)
+	}
+
+	/// Uses the endpoint and account configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = url;
+		}
+		if let Some(suri) = config.get("suri", |s| &s.suri) {
+			self.suri = suri;
+		}
+	}
+
 	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
 		clear_screen()?;
 
@@ -67,12 +155,15 @@ impl UpContractCommand {
 			log::warning(format!("NOTE: contract has not yet been built."))?;
 			intro(format!("{}: Building a contract", style(" Pop CLI ").black().on_magenta()))?;
 			// Build the contract in release mode
-			let result = build_smart_contract(&self.path, true)?;
-			log::success(result.to_string())?;
+			let artifacts = build_smart_contract(&self.path, true)?;
+			log::success(display_build(&artifacts))?;
 		}
 
 		if !is_chain_alive(self.url.clone()).await? {
 			if !self.skip_confirm {
+				output::ensure_interactive(
+					"confirmation to start a local node (use `-y` to skip it)",
+				)?;
 				if !confirm(format!(
 				"The chain \"{}\" is not live. Would you like pop to start a local node in the background for testing?",
 				self.url.to_string()
@@ -83,57 +174,374 @@ impl UpContractCommand {
 				return Ok(());
 			}
 			}
-			let process = run_contracts_node(crate::cache()?).await?;
-			log::success("Local node started successfully in the background.")?;
-			log::warning(format!("NOTE: The contracts node is running in the background with process ID {}. Please close it manually when done testing.", process.id()))?;
+			let cache = crate::cache()?;
+			let project = match &self.path {
+				Some(path) => path.clone(),
+				None => current_dir()?,
+			};
+			let Some(binary) =
+				source_contracts_node(&cache, &project, None, self.skip_confirm).await?
+			else {
+				return Ok(());
+			};
+			let port = self.url.port().unwrap_or(DEFAULT_PORT);
+			let opts = ContractsNodeOpts {
+				port,
+				log: Some(contracts_node_log(&cache, port)),
+				..Default::default()
+			};
+			let spinner = cliclack::spinner();
+			spinner.start("Starting a local node...");
+			let node = start_contracts_node(&cache, &binary.path, &opts).await?;
+			spinner.stop("Local node started successfully in the background.");
+			log::warning(format!("NOTE: The contracts node is running in the background with process ID {}. Use `pop down contracts-node` to stop it when done testing.", node.pid))?;
 		}
 
 		// if build exists then proceed
 		intro(format!("{}: Deploy a smart contract", style(" Pop CLI ").black().on_magenta()))?;
 
-		println!("{}: Deploying a smart contract", style(" Pop CLI ").black().on_magenta());
+		let password = account_password(&self.suri, self.scheme)?;
+		if self.upload_only {
+			return self.upload(password).await;
+		}
+
+		if !output::is_json() {
+			println!("{}: Deploying a smart contract", style(" Pop CLI ").black().on_magenta());
+		}
+
+		let mut up_opts = self.up_opts(password);
+		if self.constructor.is_none() {
+			// If the user doesn't specify a constructor, guide them to select one from the
+			// contract.
+			output::ensure_interactive("the constructor (use `--constructor` to specify it)")?;
+			let constructors = get_constructors(&self.path)?;
+			let constructor = select_function("Select the constructor to call:", &constructors)?;
+			up_opts.constructor = constructor.label.clone();
+			up_opts.args = prompt_function_args(constructor, &self.args)?;
+			if constructor.payable {
+				up_opts.value = input("Value to transfer to the contract:")
+					.placeholder(&self.value)
+					.default_input(&self.value)
+					.interact()?;
+			}
+		}
+
+		if self.watch {
+			return self.watch(up_opts).await;
+		}
+		let Some(contract) = self.deploy(&up_opts, self.skip_confirm).await? else {
+			outro_cancel("Deployment cancelled.")?;
+			return Ok(());
+		};
+		self.record(&up_opts, &contract)?;
+		output::set_result(serde_json::to_value(&contract)?)?;
+		outro("Deployment complete")?;
+		Ok(())
+	}
+
+	/// Deploys the contract, returning `None` if the deployment was cancelled.
+	///
+	/// # Arguments
+	///
+	/// * `up_opts` - attributes for the deployment.
+	/// * `skip_confirm` - whether to submit the deployment without confirming its cost.
+	async fn deploy(
+		&self,
+		up_opts: &UpOpts,
+		skip_confirm: bool,
+	) -> anyhow::Result<Option<ContractInfo>> {
+		let instantiate_exec = set_up_deployment(up_opts.clone()).await?;
+
+		let spinner = cliclack::spinner();
+		spinner.start("Doing a dry run to estimate the cost...");
+		let (weight_limit, storage_deposit) = match (self.gas_limit, self.proof_size) {
+			(Some(gas_limit), Some(proof_size)) =>
+				(Weight::from_parts(gas_limit, proof_size), None),
+			_ => match dry_run_estimate_instantiate(&instantiate_exec).await {
+				Ok((weight, storage_deposit)) => (
+					weight_limit(weight, self.weight_margin, self.gas_limit, self.proof_size),
+					Some(storage_deposit),
+				),
+				Err(e) => {
+					spinner.error("Deployment failed.");
+					return Err(e);
+				},
+			},
+		};
+		let fee = match estimate_instantiate_fee(&instantiate_exec, weight_limit).await {
+			Ok(fee) => fee,
+			Err(e) => {
+				spinner.error("Deployment failed.");
+				return Err(e);
+			},
+		};
+		spinner.clear();
+		if !confirm_cost(Some(weight_limit), storage_deposit, fee, &self.url, skip_confirm).await? {
+			return Ok(None);
+		}
+		let spinner = cliclack::spinner();
+		spinner.start(match self.code_hash {
+			Some(_) => "Instantiating the contract...",
+			None => "Uploading and instantiating the contract...",
+		});
+		let contract = instantiate_smart_contract(
+			instantiate_exec,
+			weight_limit,
+			&self.transaction.tx_opts(),
+			show_status(&spinner),
+		)
+		.await
+		.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+		spinner.stop(format!(
+			"Contract deployed and instantiated: The Contract Address is {:?}",
+			contract.address
+		));
+		Ok(Some(contract))
+	}
+
+	/// Records the deployment of the contract within the contract project.
+	fn record(&self, up_opts: &UpOpts, contract: &ContractInfo) -> anyhow::Result<()> {
+		match record_deployment(up_opts, contract, self.alias.clone()) {
+			Ok(deployment) => log::info(format!(
+				"Deployment recorded in {DEPLOYMENTS_FILE}. Call it using `--contract {}`.",
+				deployment.alias.unwrap_or(deployment.name)
+			))?,
+			Err(e) => log::warning(format!("Failed to record the deployment: {e}"))?,
+		}
+		Ok(())
+	}
+
+	/// Deploys the contract, then rebuilds and redeploys it whenever its source files change,
+	/// replaying any setup calls after each deployment. Failures are reported without ending the
+	/// watch, which continues until interrupted.
+	///
+	/// # Arguments
+	///
+	/// * `up_opts` - attributes for the deployments.
+	async fn watch(&self, up_opts: UpOpts) -> anyhow::Result<()> {
+		let mut setup = self.setup()?;
+		if let Some(setup) = &mut setup {
+			unlock_scenario(setup)?;
+		}
+		let project = self.path.clone().unwrap_or_else(|| PathBuf::from("./"));
+		let mut snapshot = SourceSnapshot::take(&self.path)?;
+		// The cost of the first deployment is confirmed, unless skipped, after which the contract
+		// is redeployed without confirmation.
+		let contract = match self.deploy(&up_opts, self.skip_confirm).await {
+			Ok(Some(contract)) => contract,
+			Ok(None) => {
+				outro_cancel("Deployment cancelled.")?;
+				return Ok(());
+			},
+			Err(e) => {
+				outro_cancel(format!("Deployment failed: {e}"))?;
+				return Ok(());
+			},
+		};
+		let mut code_hash = contract.code_hash.clone();
+		self.record(&up_opts, &contract)?;
+		self.replay(&setup).await?;
+		loop {
+			log::step(format!(
+				"Watching {} for changes. Press Ctrl+C to stop.",
+				project.display()
+			))?;
+			let changes =
+				match wait_for_changes(&self.path, &mut snapshot, DEFAULT_POLL_INTERVAL).await {
+					Ok(changes) => changes,
+					Err(e) => {
+						log::warning(format!("Failed to check the source files for changes: {e}"))?;
+						tokio::time::sleep(DEFAULT_POLL_INTERVAL).await;
+						continue;
+					},
+				};
+			log::info(format!(
+				"Changed: {}",
+				changes.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+			))?;
 
-		let instantiate_exec = set_up_deployment(UpOpts {
+			let spinner = cliclack::spinner();
+			spinner.start("Building the contract...");
+			// The contract is built on a blocking thread, so that the runtime is not blocked.
+			let path = self.path.clone();
+			let build = tokio::task::spawn_blocking(move || build_smart_contract(&path, true))
+				.await
+				.map_err(anyhow::Error::from)
+				.and_then(|build| build);
+			if let Err(e) = build {
+				spinner.error("Build failed.");
+				log::error(format!("{e:?}"))?;
+				continue;
+			}
+			spinner.stop("Contract built.");
+			match built_code_hash(&self.path) {
+				Ok(hash) if Some(&hash) == code_hash.as_ref() => {
+					log::info("The contract code is unchanged, so it was not redeployed.")?;
+					continue;
+				},
+				Ok(_) => {},
+				Err(e) => log::warning(format!("Failed to read the code hash: {e}"))?,
+			}
+			match self.deploy(&up_opts, true).await {
+				Ok(Some(contract)) => {
+					code_hash = contract.code_hash.clone();
+					self.record(&up_opts, &contract)?;
+					self.replay(&setup).await?;
+				},
+				Ok(None) => {},
+				Err(e) => log::error(format!("Deployment failed: {e}"))?,
+			}
+		}
+	}
+
+	/// Loads the setup calls of the watch, if any, which are made against the endpoint and by the
+	/// account specified by the command arguments, unless a step specifies its own account.
+	fn setup(&self) -> anyhow::Result<Option<Scenario>> {
+		let Some(path) = &self.setup else {
+			return Ok(None);
+		};
+		let mut setup = Scenario::load(path)?;
+		setup.url = self.url.clone();
+		setup.suri = self.suri.clone();
+		setup.scheme = self.scheme;
+		Ok(Some(setup))
+	}
+
+	/// Replays the setup calls of the watch, if any, against the deployed contract.
+	async fn replay(&self, setup: &Option<Scenario>) -> anyhow::Result<()> {
+		let Some(setup) = setup else {
+			return Ok(());
+		};
+		let spinner = cliclack::spinner();
+		spinner.start("Replaying the setup calls...");
+		let report = setup.run(&ProgressReporter(spinner.clone())).await;
+		match report.steps.iter().find(|s| s.status == StepStatus::Failed) {
+			None => spinner.stop(format!("Replayed {} setup calls", report.steps.len())),
+			Some(step) => {
+				spinner.error(format!("Setup call `{}` failed.", step.name));
+				log::error(step.error.clone().unwrap_or_default())?;
+			},
+		}
+		Ok(())
+	}
+
+	/// Uploads the contract code without instantiating it.
+	///
+	/// # Arguments
+	///
+	/// * `password` - the password of the stored account, if any, uploading the code.
+	async fn upload(&self, password: Option<String>) -> anyhow::Result<()> {
+		let upload_exec = set_up_upload(self.up_opts(password)).await?;
+
+		let spinner = cliclack::spinner();
+		spinner.start("Doing a dry run to validate the upload...");
+		match dry_run_upload(&upload_exec).await {
+			Ok(result) => {
+				spinner.stop(format!("Storage deposit for the upload: {}", result.deposit));
+			},
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Upload failed.")?;
+				return Ok(());
+			},
+		};
+
+		let spinner = cliclack::spinner();
+		spinner.start("Uploading the contract...");
+		let code_hash =
+			upload_smart_contract(&upload_exec, &self.transaction.tx_opts(), show_status(&spinner))
+				.await
+				.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+		spinner.stop(format!("Contract uploaded: The code hash is {:?}", code_hash));
+		output::set_result(json!({ "code_hash": code_hash }))?;
+		outro("Upload complete")?;
+		Ok(())
+	}
+
+	/// Attributes for the deployment, as specified by the command arguments.
+	///
+	/// # Arguments
+	///
+	/// * `password` - the password of the stored account, if any, deploying the contract.
+	fn up_opts(&self, password: Option<String>) -> UpOpts {
+		UpOpts {
 			path: self.path.clone(),
-			constructor: self.constructor.clone(),
+			constructor: self.constructor.clone().unwrap_or("new".to_string()),
 			args: self.args.clone(),
 			value: self.value.clone(),
 			gas_limit: self.gas_limit,
 			proof_size: self.proof_size,
 			salt: self.salt.clone(),
+			storage_deposit_limit: self.storage_deposit_limit.clone(),
 			url: self.url.clone(),
 			suri: self.suri.clone(),
-		})
-		.await?;
-
-		let weight_limit;
-		if self.gas_limit.is_some() && self.proof_size.is_some() {
-			weight_limit = Weight::from_parts(self.gas_limit.unwrap(), self.proof_size.unwrap());
-		} else {
-			let spinner = cliclack::spinner();
-			spinner.start("Doing a dry run to estimate the gas...");
-			weight_limit = match dry_run_gas_estimate_instantiate(&instantiate_exec).await {
-				Ok(w) => {
-					log::info(format!("Gas limit {:?}", w))?;
-					w
-				},
-				Err(e) => {
-					spinner.error(format!("{e}"));
-					outro_cancel("Deployment failed.")?;
-					return Ok(());
-				},
-			};
+			scheme: self.scheme,
+			password,
+			code_hash: self.code_hash,
 		}
-		let spinner = cliclack::spinner();
-		spinner.start("Uploading and instantiating the contract...");
-		let contract_address = instantiate_smart_contract(instantiate_exec, weight_limit)
-			.await
-			.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
-		spinner.stop(format!(
-			"Contract deployed and instantiated: The Contract Address is {:?}",
-			contract_address
-		));
-		outro("Deployment complete")?;
+	}
+}
+
+struct ProgressReporter(ProgressBar);
+
+impl Status for ProgressReporter {
+	fn update(&self, status: &str) {
+		self.0.set_message(status)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{commands::up::UpCommands, Cli, Commands::Up};
+	use clap::Parser;
+	use pop_contracts::Scheme;
+	use std::{fs, path::PathBuf};
+
+	#[test]
+	fn watch_args_are_parsed() {
+		let cli = Cli::parse_from(["pop", "up", "contract", "--watch", "--setup", "setup.toml"]);
+		let Up(args) = cli.command else { panic!("unable to parse command") };
+		let Some(UpCommands::Contract(command)) = args.command else {
+			panic!("unable to parse command")
+		};
+		assert!(command.watch);
+		assert_eq!(command.setup, Some(PathBuf::from("setup.toml")));
+		// Setup calls are only replayed while watching.
+		assert!(Cli::try_parse_from(["pop", "up", "contract", "--setup", "setup.toml"]).is_err());
+		assert!(Cli::try_parse_from(["pop", "up", "contract", "--watch", "--upload-only"]).is_err());
+	}
+
+	#[test]
+	fn setup_uses_command_endpoint_and_account() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("setup.toml");
+		fs::write(
+			&path,
+			"url = \"ws://localhost:9944\"\nsuri = \"//Alice\"\n\n[[step]]\nname = \"flip\"\n\
+			 action = \"call\"\npath = \".\"\ncontract = \"main\"\nmessage = \"flip\"\n",
+		)?;
+		let cli = Cli::parse_from([
+			"pop",
+			"up",
+			"contract",
+			"--watch",
+			"--setup",
+			path.to_str().unwrap(),
+			"--url",
+			"ws://node:9944",
+			"--suri",
+			"//Bob",
+			"--scheme",
+			"ed25519",
+		]);
+		let Up(args) = cli.command else { panic!("unable to parse command") };
+		let Some(UpCommands::Contract(command)) = args.command else {
+			panic!("unable to parse command")
+		};
+		let setup = command.setup()?.expect("the setup is loaded");
+		assert_eq!(setup.url.as_str(), "ws://node:9944/");
+		assert_eq!(setup.suri, "//Bob");
+		assert_eq!(setup.scheme, Scheme::Ed25519);
 		Ok(())
 	}
 }
@@ -0,0 +1,285 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::Args;
+use cliclack::{confirm, intro, log, outro, outro_cancel, ProgressBar};
+use pop_contracts::{
+	contracts_node_generator, contracts_node_log, start_contracts_node, ContractsNodeOpts,
+	ContractsNodePin, Status, CONFIG_FILE, DEFAULT_PORT,
+};
+use serde_json::json;
+use std::{
+	env::current_dir,
+	path::{Path, PathBuf},
+	sync::Mutex,
+};
+
+use crate::{
+	output::{self, clear_screen},
+	style::style,
+};
+
+#[derive(Args)]
+pub struct ContractsNodeCommand {
+	/// The port on which the node listens for RPC connections.
+	#[clap(long, default_value_t = DEFAULT_PORT)]
+	port: u16,
+	/// Run a development chain, with pre-funded development accounts.
+	#[clap(long)]
+	dev: bool,
+	/// Use temporary state, which is discarded when the node stops.
+	#[clap(long)]
+	tmp: bool,
+	/// The file to which the output of the node is written [default: a log file within the pop
+	/// cache].
+	#[clap(long)]
+	log: Option<PathBuf>,
+	/// The version of the node to be used, as per the release tag (e.g. "v0.41.0"). Defaults to
+	/// the version pinned by the project, or the latest version otherwise.
+	#[clap(long)]
+	version: Option<String>,
+	/// Path to the project, whose `pop.toml` may pin the version of the node [default: current
+	/// directory].
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// Pin the version of the node used within the `pop.toml` of the project, along with the
+	/// checksums of its release archives.
+	#[clap(long)]
+	pin: bool,
+	/// Use the cached version of the node without asking whether to update it when a newer
+	/// version is available.
+	#[clap(short('y'), long)]
+	skip_confirm: bool,
+}
+
+impl ContractsNodeCommand {
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+		intro(format!("{}: Launch a contracts node", style(" Pop CLI ").black().on_magenta()))?;
+		let cache = crate::cache()?;
+		let opts = self.opts(&cache);
+		let project = match &self.path {
+			Some(path) => path.clone(),
+			None => current_dir()?,
+		};
+
+		let Some(binary) =
+			source_contracts_node(&cache, &project, self.version.as_deref(), self.skip_confirm)
+				.await?
+		else {
+			return Ok(());
+		};
+		if self.pin {
+			pin_contracts_node(&project, &binary.version).await?;
+		}
+
+		let spinner = cliclack::spinner();
+		spinner.start("Starting the contracts node...");
+		let node = start_contracts_node(&cache, &binary.path, &opts).await?;
+		spinner.stop(format!(
+			"Contracts node started in the background with process ID {}.",
+			node.pid
+		));
+		if let Some(log) = &node.log {
+			log::info(format!("Logs are written to {}", log.display()))?;
+		}
+		output::set_result(json!({
+			"url": node.url(),
+			"pid": node.pid,
+			"version": binary.version,
+			"log": node.log,
+		}))?;
+		outro(format!(
+			"Node listening at {}. Use `pop down contracts-node` to stop it.",
+			node.url()
+		))?;
+		Ok(())
+	}
+
+	// Options for running the node, as specified by the command arguments.
+	fn opts(&self, cache: &std::path::Path) -> ContractsNodeOpts {
+		ContractsNodeOpts {
+			port: self.port,
+			dev: self.dev,
+			temporary: self.tmp,
+			log: Some(self.log.clone().unwrap_or_else(|| contracts_node_log(cache, self.port))),
+		}
+	}
+}
+
+/// The `substrate-contracts-node` binary sourced for use.
+pub(crate) struct ContractsNodeBinary {
+	/// The path of the binary.
+	pub(crate) path: PathBuf,
+	/// The version of the binary.
+	pub(crate) version: String,
+}
+
+/// Sources the `substrate-contracts-node` binary to be used, if not already cached. The version
+/// pinned by the project is used when no version is specified. Prompts to update the binary when
+/// a newer version is available, unless a version is specified or pinned.
+///
+/// Returns `None` if the binary could not be sourced.
+///
+/// # Arguments
+///
+/// * `cache` - The path where the binary is cached.
+/// * `project` - The path to the project, which may pin the version to be used.
+/// * `version` - The version to be used, if specified.
+/// * `skip_confirm` - Whether to use a cached version without prompting to update it.
+pub(crate) async fn source_contracts_node(
+	cache: &Path,
+	project: &Path,
+	version: Option<&str>,
+	skip_confirm: bool,
+) -> anyhow::Result<Option<ContractsNodeBinary>> {
+	let pin = ContractsNodePin::load(project)?;
+	if let (None, Some(pin)) = (version, &pin) {
+		log::info(format!(
+			"Using version {} of the contracts node, as pinned in {CONFIG_FILE}.",
+			pin.version
+		))?;
+	}
+	let version = version.or(pin.as_ref().map(|p| p.version.as_str()));
+	// The checksums pinned only apply to the pinned version, for which the checksum of the archive
+	// for this platform is expected when any are pinned.
+	let pin = pin.as_ref().filter(|pin| Some(pin.version.as_str()) == version);
+	let checksum = pin.and_then(|pin| pin.checksum());
+	if let (Some(pin), None) = (pin, checksum) {
+		if !pin.checksums.is_empty() {
+			return Err(anyhow::anyhow!(
+				"No checksum is pinned in {CONFIG_FILE} for the release archive of version {} for this platform.",
+				pin.version
+			));
+		}
+	}
+	let mut binary = contracts_node_generator(cache, version, checksum).await?;
+
+	if binary.stale() {
+		log::warning(format!(
+			"ℹ️ A newer version of {} is available:\n   {}",
+			binary.name(),
+			style(format!(
+				"> {} -> {}",
+				binary.version().unwrap_or("None"),
+				binary.latest().unwrap_or("None")
+			))
+			.dim()
+		))?;
+		if !skip_confirm &&
+			output::is_interactive() &&
+			confirm("📦 Would you like to source it automatically now? It may take some time...")
+				.initial_value(true)
+				.interact()?
+		{
+			binary.use_latest();
+		}
+	}
+
+	if !binary.exists() {
+		log::info(format!("ℹ️ The contracts node will be cached at {}", cache.display()))?;
+		let spinner = cliclack::spinner();
+		spinner.start(format!("📦 Sourcing {}...", binary.name()));
+		let reporter = ProgressReporter(spinner.clone(), Mutex::default());
+		let sourced = binary.source(true, &reporter, false).await;
+		let warnings = std::mem::take(&mut *reporter.1.lock().expect("not poisoned; qed"));
+		if let Err(e) = sourced {
+			spinner.error(format!("🚫 {}: {e}", binary.name()));
+			outro_cancel("🚫 Cannot launch the contracts node until it is available.")?;
+			return Ok(None);
+		}
+		spinner.stop(format!("✅  {} {}", binary.name(), binary.version().unwrap_or_default()));
+		for warning in warnings {
+			log::warning(warning)?;
+		}
+	}
+	let version = binary.version().unwrap_or_default().to_string();
+	Ok(Some(ContractsNodeBinary { path: binary.path(), version }))
+}
+
+// Pins the version of the node used within the configuration file of the project, retaining any
+// checksums already pinned for that version.
+async fn pin_contracts_node(project: &Path, version: &str) -> anyhow::Result<()> {
+	if ContractsNodePin::load(project)?.is_some_and(|pin| pin.version == version) {
+		return Ok(());
+	}
+	let pin = ContractsNodePin::new(version).await?;
+	if pin.checksums.is_empty() {
+		log::warning(format!(
+			"No checksums are published for version {version}, so they could not be pinned."
+		))?;
+	}
+	pin.save(project)?;
+	log::success(format!("Version {version} of the contracts node pinned in {CONFIG_FILE}."))?;
+	Ok(())
+}
+
+/// Reports any observed status updates to a progress bar, retaining any warnings to be reported
+/// once complete.
+struct ProgressReporter(ProgressBar, Mutex<Vec<String>>);
+
+impl Status for ProgressReporter {
+	fn update(&self, status: &str) {
+		self.0.start(status)
+	}
+
+	fn warn(&self, warning: &str) {
+		self.1.lock().expect("not poisoned; qed").push(warning.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		commands::up::{UpArgs, UpCommands},
+		Cli,
+		Commands::Up,
+	};
+	use clap::Parser;
+
+	#[test]
+	fn contracts_node_command_opts_works() {
+		let cli = Cli::parse_from([
+			"pop",
+			"up",
+			"contracts-node",
+			"--port",
+			"9955",
+			"--dev",
+			"--tmp",
+			"--version",
+			"v0.41.0",
+			"--pin",
+		]);
+		let Up(UpArgs { command: Some(UpCommands::ContractsNode(command)), .. }) = cli.command
+		else {
+			panic!("unable to parse command")
+		};
+		let cache = PathBuf::from("cache");
+		assert_eq!(
+			command.opts(&cache),
+			ContractsNodeOpts {
+				port: 9955,
+				dev: true,
+				temporary: true,
+				log: Some(contracts_node_log(&cache, 9955)),
+			}
+		);
+		assert_eq!(command.version.as_deref(), Some("v0.41.0"));
+		assert!(command.pin);
+	}
+
+	#[tokio::test]
+	async fn source_contracts_node_fails_without_pinned_checksum() -> anyhow::Result<()> {
+		let (cache, project) = (tempfile::tempdir()?, tempfile::tempdir()?);
+		// Checksums are pinned, but not for the release archive for this platform.
+		std::fs::write(
+			project.path().join(CONFIG_FILE),
+			"[contracts-node]\nversion = \"v0.41.0\"\n\n[contracts-node.checksums]\n\"other.tar.gz\" = \"0x1234\"\n",
+		)?;
+		assert!(source_contracts_node(cache.path(), project.path(), None, true)
+			.await
+			.is_err_and(|e| e.to_string().starts_with("No checksum is pinned")));
+		Ok(())
+	}
+}
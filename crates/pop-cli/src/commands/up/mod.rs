@@ -2,16 +2,92 @@
 
 #[cfg(feature = "contract")]
 mod contract;
+#[cfg(feature = "contract")]
+pub(crate) mod contracts_node;
 #[cfg(feature = "parachain")]
 mod parachain;
 
+use super::{detect_project, project_not_found};
+use crate::config::CommandConfig;
 use clap::{Args, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Args)]
 #[command(args_conflicts_with_subcommands = true)]
 pub(crate) struct UpArgs {
 	#[command(subcommand)]
-	pub(crate) command: UpCommands,
+	pub(crate) command: Option<UpCommands>,
+	/// Directory path for your project, when launching without specifying whether it is a
+	/// contract or a parachain [default: current directory]
+	#[arg(short = 'p', long)]
+	pub(crate) path: Option<PathBuf>,
+	/// Whether the subcommand was detected from the project, rather than specified.
+	#[arg(skip)]
+	resolved: bool,
+}
+
+impl UpArgs {
+	/// Determines what to launch when not specified, as detected from the project: its network,
+	/// when it has a network configuration file or is a parachain, otherwise its contract.
+	pub(crate) fn resolve(&mut self) -> anyhow::Result<()> {
+		if self.command.is_some() {
+			return Ok(());
+		}
+		let project = detect_project(&self.path)?;
+		self.resolved = true;
+		#[cfg(feature = "parachain")]
+		if project.network.is_some() || project.parachain.is_some() {
+			self.command = Some(UpCommands::Parachain(parachain::ZombienetCommand {
+				file: project.network.map(|file| file.display().to_string()),
+				relay_chain: None,
+				system_parachain: None,
+				parachain: None,
+				command: None,
+				verbose: false,
+			}));
+			return Ok(());
+		}
+		#[cfg(feature = "contract")]
+		match project.contracts.as_slice() {
+			[] => {},
+			[path] => {
+				self.command =
+					Some(UpCommands::Contract(contract::UpContractCommand::from_path(path)?));
+				return Ok(());
+			},
+			_ =>
+				return Err(anyhow::anyhow!(
+					"Multiple contracts were found: run `pop up contract --path <path>` to deploy one of them"
+				)),
+		}
+		Err(project_not_found(&self.path, "up"))
+	}
+
+	/// Applies the settings configured by the project to the arguments of the subcommand not
+	/// specified on the command line. Fails when the network detected from the project has no
+	/// configuration file, neither found in the project nor configured.
+	///
+	/// # Arguments
+	///
+	/// * `load` - loads the settings configured for the command.
+	pub(crate) fn configure<'a>(
+		&mut self,
+		load: impl FnOnce() -> anyhow::Result<CommandConfig<'a>>,
+	) -> anyhow::Result<()> {
+		match &mut self.command {
+			#[cfg(feature = "parachain")]
+			Some(UpCommands::Parachain(command)) => {
+				command.configure(&load()?);
+				if self.resolved && command.file.is_none() {
+					return Err(project_not_found(&self.path, "up"));
+				}
+			},
+			#[cfg(feature = "contract")]
+			Some(UpCommands::Contract(command)) => command.configure(&load()?),
+			_ => {},
+		}
+		Ok(())
+	}
 }
 
 #[derive(Subcommand)]
@@ -24,4 +100,89 @@ pub(crate) enum UpCommands {
 	/// Deploy a smart contract to a node.
 	#[clap(alias = "c")]
 	Contract(contract::UpContractCommand),
+	#[cfg(feature = "contract")]
+	/// Launch a local contracts node in the background.
+	#[clap(alias = "n")]
+	ContractsNode(contracts_node::ContractsNodeCommand),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Cli, Commands::Up};
+	use clap::{CommandFactory, FromArgMatches, Parser};
+	use pop_common::{Settings, NETWORK_FILE};
+	use std::fs;
+
+	#[test]
+	#[cfg(all(feature = "contract", feature = "parachain"))]
+	fn resolve_works() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		let resolve = || -> anyhow::Result<Option<UpCommands>> {
+			let cli = Cli::try_parse_from(["pop", "up", "--path", path.to_str().unwrap()])?;
+			let Up(mut args) = cli.command else { panic!("unable to parse command") };
+			assert!(args.command.is_none());
+			args.resolve()?;
+			Ok(args.command)
+		};
+		assert!(resolve().is_err());
+
+		// A contract is deployed.
+		fs::write(
+			path.join("Cargo.toml"),
+			"[package]\nname = \"flipper\"\n\n[dependencies]\nink = \"5.0.0\"\n",
+		)?;
+		let Some(UpCommands::Contract(_)) = resolve()? else { panic!("contract expected") };
+
+		// The network is launched, once configured.
+		fs::write(path.join(NETWORK_FILE), "")?;
+		let Some(UpCommands::Parachain(command)) = resolve()? else { panic!("parachain expected") };
+		assert_eq!(command.file, Some(path.join(NETWORK_FILE).display().to_string()));
+
+		// A subcommand specified is used as is.
+		let cli = Cli::try_parse_from(["pop", "up", "contract"])?;
+		let Up(mut args) = cli.command else { panic!("unable to parse command") };
+		args.resolve()?;
+		assert!(matches!(args.command, Some(UpCommands::Contract(_))));
+		Ok(())
+	}
+
+	#[test]
+	#[cfg(feature = "parachain")]
+	fn configure_requires_network_file() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		fs::write(
+			path.join("Cargo.toml"),
+			"[package]\nname = \"parachain\"\n\n[dependencies]\nsc-cli = \"0.46.0\"\n",
+		)?;
+		let matches =
+			Cli::command().try_get_matches_from(["pop", "up", "--path", path.to_str().unwrap()])?;
+		let configure = |settings: Settings| -> anyhow::Result<Option<UpCommands>> {
+			let Up(mut args) = Cli::from_arg_matches(&matches)?.command else {
+				panic!("unable to parse command")
+			};
+			args.resolve()?;
+			args.configure(|| Ok(CommandConfig::new(settings, &matches)))?;
+			Ok(args.command)
+		};
+		// The network has no configuration file.
+		let Err(e) = configure(Settings::default()) else { panic!("error expected") };
+		assert!(e.to_string().starts_with("No contract or parachain was found"));
+
+		// The configured file is used.
+		let settings = Settings { file: Some(PathBuf::from("network.toml")), ..Default::default() };
+		let Some(UpCommands::Parachain(command)) = configure(settings)? else {
+			panic!("parachain expected")
+		};
+		assert_eq!(command.file.as_deref(), Some("network.toml"));
+
+		// A network specified is launched as is, reporting the missing file when run.
+		let cli = Cli::try_parse_from(["pop", "up", "parachain"])?;
+		let Up(mut args) = cli.command else { panic!("unable to parse command") };
+		args.resolve()?;
+		args.configure(|| Ok(CommandConfig::new(Settings::default(), &matches)))?;
+		Ok(())
+	}
 }
@@ -1,51 +1,78 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::style::{style, Theme};
+use crate::{
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::{style, Theme},
+};
 use clap::Args;
 use cliclack::{
-	clear_screen, confirm, intro, log, multi_progress, outro, outro_cancel, set_theme, ProgressBar,
-	Theme as _, ThemeState,
+	confirm, intro, log, multi_progress, outro, outro_cancel, set_theme, ProgressBar, Theme as _,
+	ThemeState,
 };
 use console::{Emoji, Style, Term};
 use duct::cmd;
 use pop_parachains::{Error, IndexSet, NetworkNode, Status, Zombienet};
+use serde_json::{json, Value};
 use std::{path::PathBuf, time::Duration};
 use tokio::time::sleep;
 
 #[derive(Args)]
 pub(crate) struct ZombienetCommand {
-	/// The Zombienet network configuration file to be used.
+	/// The Zombienet network configuration file to be used [default: the file configured in
+	/// `pop.toml`].
 	#[arg(short, long)]
-	file: String,
+	pub(crate) file: Option<String>,
 	/// The version of Polkadot to be used for the relay chain, as per the release tag (e.g.
 	/// "v1.11.0").
 	#[arg(short, long)]
-	relay_chain: Option<String>,
+	pub(crate) relay_chain: Option<String>,
 	/// The version of Polkadot to be used for a system parachain, as per the release tag (e.g.
 	/// "v1.11.0"). Defaults to the relay chain version if not specified.
 	#[arg(short, long)]
-	system_parachain: Option<String>,
+	pub(crate) system_parachain: Option<String>,
 	/// The url of the git repository of a parachain to be used, with branch/release tag/commit specified as #fragment (e.g. 'https://github.com/org/repository#ref').
 	/// A specific binary name can also be optionally specified via query string parameter (e.g. 'https://github.com/org/repository?binaryname#ref'), defaulting to the name of the repository when not specified.
 	#[arg(short, long)]
-	parachain: Option<Vec<String>>,
+	pub(crate) parachain: Option<Vec<String>>,
 	/// The command to run after the network has been launched.
 	#[clap(name = "cmd", short = 'c', long)]
-	command: Option<String>,
+	pub(crate) command: Option<String>,
 	/// Whether the output should be verbose.
 	#[arg(short, long, action)]
-	verbose: bool,
+	pub(crate) verbose: bool,
 }
 impl ZombienetCommand {
+	/// Uses the network configuration and binary versions configured by the project, unless
+	/// specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(file) = config.get("file", |s| &s.file) {
+			self.file = Some(file.display().to_string());
+		}
+		if let Some(relay_chain) = config.get("relay_chain", |s| &s.relay_chain) {
+			self.relay_chain = Some(relay_chain);
+		}
+		if let Some(system_parachain) = config.get("system_parachain", |s| &s.system_parachain) {
+			self.system_parachain = Some(system_parachain);
+		}
+		if let Some(parachains) = config.get("parachain", |s| &s.parachains) {
+			self.parachain = Some(parachains);
+		}
+	}
+
 	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
 		clear_screen()?;
 		intro(format!("{}: Launch a local network", style(" Pop CLI ").black().on_magenta()))?;
 		set_theme(Theme);
 
 		// Parse arguments
+		let Some(file) = &self.file else {
+			outro_cancel("🚫 A network configuration file is required: use `--file` or configure a `file` in pop.toml.")?;
+			return Ok(());
+		};
 		let cache = crate::cache()?;
 		let mut zombienet = match Zombienet::new(
 			&cache,
-			&self.file,
+			file,
 			self.relay_chain.as_ref().map(|v| v.as_str()),
 			self.system_parachain.as_ref().map(|v| v.as_str()),
 			self.parachain.as_ref(),
@@ -125,6 +152,35 @@ impl ZombienetCommand {
 					}
 				}
 
+				// The endpoints of the nodes are output once launched, rather than once terminated.
+				let nodes = |nodes: Vec<&NetworkNode>| -> Vec<Value> {
+					nodes
+						.iter()
+						.map(|node| {
+							json!({
+								"name": node.name(),
+								"ws_uri": node.ws_uri(),
+								"log": format!("{base_dir}/{0}/{0}.log", node.name()),
+							})
+						})
+						.collect()
+				};
+				output::set_result(json!({
+					"relay_chain": {
+						"chain": network.relaychain().chain(),
+						"nodes": nodes(network.relaychain().nodes()),
+					},
+					"parachains": parachains
+						.iter()
+						.map(|parachain| json!({
+							"para_id": parachain.para_id(),
+							"chain": parachain.chain_id(),
+							"nodes": nodes(parachain.collators()),
+						}))
+						.collect::<Vec<_>>(),
+				}))?;
+				output::emit(&Ok(()));
+
 				if let Some(command) = &self.command {
 					run_custom_command(&spinner, command).await?;
 				}
@@ -183,6 +239,7 @@ impl ZombienetCommand {
 			))
 			.dim()
 			.to_string();
+			output::ensure_interactive("confirmation to source the missing binaries")?;
 			if !confirm(format!(
 				"📦 Would you like to source them automatically now? It may take some time...\n   {list}"))
 			.initial_value(true)
@@ -222,12 +279,14 @@ impl ZombienetCommand {
 				"ℹ️ The following binaries have newer versions available:\n   {list}"
 			))?;
 
-			latest = confirm(
-				"📦 Would you like to source them automatically now? It may take some time..."
-					.to_string(),
-			)
-			.initial_value(true)
-			.interact()?;
+			// When running non-interactively, the binaries already cached are used.
+			latest = output::is_interactive() &&
+				confirm(
+					"📦 Would you like to source them automatically now? It may take some time..."
+						.to_string(),
+				)
+				.initial_value(true)
+				.interact()?;
 		}
 
 		let binaries: Vec<_> = binaries
@@ -346,7 +405,7 @@ impl Status for VerboseReporter {
 			status = style(status).dim()
 		);
 		if let Err(e) = Term::stderr().write_line(&message) {
-			println!("An error occurred logging the status message of '{status}': {e}")
+			eprintln!("An error occurred logging the status message of '{status}': {e}")
 		}
 	}
 }
@@ -355,6 +414,44 @@ impl Status for VerboseReporter {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::{commands::up::UpCommands, Cli, Commands::Up};
+	use clap::{CommandFactory, FromArgMatches};
+	use pop_common::Settings;
+
+	#[test]
+	fn configure_works() -> anyhow::Result<()> {
+		let matches = Cli::command().try_get_matches_from([
+			"pop",
+			"up",
+			"parachain",
+			"--relay-chain",
+			"v1.12.0",
+		])?;
+		let Up(args) = Cli::from_arg_matches(&matches)?.command else {
+			panic!("unable to parse command")
+		};
+		let Some(UpCommands::Parachain(mut command)) = args.command else {
+			panic!("unable to parse command")
+		};
+		let (_, up) = matches.subcommand().expect("subcommand expected");
+		let (_, parachain) = up.subcommand().expect("subcommand expected");
+		let settings = Settings {
+			file: Some(PathBuf::from("network.toml")),
+			relay_chain: Some("v1.13.0".to_string()),
+			parachains: Some(vec!["https://github.com/r0gue-io/pop-node#v0.1.0".to_string()]),
+			..Default::default()
+		};
+		command.configure(&CommandConfig::new(settings, parachain));
+		assert_eq!(command.file.as_deref(), Some("network.toml"));
+		// Arguments specified on the command line take precedence.
+		assert_eq!(command.relay_chain.as_deref(), Some("v1.12.0"));
+		assert_eq!(command.system_parachain, None);
+		assert_eq!(
+			command.parachain,
+			Some(vec!["https://github.com/r0gue-io/pop-node#v0.1.0".to_string()])
+		);
+		Ok(())
+	}
 
 	#[tokio::test]
 	async fn test_run_custom_command() -> Result<(), anyhow::Error> {
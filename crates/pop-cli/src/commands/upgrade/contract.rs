@@ -0,0 +1,440 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::anyhow;
+use clap::Args;
+use cliclack::{confirm, intro, log, outro, outro_cancel};
+use pop_contracts::{
+	build_smart_contract, call_smart_contract, compare_storage_layouts, dry_run_estimate_call,
+	dry_run_upload, estimate_call_fee, estimate_upload_fee, get_code_hash, set_up_call,
+	set_up_upload, upload_smart_contract, CallOpts, Deployments, Scheme, TxOpts, UpOpts, WaitFor,
+	DEFAULT_UPGRADE_MESSAGE,
+};
+use serde_json::json;
+use sp_weights::Weight;
+use std::path::PathBuf;
+
+use crate::{
+	commands::{
+		account::{account_password, scheme_parser},
+		build::contract::display_build,
+		call::contract::{
+			confirm_cost, display_events, show_status, weight_limit, TransactionArgs,
+			DEFAULT_WEIGHT_MARGIN,
+		},
+	},
+	config::CommandConfig,
+	output::{self, clear_screen},
+	style::style,
+};
+
+#[derive(Args)]
+pub struct UpgradeContractCommand {
+	/// Path to the contract build folder of the new version of the contract.
+	#[arg(short = 'p', long)]
+	path: Option<PathBuf>,
+	/// The address of the contract to upgrade, or the alias or name of a contract deployed using
+	/// `pop up contract`.
+	#[clap(name = "contract", long, env = "CONTRACT")]
+	contract: String,
+	/// The metadata (`.contract` or `.json` file) of the version of the contract being upgraded,
+	/// used to check that the storage layout of the new version is compatible.
+	#[clap(long)]
+	old_metadata: Option<PathBuf>,
+	/// The name of the contract message which sets the code hash of the contract, taking the new
+	/// code hash as its only argument.
+	#[clap(long, short, default_value = DEFAULT_UPGRADE_MESSAGE)]
+	message: String,
+	/// Maximum amount of gas to be used for the call to the upgrade message.
+	/// If not specified it will perform a dry-run to estimate the gas consumed for the call.
+	#[clap(name = "gas", long)]
+	gas_limit: Option<u64>,
+	/// Maximum proof size for the call to the upgrade message.
+	/// If not specified it will perform a dry-run to estimate the proof size required.
+	#[clap(long)]
+	proof_size: Option<u64>,
+	/// Maximum storage deposit which may be charged for uploading the new contract code and for
+	/// the call to the upgrade message, e.g. `1000` or `1.5UNIT`. If not specified, each may use
+	/// all of the free balance of the account.
+	#[clap(long)]
+	storage_deposit_limit: Option<String>,
+	/// The percentage by which the gas and proof size estimated by a dry run of the upgrade
+	/// message are increased, so that the call does not run out of gas when the state of the
+	/// chain changes before it is included in a block.
+	#[clap(long, default_value_t = DEFAULT_WEIGHT_MARGIN)]
+	weight_margin: u32,
+	/// Websocket endpoint of a node.
+	#[clap(name = "url", long, value_parser, default_value = "ws://localhost:9944")]
+	url: url::Url,
+	/// Secret key URI for the account upgrading the contract, or the name of an account added
+	/// using `pop account add`.
+	///
+	/// e.g.
+	/// - for a dev account "//Alice"
+	/// - with a password "//Alice///SECRET_PASSWORD"
+	/// - read from an environment variable "env:VAR"
+	/// - read from a file "file:path"
+	#[clap(name = "suri", long, short, default_value = "//Alice")]
+	suri: String,
+	/// The cryptographic scheme of the account, when specified by a secret key URI. Accounts added
+	/// using `pop account add` use the scheme they were added with.
+	#[arg(long, default_value = "sr25519", value_parser = scheme_parser())]
+	scheme: Scheme,
+	/// Upgrade the contract even if the storage layout of the new version is incompatible.
+	#[clap(long)]
+	force: bool,
+	/// Upgrade the contract without asking for confirmation when the storage layout cannot be
+	/// checked, or of the estimated cost of the upload and of the call to the upgrade message.
+	#[clap(short('y'), long)]
+	skip_confirm: bool,
+	// Options controlling the submission of the upload and of the call to the upgrade message.
+	#[command(flatten)]
+	transaction: TransactionArgs,
+}
+
+impl UpgradeContractCommand {
+	/// Uses the endpoint and account configured by the project, unless specified.
+	pub(crate) fn configure(&mut self, config: &CommandConfig) {
+		if let Some(url) = config.get("url", |s| &s.url) {
+			self.url = url;
+		}
+		if let Some(suri) = config.get("suri", |s| &s.suri) {
+			self.suri = suri;
+		}
+	}
+
+	pub(crate) async fn execute(&self) -> anyhow::Result<()> {
+		clear_screen()?;
+
+		// Check if build exists in the specified "Contract build folder"
+		let build_path = self.path.clone().unwrap_or("./".into()).join("target/ink");
+		if !build_path.as_path().exists() {
+			log::warning("NOTE: contract has not yet been built.")?;
+			intro(format!("{}: Building a contract", style(" Pop CLI ").black().on_magenta()))?;
+			// Build the contract in release mode
+			let artifacts = build_smart_contract(&self.path, true)?;
+			log::success(display_build(&artifacts))?;
+		}
+
+		intro(format!("{}: Upgrade a smart contract", style(" Pop CLI ").black().on_magenta()))?;
+
+		if !self.check_storage_layout()? {
+			// The upgrade is only cancelled successfully when the user chose to cancel it.
+			return match output::is_interactive() {
+				true => Ok(()),
+				false => Err(anyhow!("The upgrade of the contract was cancelled")),
+			};
+		}
+		let password = account_password(&self.suri, self.scheme)?;
+
+		// Estimate the cost of both the upload of the new contract code and the call to the upgrade
+		// message before submitting either, so that the upgrade is confirmed once.
+		let upload_exec = set_up_upload(self.up_opts(password.clone())).await?;
+		let spinner = cliclack::spinner();
+		spinner.start("Doing a dry run to estimate the cost of the upgrade...");
+		let estimate = match dry_run_upload(&upload_exec).await {
+			Ok(result) => estimate_upload_fee(&upload_exec).await.map(|fee| (result.deposit, fee)),
+			Err(e) => Err(e),
+		};
+		let (upload_deposit, upload_fee) = match estimate {
+			Ok(estimate) => estimate,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Upgrade failed.")?;
+				return Err(e);
+			},
+		};
+		// The new code is not yet uploaded, so the upgrade message is estimated by setting the
+		// code hash the contract already uses.
+		let estimate = match get_code_hash(&self.path, &self.contract, &self.url).await {
+			Ok(code_hash) => self.estimate_call(code_hash, password.clone()).await,
+			Err(e) => Err(e),
+		};
+		let (weight_limit, call_deposit, call_fee) = match estimate {
+			Ok(estimate) => estimate,
+			Err(e) => {
+				spinner.error(format!("{e}"));
+				outro_cancel("Upgrade failed.")?;
+				return Err(e);
+			},
+		};
+		spinner.clear();
+		if !confirm_cost(
+			Some(weight_limit),
+			Some(upload_deposit + call_deposit.unwrap_or_default()),
+			upload_fee + call_fee,
+			&self.url,
+			self.skip_confirm,
+		)
+		.await?
+		{
+			outro_cancel("Upgrade cancelled.")?;
+			return Ok(());
+		}
+
+		// Upload the code of the new version of the contract.
+		let (upload_opts, call_opts) = self.tx_opts();
+		let spinner = cliclack::spinner();
+		spinner.start("Uploading the new contract code...");
+		let code_hash = upload_smart_contract(&upload_exec, &upload_opts, show_status(&spinner))
+			.await
+			.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+		spinner.stop(format!("Contract code uploaded: The code hash is {:?}", code_hash));
+
+		// Call the upgrade message of the contract with the new code hash.
+		let call_exec = set_up_call(self.call_opts(code_hash.clone(), password)).await?;
+		let spinner = cliclack::spinner();
+		spinner.start(format!("Calling `{}` to upgrade the contract...", self.message));
+		let call_result = call_smart_contract(
+			call_exec,
+			weight_limit,
+			&self.url,
+			&call_opts,
+			show_status(&spinner),
+		)
+		.await
+		.map_err(|err| anyhow!("{} {}", "ERROR:", format!("{err:?}")))?;
+		spinner.stop(match call_opts.wait_for {
+			WaitFor::InBlock => "Upgrade included in a block",
+			WaitFor::Finalized => "Upgrade finalized",
+		});
+		log::info(display_events(&call_result.events))?;
+
+		// Record the new code hash of the contract if it was deployed from the project.
+		let updated = Deployments::load(&self.path).and_then(|mut deployments| {
+			let address = deployments
+				.resolve(&self.contract, &self.url)
+				.map(|d| d.address.clone())
+				.unwrap_or_else(|| self.contract.clone());
+			deployments.set_code_hash(&address, &self.url, &code_hash)
+		});
+		if let Err(e) = updated {
+			log::warning(format!("The new code hash could not be recorded: {e}"))?;
+		}
+
+		output::set_result(json!({
+			"contract": self.contract,
+			"code_hash": code_hash,
+			"block_hash": call_result.block_hash,
+			"gas_used": call_result.gas_used,
+			"events": call_result.events,
+		}))?;
+		outro(format!("Contract upgraded to code hash {code_hash}"))?;
+		Ok(())
+	}
+
+	// Checks the storage layout of the new version of the contract is compatible with the version
+	// being upgraded, returning whether to proceed with the upgrade.
+	fn check_storage_layout(&self) -> anyhow::Result<bool> {
+		let Some(old_metadata) = &self.old_metadata else {
+			log::warning(format!(
+				"The storage layout of the new version of the contract could not be checked for compatibility. Specify the metadata of the version being upgraded using {}.",
+				"--old-metadata"
+			))?;
+			if !self.skip_confirm {
+				output::ensure_interactive(
+					"confirmation to upgrade without checking the storage layout (use `-y` to skip it)",
+				)?;
+			}
+			if !self.skip_confirm &&
+				!confirm(
+					"Would you like to upgrade the contract without checking its storage layout?",
+				)
+				.initial_value(false)
+				.interact()?
+			{
+				outro_cancel("Upgrade cancelled.")?;
+				return Ok(false);
+			}
+			return Ok(true);
+		};
+
+		let changes = compare_storage_layouts(&Some(old_metadata.clone()), &self.path)?;
+		if changes.is_empty() {
+			log::success("The storage layout of the new version of the contract is compatible.")?;
+			return Ok(true);
+		}
+		let changes: Vec<_> = changes.iter().map(|change| format!("- {change}")).collect();
+		log::error(format!(
+			"The storage layout of the new version of the contract is incompatible with the version being upgraded, which will corrupt its storage:\n{}",
+			changes.join("\n")
+		))?;
+		if !self.force {
+			outro_cancel(format!(
+				"Upgrade cancelled. Migrate the storage of the contract, or use {} to upgrade regardless.",
+				"--force"
+			))?;
+			return Ok(false);
+		}
+		log::warning(
+			"Upgrading despite the incompatible storage layout, as --force was specified.",
+		)?;
+		Ok(true)
+	}
+
+	// Estimates the weight limit, storage deposit and fee of the call to the upgrade message.
+	async fn estimate_call(
+		&self,
+		code_hash: String,
+		password: Option<String>,
+	) -> anyhow::Result<(Weight, Option<u128>, u128)> {
+		let call_exec = set_up_call(self.call_opts(code_hash, password)).await?;
+		let (weight_limit, storage_deposit) = match (self.gas_limit, self.proof_size) {
+			(Some(gas_limit), Some(proof_size)) =>
+				(Weight::from_parts(gas_limit, proof_size), None),
+			_ => {
+				let (weight, storage_deposit) = dry_run_estimate_call(&call_exec).await?;
+				(
+					weight_limit(weight, self.weight_margin, self.gas_limit, self.proof_size),
+					Some(storage_deposit),
+				)
+			},
+		};
+		let fee = estimate_call_fee(&call_exec, weight_limit).await?;
+		Ok((weight_limit, storage_deposit, fee))
+	}
+
+	// Options for submitting the upload and the call to the upgrade message. A specified nonce is
+	// used for the upload, and the next for the call.
+	fn tx_opts(&self) -> (TxOpts, TxOpts) {
+		let upload = self.transaction.tx_opts();
+		let call = TxOpts { nonce: upload.nonce.map(|nonce| nonce + 1), ..upload.clone() };
+		(upload, call)
+	}
+
+	/// Attributes for the call to the upgrade message, as specified by the command arguments.
+	///
+	/// # Arguments
+	///
+	/// * `code_hash` - the code hash to upgrade the contract to.
+	/// * `password` - the password of the stored account, if any, calling the contract.
+	fn call_opts(&self, code_hash: String, password: Option<String>) -> CallOpts {
+		CallOpts {
+			path: self.path.clone(),
+			contract: self.contract.clone(),
+			message: self.message.clone(),
+			args: vec![code_hash],
+			value: "0".to_string(),
+			gas_limit: self.gas_limit,
+			proof_size: self.proof_size,
+			storage_deposit_limit: self.storage_deposit_limit.clone(),
+			url: self.url.clone(),
+			suri: self.suri.clone(),
+			scheme: self.scheme,
+			password,
+			execute: true,
+		}
+	}
+
+	/// Attributes for uploading the new contract code, as specified by the command arguments.
+	///
+	/// # Arguments
+	///
+	/// * `password` - the password of the stored account, if any, uploading the code.
+	fn up_opts(&self, password: Option<String>) -> UpOpts {
+		UpOpts {
+			path: self.path.clone(),
+			constructor: String::new(),
+			args: Vec::new(),
+			value: "0".to_string(),
+			gas_limit: None,
+			proof_size: None,
+			salt: None,
+			storage_deposit_limit: self.storage_deposit_limit.clone(),
+			url: self.url.clone(),
+			suri: self.suri.clone(),
+			scheme: self.scheme,
+			password,
+			code_hash: None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		commands::upgrade::{UpgradeArgs, UpgradeCommands},
+		Cli,
+		Commands::Upgrade,
+	};
+	use clap::Parser;
+	use std::fs;
+
+	fn upgrade_command(args: &[&str]) -> UpgradeContractCommand {
+		let cli = Cli::parse_from(
+			[&["pop", "upgrade", "contract", "--contract", "flipper"], args].concat(),
+		);
+		let Upgrade(UpgradeArgs { command: UpgradeCommands::Contract(command) }) = cli.command
+		else {
+			panic!("unable to parse command")
+		};
+		command
+	}
+
+	// Writes the testing metadata and a version with an incompatible storage layout.
+	fn metadata(dir: &std::path::Path) -> anyhow::Result<(PathBuf, PathBuf)> {
+		let testing = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("../pop-contracts/tests/files/testing.json");
+		let mut metadata: serde_json::Value = serde_json::from_str(&fs::read_to_string(testing)?)?;
+		let (old, new) = (dir.join("old.json"), dir.join("new.json"));
+		fs::write(&old, metadata.to_string())?;
+		metadata["storage"]["root"]["layout"]["struct"]["fields"]
+			.as_array_mut()
+			.unwrap()
+			.swap(0, 1);
+		fs::write(&new, metadata.to_string())?;
+		Ok((old, new))
+	}
+
+	#[test]
+	fn upgrade_is_cancelled_for_incompatible_layout() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let (old, new) = metadata(temp_dir.path())?;
+		let command = upgrade_command(&[
+			"--path",
+			new.to_str().unwrap(),
+			"--old-metadata",
+			old.to_str().unwrap(),
+		]);
+		assert_eq!(command.message, DEFAULT_UPGRADE_MESSAGE);
+		assert_eq!(command.weight_margin, DEFAULT_WEIGHT_MARGIN);
+		assert_eq!(command.storage_deposit_limit, None);
+		assert!(!command.check_storage_layout()?);
+		Ok(())
+	}
+
+	#[test]
+	fn upgrade_proceeds_for_compatible_layout_or_when_forced() -> anyhow::Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let (old, new) = metadata(temp_dir.path())?;
+		let command = upgrade_command(&[
+			"--path",
+			old.to_str().unwrap(),
+			"--old-metadata",
+			old.to_str().unwrap(),
+		]);
+		assert!(command.check_storage_layout()?);
+		let command = upgrade_command(&[
+			"--path",
+			new.to_str().unwrap(),
+			"--old-metadata",
+			old.to_str().unwrap(),
+			"--force",
+		]);
+		assert!(command.check_storage_layout()?);
+		Ok(())
+	}
+
+	#[test]
+	fn transaction_args_apply_to_upload_and_call() {
+		let (upload, call) = upgrade_command(&[]).tx_opts();
+		assert_eq!((upload, call), (TxOpts::default(), TxOpts::default()));
+
+		let (upload, call) =
+			upgrade_command(&["--wait", "finalized", "--nonce", "7", "--tip", "100"]).tx_opts();
+		let expected =
+			TxOpts { wait_for: WaitFor::Finalized, nonce: Some(7), tip: 100, ..Default::default() };
+		assert_eq!(upload, expected);
+		assert_eq!(call, TxOpts { nonce: Some(8), ..expected });
+	}
+}
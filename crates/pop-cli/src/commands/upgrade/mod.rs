@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use clap::{Args, Subcommand};
+
+pub(crate) mod contract;
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct UpgradeArgs {
+	#[command(subcommand)]
+	pub command: UpgradeCommands,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum UpgradeCommands {
+	/// Upgrade the code of a deployed contract
+	#[clap(alias = "c")]
+	Contract(contract::UpgradeContractCommand),
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::anyhow;
+use clap::{parser::ValueSource, ArgMatches};
+use pop_common::{Config, Settings};
+use std::{env::current_dir, path::PathBuf};
+
+/// The settings configured for a command by its project, used in place of the arguments not
+/// specified on the command line.
+pub(crate) struct CommandConfig<'a> {
+	settings: Settings,
+	matches: &'a ArgMatches,
+}
+
+impl<'a> CommandConfig<'a> {
+	/// Loads the settings configured for the command being run, by the project at the path
+	/// specified by the command or otherwise in the current directory. A configured network file
+	/// is relative to the project.
+	///
+	/// # Arguments
+	///
+	/// * `network` - the name of the network profile selected, if any.
+	/// * `matches` - the arguments matched on the command line.
+	pub(crate) fn load(network: Option<&str>, matches: &'a ArgMatches) -> anyhow::Result<Self> {
+		let (command, mut matches) =
+			matches.subcommand().ok_or_else(|| anyhow!("no command was specified"))?;
+		while let Some((_, subcommand)) = matches.subcommand() {
+			matches = subcommand;
+		}
+		// The path of a command is its project, or a file within it such as a scenario.
+		let project = match matches.try_get_one::<PathBuf>("path").ok().flatten() {
+			Some(path) if path.is_file() => path.parent().map(|p| p.to_path_buf()),
+			path => path.cloned(),
+		}
+		.filter(|path| !path.as_os_str().is_empty());
+		let mut settings = match &project {
+			Some(project) => Config::load(project)?,
+			None => Config::load(&current_dir()?)?,
+		}
+		.settings(command, network)?;
+		if let (Some(project), Some(file)) = (project, &mut settings.file) {
+			*file = project.join(&*file);
+		}
+		Ok(Self::new(settings, matches))
+	}
+
+	/// The settings configured for a command.
+	///
+	/// # Arguments
+	///
+	/// * `settings` - the settings configured for the command.
+	/// * `matches` - the arguments of the command matched on the command line.
+	pub(crate) fn new(settings: Settings, matches: &'a ArgMatches) -> Self {
+		Self { settings, matches }
+	}
+
+	/// The configured value of a setting, unless its argument was specified on the command line
+	/// or by an environment variable. Arguments not matched by the command, such as those of a
+	/// subcommand detected from the project rather than specified, are never specified.
+	///
+	/// # Arguments
+	///
+	/// * `arg` - the identifier of the argument of the command.
+	/// * `setting` - selects the setting from the settings configured.
+	pub(crate) fn get<T: Clone>(
+		&self,
+		arg: &str,
+		setting: impl FnOnce(&Settings) -> &Option<T>,
+	) -> Option<T> {
+		let specified = self.matches.try_contains_id(arg).unwrap_or(false) &&
+			matches!(
+				self.matches.value_source(arg),
+				Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+			);
+		match specified {
+			true => None,
+			false => setting(&self.settings).clone(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Cli;
+	use clap::CommandFactory;
+	use pop_common::CONFIG_FILE;
+	use std::fs;
+	use url::Url;
+
+	#[test]
+	fn get_works() -> anyhow::Result<()> {
+		let settings = Settings {
+			url: Some(Url::parse("wss://testnet-a.example.com")?),
+			suri: Some("deployer".to_string()),
+			..Default::default()
+		};
+		let matches = Cli::command().try_get_matches_from([
+			"pop",
+			"call",
+			"contract",
+			"--contract",
+			"main",
+			"--url",
+			"ws://localhost:9944",
+		])?;
+		let (_, matches) = matches.subcommand().expect("subcommand expected");
+		let (_, matches) = matches.subcommand().expect("subcommand expected");
+		let config = CommandConfig::new(settings, matches);
+		// Arguments specified on the command line take precedence.
+		assert_eq!(config.get("url", |s| &s.url), None);
+		assert_eq!(config.get("suri", |s| &s.suri), Some("deployer".to_string()));
+		assert_eq!(config.get("suri", |s| &s.file), None);
+		// Arguments not matched by the command are never specified.
+		assert_eq!(config.get("relay_chain", |s| &s.suri), Some("deployer".to_string()));
+		Ok(())
+	}
+
+	#[test]
+	fn load_uses_project_of_path() -> anyhow::Result<()> {
+		let project = tempfile::tempdir()?;
+		fs::write(
+			project.path().join(CONFIG_FILE),
+			"[defaults.call]\nurl = \"wss://testnet-a.example.com\"\n\n[defaults.up]\nfile = \"network.toml\"\n",
+		)?;
+		let path = project.path().to_str().expect("valid path");
+		let matches = Cli::command().try_get_matches_from([
+			"pop",
+			"call",
+			"contract",
+			"-p",
+			path,
+			"--contract",
+			"main",
+		])?;
+		let config = CommandConfig::load(None, &matches)?;
+		assert_eq!(config.get("url", |s| &s.url), Some(Url::parse("wss://testnet-a.example.com")?));
+
+		// A configured network file is relative to the project.
+		let matches = Cli::command().try_get_matches_from(["pop", "up", "-p", path])?;
+		let config = CommandConfig::load(None, &matches)?;
+		assert_eq!(config.get("file", |s| &s.file), Some(project.path().join("network.toml")));
+		Ok(())
+	}
+}
@@ -5,12 +5,16 @@ compile_error!("feature \"contract\" or feature \"parachain\" must be enabled");
 
 #[cfg(any(feature = "parachain", feature = "contract"))]
 mod commands;
+mod config;
+mod output;
 mod style;
 
 use anyhow::anyhow;
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use commands::*;
+use config::CommandConfig;
+use output::{Output, OutputFormat};
 #[cfg(feature = "telemetry")]
 use pop_telemetry::{config_file_path, record_cli_command, record_cli_used, Telemetry};
 use serde_json::{json, Value};
@@ -21,6 +25,17 @@ use std::{fs::create_dir_all, path::PathBuf};
 #[derive(Parser)]
 #[command(author, version, about, styles=style::get_styles())]
 pub struct Cli {
+	/// The format of the output: human-readable text, or a single JSON document with the result of
+	/// the command written to stdout, which implies `--non-interactive`.
+	#[arg(long, global = true, default_value = "text", value_parser = output::output_format_parser())]
+	output: OutputFormat,
+	/// Never prompt for input or clear the screen, failing instead when input is missing.
+	#[arg(long, global = true)]
+	non_interactive: bool,
+	/// The network profile configured in the `pop.toml` file of the project to use, providing the
+	/// arguments not specified on the command line [default: the `network` configured].
+	#[arg(long, global = true)]
+	network: Option<String>,
 	#[command(subcommand)]
 	command: Commands,
 }
@@ -32,6 +47,9 @@ enum Commands {
 	#[clap(alias = "n")]
 	#[cfg(any(feature = "parachain", feature = "contract"))]
 	New(new::NewArgs),
+	/// Manage the accounts used to sign transactions.
+	#[cfg(feature = "contract")]
+	Account(account::AccountArgs),
 	/// Build a parachain or smart contract.
 	#[clap(alias = "b")]
 	#[cfg(any(feature = "parachain", feature = "contract"))]
@@ -40,25 +58,119 @@ enum Commands {
 	#[clap(alias = "c")]
 	#[cfg(feature = "contract")]
 	Call(call::CallArgs),
+	/// Inspect the smart contracts deployed from a project.
+	#[cfg(feature = "contract")]
+	Contracts(contracts::ContractsArgs),
+	/// Subscribe to or query the events emitted by smart contracts.
+	#[cfg(feature = "contract")]
+	Events(events::EventsArgs),
 	/// Deploy a parachain or smart contract.
 	#[clap(alias = "u")]
 	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Up(up::UpArgs),
-	/// Test a smart contract.
-	#[clap(alias = "t")]
+	/// Stop a local node started in the background.
+	#[cfg(feature = "contract")]
+	Down(down::DownArgs),
+	/// Show the status of the local nodes started in the background.
+	#[cfg(feature = "contract")]
+	Status(status::StatusArgs),
+	/// Inspect the storage of a deployed smart contract.
+	#[cfg(feature = "contract")]
+	Storage(storage::StorageArgs),
+	/// Upgrade a deployed smart contract.
+	#[cfg(feature = "contract")]
+	Upgrade(upgrade::UpgradeArgs),
+	/// Profile the gas and storage deposit required by a smart contract.
 	#[cfg(feature = "contract")]
+	Profile(profile::ProfileArgs),
+	/// Run a scenario of smart contract deployments and calls.
+	#[cfg(feature = "contract")]
+	Run(run::RunArgs),
+	/// Test a parachain or smart contract.
+	#[clap(alias = "t")]
+	#[cfg(any(feature = "parachain", feature = "contract"))]
 	Test(test::TestArgs),
 	/// Set up the environment for development by installing required packages
 	#[clap(alias = "i")]
 	Install(install::InstallArgs),
 }
 
+impl Commands {
+	/// Resolves the subcommand to run when not specified, as detected from the project.
+	fn resolve(&mut self) -> Result<()> {
+		match self {
+			#[cfg(any(feature = "parachain", feature = "contract"))]
+			Commands::Up(args) => args.resolve(),
+			_ => Ok(()),
+		}
+	}
+
+	/// Applies the settings configured by the project to the arguments of the command not
+	/// specified on the command line. The configuration is only loaded for commands which use it.
+	///
+	/// # Arguments
+	///
+	/// * `load` - loads the settings configured for the command.
+	fn configure<'a>(&mut self, load: impl FnOnce() -> Result<CommandConfig<'a>>) -> Result<()> {
+		match self {
+			#[cfg(feature = "parachain")]
+			Commands::New(args) =>
+				if let new::NewCommands::Parachain(cmd) = &mut args.command {
+					cmd.configure(&load()?)
+				},
+			#[cfg(feature = "contract")]
+			Commands::Account(args) =>
+				if let account::AccountCommands::Transfer(cmd) = &mut args.command {
+					cmd.configure(&load()?)
+				},
+			#[cfg(feature = "contract")]
+			Commands::Call(args) => match &mut args.command {
+				call::CallCommands::Contract(cmd) => cmd.configure(&load()?),
+			},
+			#[cfg(feature = "contract")]
+			Commands::Events(args) => match &mut args.command {
+				events::EventsCommands::Contract(cmd) => cmd.configure(&load()?),
+			},
+			#[cfg(any(feature = "parachain", feature = "contract"))]
+			Commands::Up(args) => args.configure(load)?,
+			#[cfg(feature = "contract")]
+			Commands::Storage(args) => match &mut args.command {
+				storage::StorageCommands::Contract(cmd) => cmd.configure(&load()?),
+			},
+			#[cfg(feature = "contract")]
+			Commands::Upgrade(args) => match &mut args.command {
+				upgrade::UpgradeCommands::Contract(cmd) => cmd.configure(&load()?),
+			},
+			#[cfg(feature = "contract")]
+			Commands::Profile(args) => match &mut args.command {
+				profile::ProfileCommands::Contract(cmd) => cmd.configure(&load()?),
+			},
+			#[cfg(feature = "contract")]
+			Commands::Run(args) => args.configure(&load()?),
+			_ => {},
+		}
+		Ok(())
+	}
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
 	#[cfg(feature = "telemetry")]
 	let maybe_tel = init().unwrap_or(None);
 
-	let cli = Cli::parse();
+	let matches = Cli::command().get_matches();
+	let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+	output::init(Output { format: cli.output, non_interactive: cli.non_interactive });
+	let network = cli.network.clone();
+	let configured = cli
+		.command
+		.resolve()
+		.and_then(|_| cli.command.configure(|| CommandConfig::load(network.as_deref(), &matches)));
+	if let Err(e) = configured {
+		let res = Err(e);
+		output::emit(&res);
+		return res;
+	}
 	let res = match cli.command {
 		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::New(args) => match args.command {
@@ -77,34 +189,79 @@ async fn main() -> Result<()> {
 			},
 			#[cfg(feature = "contract")]
 			new::NewCommands::Contract(cmd) => {
-				// When more contract selections are added, the tel data will likely need to go deeper in the stack
-				cmd.execute().await.map(|_| json!("default"))
+				cmd.execute().await.map(|template| json!(template.to_string()))
 			},
 		},
+		#[cfg(feature = "contract")]
+		Commands::Account(args) => match &args.command {
+			account::AccountCommands::Add(cmd) => cmd.execute().map(|_| Value::Null),
+			account::AccountCommands::List(cmd) => cmd.execute().map(|_| Value::Null),
+			account::AccountCommands::Remove(cmd) => cmd.execute().map(|_| Value::Null),
+			account::AccountCommands::Transfer(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
 		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Build(args) => match &args.command {
 			#[cfg(feature = "parachain")]
-			build::BuildCommands::Parachain(cmd) => cmd.execute().map(|_| Value::Null),
+			Some(build::BuildCommands::Parachain(cmd)) => cmd.execute().map(|_| Value::Null),
 			#[cfg(feature = "contract")]
-			build::BuildCommands::Contract(cmd) => cmd.execute().map(|_| Value::Null),
+			Some(build::BuildCommands::Contract(cmd)) => cmd.execute().map(|_| Value::Null),
+			None => args.execute().map(|_| Value::Null),
 		},
 		#[cfg(feature = "contract")]
 		Commands::Call(args) => match &args.command {
 			call::CallCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
 		},
+		#[cfg(feature = "contract")]
+		Commands::Contracts(args) => match &args.command {
+			contracts::ContractsCommands::List(cmd) => cmd.execute().map(|_| Value::Null),
+		},
+		#[cfg(feature = "contract")]
+		Commands::Events(args) => match &args.command {
+			events::EventsCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
 		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Up(args) => match &args.command {
 			#[cfg(feature = "parachain")]
-			up::UpCommands::Parachain(cmd) => cmd.execute().await.map(|_| Value::Null),
+			Some(up::UpCommands::Parachain(cmd)) => cmd.execute().await.map(|_| Value::Null),
+			#[cfg(feature = "contract")]
+			Some(up::UpCommands::Contract(cmd)) => cmd.execute().await.map(|_| Value::Null),
 			#[cfg(feature = "contract")]
-			up::UpCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+			Some(up::UpCommands::ContractsNode(cmd)) => cmd.execute().await.map(|_| Value::Null),
+			// Resolved from the project before running.
+			None => Err(commands::project_not_found(&args.path, "up")),
+		},
+		#[cfg(feature = "contract")]
+		Commands::Down(args) => match &args.command {
+			down::DownCommands::ContractsNode(cmd) => cmd.execute().map(|_| Value::Null),
+		},
+		#[cfg(feature = "contract")]
+		Commands::Status(args) => match &args.command {
+			status::StatusCommands::ContractsNode(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(feature = "contract")]
+		Commands::Storage(args) => match &args.command {
+			storage::StorageCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(feature = "contract")]
+		Commands::Upgrade(args) => match &args.command {
+			upgrade::UpgradeCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
+		},
+		#[cfg(feature = "contract")]
+		Commands::Profile(args) => match &args.command {
+			profile::ProfileCommands::Contract(cmd) => cmd.execute().await.map(|_| Value::Null),
 		},
 		#[cfg(feature = "contract")]
+		Commands::Run(args) => args.execute().await.map(|_| Value::Null),
+		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Test(args) => match &args.command {
-			test::TestCommands::Contract(cmd) => match cmd.execute() {
+			#[cfg(feature = "parachain")]
+			Some(test::TestCommands::Parachain(cmd)) => cmd.execute().map(|feature| json!(feature)),
+			#[cfg(feature = "contract")]
+			Some(test::TestCommands::Contract(cmd)) => match cmd.execute().await {
 				Ok(feature) => Ok(json!(feature)),
 				Err(e) => Err(e),
 			},
+			None => args.execute().await.map(|_| json!("project")),
 		},
 		#[cfg(any(feature = "parachain", feature = "contract"))]
 		Commands::Install(args) => args.execute().await.map(|_| Value::Null),
@@ -128,6 +285,7 @@ async fn main() -> Result<()> {
 		}
 	}
 
+	output::emit(&res);
 	// map result from Result<Value> to Result<()>
 	res.map(|_| ())
 }
@@ -178,6 +336,23 @@ mod tests {
 		Cli::command().debug_assert()
 	}
 
+	#[test]
+	fn configuration_is_only_loaded_when_used() -> Result<()> {
+		let load = |args: &[&str]| -> Result<bool> {
+			let mut cli = Cli::parse_from(args);
+			let loaded = std::cell::Cell::new(false);
+			cli.command.configure(|| {
+				loaded.set(true);
+				Err(anyhow!("invalid configuration"))
+			})?;
+			Ok(loaded.get())
+		};
+		assert!(!load(&["pop", "install"])?);
+		#[cfg(feature = "contract")]
+		assert!(load(&["pop", "call", "contract", "--contract", "main"]).is_err());
+		Ok(())
+	}
+
 	#[test]
 	fn test_cache() -> Result<(), Box<dyn std::error::Error>> {
 		let path = cache()?;
@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::anyhow;
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use serde_json::{json, Value};
+use std::{
+	str::FromStr,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex, OnceLock,
+	},
+};
+use strum::{AsRefStr, EnumString, VariantArray};
+
+static OUTPUT: OnceLock<Output> = OnceLock::new();
+static RESULT: Mutex<Option<Value>> = Mutex::new(None);
+static EMITTED: AtomicBool = AtomicBool::new(false);
+static STREAMED: AtomicBool = AtomicBool::new(false);
+static CLEARED: AtomicBool = AtomicBool::new(false);
+
+/// The format in which the result of a command is output.
+#[derive(AsRefStr, Clone, Copy, Debug, Default, EnumString, Eq, PartialEq, VariantArray)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum OutputFormat {
+	/// Human-readable text, with prompts for any missing input.
+	#[default]
+	Text,
+	/// A single JSON document with the result of the command, written to stdout once the command
+	/// completes. Progress is still reported on stderr.
+	Json,
+}
+
+/// Parses the output format, listing the supported formats as possible values.
+pub(crate) fn output_format_parser() -> impl TypedValueParser<Value = OutputFormat> {
+	crate::enum_variants!(OutputFormat)
+}
+
+/// How a command interacts with the user and outputs its result.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Output {
+	/// The format in which the result of the command is output.
+	pub(crate) format: OutputFormat,
+	/// Whether prompting for input is disabled.
+	pub(crate) non_interactive: bool,
+}
+
+impl Output {
+	/// Whether the user may be prompted for input and the screen may be cleared. Outputting JSON
+	/// implies running non-interactively.
+	pub(crate) fn interactive(&self) -> bool {
+		!self.non_interactive && self.format == OutputFormat::Text
+	}
+
+	/// Fails when the user may not be prompted, so that a command reports the missing input rather
+	/// than waiting for input which never arrives.
+	///
+	/// # Arguments
+	///
+	/// * `input` - a description of the input the command would prompt for.
+	pub(crate) fn ensure_interactive(&self, input: &str) -> anyhow::Result<()> {
+		match self.interactive() {
+			true => Ok(()),
+			false => Err(anyhow!("Cannot prompt for {input} when running non-interactively")),
+		}
+	}
+
+	/// The JSON document output for the outcome of a command: its result, or the error with which
+	/// it failed along with any result recorded before failing.
+	///
+	/// # Arguments
+	///
+	/// * `result` - the result recorded by the command, if any.
+	/// * `error` - the error with which the command failed, if any.
+	pub(crate) fn document(result: Option<Value>, error: Option<&anyhow::Error>) -> Value {
+		let Some(error) = error else {
+			return result.unwrap_or(Value::Null);
+		};
+		let mut document = json!({ "error": format!("{error:#}") });
+		if let Some(result) = result {
+			document["result"] = result;
+		}
+		document
+	}
+}
+
+/// Sets how commands interact with the user and output their results, for the remainder of the
+/// process.
+///
+/// # Arguments
+///
+/// * `output` - how commands interact with the user and output their results.
+pub(crate) fn init(output: Output) {
+	let _ = OUTPUT.set(output);
+}
+
+/// How commands interact with the user and output their results.
+pub(crate) fn current() -> Output {
+	OUTPUT.get().copied().unwrap_or_default()
+}
+
+/// Whether the result of the command is output as JSON.
+pub(crate) fn is_json() -> bool {
+	current().format == OutputFormat::Json
+}
+
+/// Whether the user may be prompted for input.
+pub(crate) fn is_interactive() -> bool {
+	current().interactive()
+}
+
+/// Fails when the user may not be prompted for input.
+///
+/// # Arguments
+///
+/// * `input` - a description of the input the command would prompt for.
+pub(crate) fn ensure_interactive(input: &str) -> anyhow::Result<()> {
+	current().ensure_interactive(input)
+}
+
+/// Clears the terminal, unless running non-interactively. The terminal is only cleared once, so
+/// that commands run one after another, such as when building each project of a workspace, keep
+/// the output of those before them.
+pub(crate) fn clear_screen() -> std::io::Result<()> {
+	match is_interactive() && !CLEARED.swap(true, Ordering::SeqCst) {
+		true => cliclack::clear_screen(),
+		false => Ok(()),
+	}
+}
+
+/// Records the result of the command, which is output as JSON once the command completes.
+///
+/// # Arguments
+///
+/// * `result` - the result of the command.
+pub(crate) fn set_result(result: Value) -> anyhow::Result<()> {
+	*RESULT
+		.lock()
+		.map_err(|_| anyhow!("the result of the command could not be recorded"))? = Some(result);
+	Ok(())
+}
+
+/// Takes the result recorded by the command, such as to combine the results of several commands.
+pub(crate) fn take_result() -> Option<Value> {
+	RESULT.lock().ok().and_then(|mut r| r.take())
+}
+
+/// Writes an item of the result of a command which streams its results, such as events as they
+/// are emitted, to stdout as a line of JSON. Once streamed, no document is written for the result
+/// when the command completes, only for an error with which it fails.
+///
+/// # Arguments
+///
+/// * `item` - the item of the result.
+pub(crate) fn stream(item: Value) -> anyhow::Result<()> {
+	STREAMED.store(true, Ordering::SeqCst);
+	println!("{item}");
+	Ok(())
+}
+
+/// Writes the JSON document for the outcome of the command to stdout, when outputting JSON. The
+/// document is only written once, so that commands which keep running, such as a launched network,
+/// can output their result before they complete. The results of commands which streamed them are
+/// not written again, whereas an error is written as a line of JSON following them.
+///
+/// # Arguments
+///
+/// * `outcome` - the outcome of the command.
+pub(crate) fn emit<T>(outcome: &anyhow::Result<T>) {
+	if !is_json() || EMITTED.swap(true, Ordering::SeqCst) {
+		return;
+	}
+	let streamed = STREAMED.load(Ordering::SeqCst);
+	if streamed && outcome.is_ok() {
+		return;
+	}
+	let result = take_result();
+	let document = Output::document(result, outcome.as_ref().err());
+	let document = match streamed {
+		true => serde_json::to_string(&document),
+		false => serde_json::to_string_pretty(&document),
+	};
+	println!("{}", document.unwrap_or_default());
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn output_format_is_parsed() -> anyhow::Result<()> {
+		assert_eq!(OutputFormat::from_str("json")?, OutputFormat::Json);
+		assert_eq!(OutputFormat::from_str("text")?, OutputFormat::Text);
+		assert!(OutputFormat::from_str("yaml").is_err());
+		assert_eq!(OutputFormat::VARIANTS.len(), 2);
+		Ok(())
+	}
+
+	#[test]
+	fn interactive_works() {
+		assert!(Output::default().interactive());
+		let json = Output { format: OutputFormat::Json, non_interactive: false };
+		assert!(!json.interactive());
+		let non_interactive = Output { format: OutputFormat::Text, non_interactive: true };
+		assert!(!non_interactive.interactive());
+		assert_eq!(
+			non_interactive.ensure_interactive("the constructor").unwrap_err().to_string(),
+			"Cannot prompt for the constructor when running non-interactively"
+		);
+		assert!(Output::default().ensure_interactive("the constructor").is_ok());
+	}
+
+	#[test]
+	fn document_works() {
+		assert_eq!(
+			Output::document(Some(json!({ "address": "5C" })), None),
+			json!({ "address": "5C" })
+		);
+		assert_eq!(Output::document(None, None), Value::Null);
+		let error = anyhow!("failed").context("Scenario failed");
+		assert_eq!(
+			Output::document(None, Some(&error)),
+			json!({ "error": "Scenario failed: failed" })
+		);
+		assert_eq!(
+			Output::document(Some(json!({ "success": false })), Some(&error)),
+			json!({ "error": "Scenario failed: failed", "result": { "success": false } })
+		);
+	}
+}
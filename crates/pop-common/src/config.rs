@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::Error;
+use serde::{de::IgnoredAny, Deserialize};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+use url::Url;
+
+/// The name of the optional configuration file at the root of a project.
+pub const CONFIG_FILE: &str = "pop.toml";
+
+/// The configuration of a project, read from the optional `pop.toml` file at its root.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+	/// The network profile used when none is selected.
+	pub network: Option<String>,
+	/// Default settings for each command, by the name of the command, e.g. `call`.
+	#[serde(default)]
+	pub defaults: BTreeMap<String, Settings>,
+	/// Named network profiles, selected using `--network <name>`.
+	#[serde(default)]
+	pub networks: BTreeMap<String, Settings>,
+	/// The version of the contracts node pinned by the project, which is read when the node is
+	/// sourced.
+	#[serde(default)]
+	pub contracts_node: Option<IgnoredAny>,
+}
+
+/// Settings used by commands in place of arguments not specified on the command line.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Settings {
+	/// Websocket endpoint of a node.
+	pub url: Option<Url>,
+	/// Secret key URI of the signer, or the name of an account added using `pop account add`.
+	pub suri: Option<String>,
+	/// The Zombienet network configuration file.
+	pub file: Option<PathBuf>,
+	/// The version of Polkadot used for the relay chain, as per the release tag.
+	pub relay_chain: Option<String>,
+	/// The version of Polkadot used for system parachains, as per the release tag.
+	pub system_parachain: Option<String>,
+	/// The git repositories of the parachains used, with the version specified as #fragment.
+	pub parachains: Option<Vec<String>>,
+	/// The release tag of the template used to generate a parachain.
+	pub release_tag: Option<String>,
+}
+
+impl Settings {
+	/// Combines settings, using those of `other` where not specified by these settings.
+	///
+	/// # Arguments
+	///
+	/// * `other` - the settings used as a fallback.
+	pub fn or(self, other: Settings) -> Settings {
+		Settings {
+			url: self.url.or(other.url),
+			suri: self.suri.or(other.suri),
+			file: self.file.or(other.file),
+			relay_chain: self.relay_chain.or(other.relay_chain),
+			system_parachain: self.system_parachain.or(other.system_parachain),
+			parachains: self.parachains.or(other.parachains),
+			release_tag: self.release_tag.or(other.release_tag),
+		}
+	}
+}
+
+impl Config {
+	/// Loads the configuration of a project, which is empty if the project has no configuration
+	/// file.
+	///
+	/// # Arguments
+	///
+	/// * `project` - The path to the project.
+	pub fn load(project: &Path) -> Result<Self, Error> {
+		let path = project.join(CONFIG_FILE);
+		if !path.exists() {
+			return Ok(Self::default());
+		}
+		toml_edit::de::from_str(&fs::read_to_string(&path)?)
+			.map_err(|e| Error::Config(format!("{}: {e}", path.display())))
+	}
+
+	/// The settings of a command: those of the selected network profile, falling back to the
+	/// defaults of the command. Fails if the network profile selected is not configured.
+	///
+	/// # Arguments
+	///
+	/// * `command` - The name of the command, e.g. `call`.
+	/// * `network` - The name of the network profile selected, if any, in place of the profile
+	///   configured as the default.
+	pub fn settings(&self, command: &str, network: Option<&str>) -> Result<Settings, Error> {
+		let profile = match network.or(self.network.as_deref()) {
+			Some(name) => self.networks.get(name).cloned().ok_or_else(|| {
+				Error::Config(format!("the network `{name}` is not configured in {CONFIG_FILE}"))
+			})?,
+			None => Settings::default(),
+		};
+		Ok(profile.or(self.defaults.get(command).cloned().unwrap_or_default()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const CONFIG: &str = r#"
+network = "local"
+
+[defaults.call]
+suri = "//Bob"
+
+[defaults.up]
+file = "network.toml"
+
+[networks.local]
+url = "ws://localhost:9944"
+
+[networks.testnet-a]
+url = "wss://testnet-a.example.com"
+suri = "deployer"
+relay-chain = "v1.13.0"
+parachains = ["https://github.com/r0gue-io/pop-node#v0.1.0"]
+
+[contracts-node]
+version = "v0.41.0"
+"#;
+
+	#[test]
+	fn load_works() -> Result<()> {
+		let project = tempfile::tempdir()?;
+		assert_eq!(Config::load(project.path())?, Config::default());
+
+		fs::write(project.path().join(CONFIG_FILE), CONFIG)?;
+		let config = Config::load(project.path())?;
+		assert_eq!(config.network.as_deref(), Some("local"));
+		assert_eq!(config.defaults.len(), 2);
+		assert_eq!(config.networks["testnet-a"].relay_chain.as_deref(), Some("v1.13.0"));
+		assert!(config.contracts_node.is_some());
+
+		fs::write(project.path().join(CONFIG_FILE), "[networks.local]\nuri = \"ws://\"\n")?;
+		assert!(matches!(Config::load(project.path()), Err(Error::Config(_))));
+		// Unknown tables are rejected, such as a misspelt `[defaults]`.
+		fs::write(project.path().join(CONFIG_FILE), "[default.call]\nsuri = \"//Bob\"\n")?;
+		assert!(matches!(Config::load(project.path()), Err(Error::Config(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn settings_works() -> Result<()> {
+		let config: Config = toml_edit::de::from_str(CONFIG)?;
+		// The default network profile is used, falling back to the defaults of the command.
+		assert_eq!(
+			config.settings("call", None)?,
+			Settings {
+				url: Some(Url::parse("ws://localhost:9944")?),
+				suri: Some("//Bob".to_string()),
+				..Default::default()
+			}
+		);
+		// The network profile selected takes precedence over the defaults of the command.
+		let settings = config.settings("call", Some("testnet-a"))?;
+		assert_eq!(settings.url, Some(Url::parse("wss://testnet-a.example.com")?));
+		assert_eq!(settings.suri.as_deref(), Some("deployer"));
+		assert_eq!(
+			config.settings("up", Some("testnet-a"))?.file,
+			Some(PathBuf::from("network.toml"))
+		);
+		assert_eq!(Config::default().settings("up", None)?, Settings::default());
+		assert!(matches!(config.settings("call", Some("mainnet")), Err(Error::Config(_))));
+		Ok(())
+	}
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: GPL-3.0
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+	#[error("Anyhow error: {0}")]
+	AnyhowError(#[from] anyhow::Error),
+
+	#[error("Archive error: {0}")]
+	ArchiveError(String),
+
+	#[error("Checksum mismatch for {archive}: expected {expected}, found {found}")]
+	ChecksumMismatch { archive: String, expected: String, found: String },
+
+	#[error("Configuration error: {0}")]
+	Config(String),
+
+	#[error("a git error occurred: {0}")]
+	Git(String),
+
+	#[error("HTTP error: {0}")]
+	HttpError(#[from] reqwest::Error),
+
+	#[error("IO error: {0}")]
+	IO(#[from] std::io::Error),
+
+	#[error("Missing binary: {0}")]
+	MissingBinary(String),
+
+	#[error("ParseError error: {0}")]
+	ParseError(#[from] url::ParseError),
+
+	#[error("Test error: {0}")]
+	Test(String),
+
+	#[error("Unsupported platform: {arch} {os}")]
+	UnsupportedPlatform { arch: &'static str, os: &'static str },
+}
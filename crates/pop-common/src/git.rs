@@ -12,7 +12,14 @@ use url::Url;
 /// A helper for handling Git operations.
 pub struct Git;
 impl Git {
-	pub(crate) fn clone(url: &Url, working_dir: &Path, reference: Option<&str>) -> Result<()> {
+	/// Clone a Git repository.
+	///
+	/// # Arguments
+	///
+	/// * `url` - the URL of the repository to clone.
+	/// * `working_dir` - the location where the repository will be cloned.
+	/// * `reference` - if applicable, the branch, tag or commit to be checked out.
+	pub fn clone(url: &Url, working_dir: &Path, reference: Option<&str>) -> Result<()> {
 		let mut fo = FetchOptions::new();
 		if reference.is_none() {
 			fo.depth(1);
@@ -212,6 +219,29 @@ impl GitHub {
 		Ok(commit)
 	}
 
+	/// Retrieves the SHA-256 checksum published for an asset of a release, if available.
+	///
+	/// # Arguments
+	///
+	/// * `tag` - the tag of the release, where `None` is the latest release.
+	/// * `asset` - the name of the asset.
+	pub async fn asset_digest(&self, tag: Option<&str>, asset: &str) -> Result<Option<String>> {
+		let client = reqwest::ClientBuilder::new().user_agent(APP_USER_AGENT).build()?;
+		let response = client.get(self.api_release_url(tag)).send().await?.error_for_status()?;
+		let value = response.json::<serde_json::Value>().await?;
+		let digest = value
+			.get("assets")
+			.and_then(|v| v.as_array())
+			.and_then(|assets| {
+				assets.iter().find(|a| a.get("name").and_then(|n| n.as_str()) == Some(asset))
+			})
+			.and_then(|a| a.get("digest"))
+			.and_then(|v| v.as_str())
+			.and_then(|v| v.strip_prefix("sha256:"))
+			.map(|v| v.to_owned());
+		Ok(digest)
+	}
+
 	pub async fn get_repo_license(&self) -> Result<String> {
 		let client = reqwest::ClientBuilder::new().user_agent(APP_USER_AGENT).build()?;
 		let url = self.api_license_url();
@@ -230,6 +260,13 @@ impl GitHub {
 		format!("{}/repos/{}/{}/releases", self.api, self.org, self.name)
 	}
 
+	fn api_release_url(&self, tag: Option<&str>) -> String {
+		match tag {
+			Some(tag) => format!("{}/tags/{tag}", self.api_releases_url()),
+			None => format!("{}/latest", self.api_releases_url()),
+		}
+	}
+
 	fn api_tag_information(&self, tag_name: &str) -> String {
 		format!("{}/repos/{}/{}/git/ref/tags/{}", self.api, self.org, self.name, tag_name)
 	}
@@ -248,7 +285,7 @@ impl GitHub {
 		))?)
 	}
 
-	pub(crate) fn name(repo: &Url) -> Result<&str> {
+	pub fn name(repo: &Url) -> Result<&str> {
 		let path_segments = repo
 			.path_segments()
 			.map(|c| c.collect::<Vec<_>>())
@@ -362,6 +399,46 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn get_asset_digest() -> Result<(), Box<dyn std::error::Error>> {
+		let mut mock_server = Server::new_async().await;
+
+		let expected_payload = r#"{
+			"tag_name": "v0.41.0",
+			"assets": [
+				{
+					"name": "substrate-contracts-node-linux.tar.gz",
+					"digest": "sha256:b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+				},
+				{ "name": "substrate-contracts-node-mac-universal.tar.gz", "digest": null }
+			]
+		}"#;
+		let repo = GitHub::parse(BASE_PARACHAIN)?.with_api(mock_server.url());
+		let mock = mock_server
+			.mock(
+				"GET",
+				format!("/repos/{}/{}/releases/tags/v0.41.0", repo.org, repo.name).as_str(),
+			)
+			.with_status(200)
+			.with_header("content-type", "application/json")
+			.with_body(expected_payload)
+			.expect(2)
+			.create_async()
+			.await;
+		assert_eq!(
+			repo.asset_digest(Some("v0.41.0"), "substrate-contracts-node-linux.tar.gz")
+				.await?,
+			Some("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9".to_string())
+		);
+		assert_eq!(
+			repo.asset_digest(Some("v0.41.0"), "substrate-contracts-node-mac-universal.tar.gz")
+				.await?,
+			None
+		);
+		mock.assert_async().await;
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn get_repo_license() -> Result<(), Box<dyn std::error::Error>> {
 		let mut mock_server = Server::new_async().await;
@@ -392,6 +469,20 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_get_release_api_url() -> Result<(), Box<dyn std::error::Error>> {
+		let repo = GitHub::parse(POLKADOT_SDK)?;
+		assert_eq!(
+			repo.api_release_url(Some("polkadot-v1.11.0")),
+			"https://api.github.com/repos/paritytech/polkadot-sdk/releases/tags/polkadot-v1.11.0"
+		);
+		assert_eq!(
+			repo.api_release_url(None),
+			"https://api.github.com/repos/paritytech/polkadot-sdk/releases/latest"
+		);
+		Ok(())
+	}
+
 	#[test]
 	fn test_url_api_tag_information() -> Result<(), Box<dyn std::error::Error>> {
 		assert_eq!(
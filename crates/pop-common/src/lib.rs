@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: GPL-3.0
+#![doc = include_str!("../README.md")]
+pub mod config;
+pub mod errors;
+pub mod git;
+pub mod project;
+pub mod sourcing;
+pub mod test;
+
+pub use config::{Config, Settings, CONFIG_FILE};
+pub use errors::Error;
+pub use git::{Git, GitHub, Release};
+pub use project::{Project, NETWORK_FILE};
+pub use sourcing::Binary;
+pub use test::{
+	run_stoppable_tests, run_tests, TestHandle, TestOpts, TestReport, TestResult, TestStatus,
+};
+
+static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Trait for observing status updates.
+pub trait Status {
+	/// Update the observer with the provided `status`.
+	fn update(&self, status: &str);
+
+	/// Warn the observer of something it should be made aware of, which is reported as a status
+	/// update unless the observer handles warnings separately.
+	fn warn(&self, warning: &str) {
+		self.update(warning)
+	}
+}
+
+impl Status for () {
+	// no-op: status updates are ignored
+	fn update(&self, _: &str) {}
+}
+
+/// Determines the target triple of the current platform, as used to name the release archives of
+/// binaries.
+pub fn target() -> Result<&'static str, Error> {
+	use std::env::consts::*;
+
+	if OS == "windows" {
+		return Err(Error::UnsupportedPlatform { arch: ARCH, os: OS });
+	}
+
+	match ARCH {
+		"aarch64" =>
+			return match OS {
+				"macos" => Ok("aarch64-apple-darwin"),
+				_ => Ok("aarch64-unknown-linux-gnu"),
+			},
+		"x86_64" | "x86" =>
+			return match OS {
+				"macos" => Ok("x86_64-apple-darwin"),
+				_ => Ok("x86_64-unknown-linux-gnu"),
+			},
+		&_ => {},
+	}
+	Err(Error::UnsupportedPlatform { arch: ARCH, os: OS })
+}
+
+#[cfg(test)]
+mod tests {
+	use anyhow::Result;
+
+	#[test]
+	fn target_works() -> Result<()> {
+		use std::{process::Command, str};
+		let output = Command::new("rustc").arg("-vV").output()?;
+		let output = str::from_utf8(&output.stdout)?;
+		let target = output
+			.lines()
+			.find(|l| l.starts_with("host: "))
+			.map(|l| &l[6..])
+			.unwrap()
+			.to_string();
+		assert_eq!(super::target()?, target);
+		Ok(())
+	}
+}
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::Error;
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use toml_edit::{DocumentMut, Item};
+
+/// The name of the Zombienet network configuration file at the root of a parachain project.
+pub const NETWORK_FILE: &str = "network.toml";
+
+// The dependencies of the node or runtime of a parachain.
+const PARACHAIN_DEPENDENCIES: [&str; 6] = [
+	"cumulus-client-service",
+	"cumulus-pallet-parachain-system",
+	"frame-executive",
+	"polkadot-sdk",
+	"sc-cli",
+	"sc-service",
+];
+
+/// The kinds of project found at a path, as described by its manifest.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Project {
+	/// The paths of the contracts: the project itself, or the members of its workspace, which
+	/// depend on ink!.
+	pub contracts: Vec<PathBuf>,
+	/// The path of the parachain, when the project or a member of its workspace is a node or a
+	/// runtime.
+	pub parachain: Option<PathBuf>,
+	/// The paths of the packages of the parachain: its node and runtime, along with the other
+	/// packages of the project which are not contracts, such as its pallets.
+	pub parachain_packages: Vec<PathBuf>,
+	/// The Zombienet network configuration file of the project, if any.
+	pub network: Option<PathBuf>,
+}
+
+impl Project {
+	/// Detects the kinds of project at a path from its manifest and those of the members of its
+	/// workspace.
+	///
+	/// # Arguments
+	///
+	/// * `path` - The path to the project.
+	pub fn detect(path: &Path) -> Result<Self, Error> {
+		let mut project = Self::default();
+		let network = path.join(NETWORK_FILE);
+		if network.exists() {
+			project.network = Some(network);
+		}
+		let Some(manifest) = read_manifest(path)? else {
+			return Ok(project);
+		};
+		let mut packages = vec![(path.to_path_buf(), manifest.clone())];
+		for member in members(path, &manifest)? {
+			if let Some(manifest) = read_manifest(&member)? {
+				packages.push((member, manifest));
+			}
+		}
+		let mut parachain = false;
+		for (package, manifest) in packages {
+			if depends_on(&manifest, &["ink"]) {
+				project.contracts.push(package);
+				continue;
+			}
+			parachain |= depends_on(&manifest, &PARACHAIN_DEPENDENCIES);
+			if manifest.contains_key("package") {
+				project.parachain_packages.push(package);
+			}
+		}
+		if parachain {
+			project.parachain = Some(path.to_path_buf());
+		} else {
+			project.parachain_packages.clear();
+		}
+		Ok(project)
+	}
+}
+
+/// The features declared by the package at a path. The members of a workspace declare their own
+/// features.
+///
+/// # Arguments
+///
+/// * `path` - The path to the package.
+pub fn features(path: &Path) -> Result<Vec<String>, Error> {
+	let Some(manifest) = read_manifest(path)? else {
+		return Ok(Vec::new());
+	};
+	let mut features: Vec<String> = manifest
+		.get("features")
+		.and_then(Item::as_table_like)
+		.map(|f| f.iter().map(|(name, _)| name.to_string()).collect())
+		.unwrap_or_default();
+	features.sort();
+	Ok(features)
+}
+
+/// The name of the package at a path, if any.
+///
+/// # Arguments
+///
+/// * `path` - The path to the package.
+pub fn package_name(path: &Path) -> Result<Option<String>, Error> {
+	Ok(read_manifest(path)?
+		.and_then(|manifest| manifest.get("package")?.get("name")?.as_str().map(String::from)))
+}
+
+// The manifest of the package or workspace at a path, if any.
+fn read_manifest(path: &Path) -> Result<Option<DocumentMut>, Error> {
+	let path = path.join("Cargo.toml");
+	if !path.exists() {
+		return Ok(None);
+	}
+	fs::read_to_string(&path)?
+		.parse::<DocumentMut>()
+		.map(Some)
+		.map_err(|e| Error::Config(format!("{}: {e}", path.display())))
+}
+
+// The paths of the members of a workspace, expanding any glob patterns and omitting any excluded.
+fn members(path: &Path, manifest: &DocumentMut) -> Result<Vec<PathBuf>, Error> {
+	let patterns = |key: &str| -> Vec<String> {
+		manifest
+			.get("workspace")
+			.and_then(|w| w.get(key))
+			.and_then(Item::as_array)
+			.map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+			.unwrap_or_default()
+	};
+	let exclude: Vec<PathBuf> = patterns("exclude").iter().map(|e| path.join(e)).collect();
+	let mut members = Vec::new();
+	for pattern in patterns("members") {
+		let pattern = path.join(pattern);
+		let paths = glob::glob(&pattern.to_string_lossy())
+			.map_err(|e| Error::Config(format!("{}: {e}", pattern.display())))?;
+		members.extend(paths.flatten().filter(|p| p.is_dir() && !exclude.contains(p)));
+	}
+	Ok(members)
+}
+
+// Whether a package depends on any of the specified crates, including when renamed.
+fn depends_on(manifest: &DocumentMut, crates: &[&str]) -> bool {
+	let Some(dependencies) = manifest.get("dependencies").and_then(Item::as_table_like) else {
+		return false;
+	};
+	dependencies.iter().any(|(name, dependency)| {
+		let package = dependency.get("package").and_then(Item::as_str).unwrap_or(name);
+		crates.contains(&package)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn write_manifest(path: &Path, dependencies: &str) -> Result<()> {
+		fs::create_dir_all(path)?;
+		fs::write(
+			path.join("Cargo.toml"),
+			format!("[package]\nname = \"package\"\n\n[dependencies]\n{dependencies}\n"),
+		)?;
+		Ok(())
+	}
+
+	#[test]
+	fn detect_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert_eq!(Project::detect(path)?, Project::default());
+
+		// A contract.
+		write_manifest(path, "ink = { version = \"5.0.0\", default-features = false }")?;
+		assert_eq!(
+			Project::detect(path)?,
+			Project { contracts: vec![path.to_path_buf()], ..Default::default() }
+		);
+
+		// A workspace of a parachain and contracts.
+		fs::write(
+			path.join("Cargo.toml"),
+			"[workspace]\nmembers = [\"node\", \"runtime\", \"contracts/*\"]\nexclude = [\"contracts/template\"]\n",
+		)?;
+		write_manifest(&path.join("node"), "sc-cli = { workspace = true }")?;
+		write_manifest(
+			&path.join("runtime"),
+			"frame = { package = \"polkadot-sdk\", version = \"0.1\" }",
+		)?;
+		write_manifest(&path.join("contracts/flipper"), "ink = \"5.0.0\"")?;
+		write_manifest(&path.join("contracts/template"), "ink = \"5.0.0\"")?;
+		fs::write(path.join(NETWORK_FILE), "")?;
+		assert_eq!(
+			Project::detect(path)?,
+			Project {
+				contracts: vec![path.join("contracts/flipper")],
+				parachain: Some(path.to_path_buf()),
+				parachain_packages: vec![path.join("node"), path.join("runtime")],
+				network: Some(path.join(NETWORK_FILE)),
+			}
+		);
+		assert_eq!(package_name(&path.join("node"))?.as_deref(), Some("package"));
+		assert_eq!(package_name(path)?, None);
+
+		// A workspace of contracts and other packages, but no parachain.
+		fs::write(path.join("Cargo.toml"), "[workspace]\nmembers = [\"lib\", \"contracts/*\"]\n")?;
+		write_manifest(&path.join("lib"), "")?;
+		let project = Project::detect(path)?;
+		assert_eq!(project.parachain, None);
+		assert!(project.parachain_packages.is_empty());
+
+		fs::write(path.join("Cargo.toml"), "[workspace")?;
+		assert!(matches!(Project::detect(path), Err(Error::Config(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn features_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path();
+		assert!(features(path)?.is_empty());
+
+		write_manifest(path, "ink = \"5.0.0\"\n\n[features]\nstd = []\ne2e-tests = []")?;
+		assert_eq!(features(path)?, ["e2e-tests", "std"]);
+
+		// The members of a workspace declare their own features.
+		fs::write(path.join("Cargo.toml"), "[workspace]\nmembers = [\"node\", \"runtime\"]\n")?;
+		write_manifest(
+			&path.join("node"),
+			"sc-cli = \"0.46.0\"\n\n[features]\nruntime-benchmarks = []",
+		)?;
+		write_manifest(
+			&path.join("runtime"),
+			"frame-executive = \"38.0.0\"\n\n[features]\nstd = []\nruntime-benchmarks = []",
+		)?;
+		assert!(features(path)?.is_empty());
+		assert_eq!(features(&path.join("node"))?, ["runtime-benchmarks"]);
+		assert_eq!(features(&path.join("runtime"))?, ["runtime-benchmarks", "std"]);
+		Ok(())
+	}
+}
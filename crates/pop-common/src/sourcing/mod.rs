@@ -1,28 +1,32 @@
 // SPDX-License-Identifier: GPL-3.0
 use crate::{Error, Git, Status, APP_USER_AGENT};
+pub use binary::Binary;
 use duct::cmd;
 use flate2::read::GzDecoder;
 use reqwest::StatusCode;
-use std::time::Duration;
+use sha2::{Digest, Sha256};
 use std::{
 	fs::{copy, metadata, read_dir, rename, File},
 	io::{BufRead, Seek, SeekFrom, Write},
 	os::unix::fs::PermissionsExt,
 	path::{Path, PathBuf},
+	time::Duration,
 };
 use tar::Archive;
 use tempfile::{tempdir, tempfile};
 use url::Url;
 
+mod binary;
+
 /// The source of a binary.
 #[derive(Clone, Debug, PartialEq)]
-pub(crate) enum Source {
+pub enum Source {
 	/// An archive for download.
 	#[allow(dead_code)]
 	Archive {
 		/// The url of the archive.
 		url: String,
-		/// The archive contents required, including the binary name.
+		/// The archive contents required, including the binary name, as paths within the archive.
 		contents: Vec<String>,
 	},
 	/// A git repository.
@@ -56,7 +60,8 @@ impl Source {
 	/// # Arguments
 	///
 	/// * `cache` - the cache to be used.
-	/// * `release` - whether any binaries needing to be built should be done so using the release profile.
+	/// * `release` - whether any binaries needing to be built should be done so using the release
+	///   profile.
 	/// * `status` - used to observe status updates.
 	/// * `verbose` - whether verbose output is required.
 	pub(super) async fn source(
@@ -69,9 +74,11 @@ impl Source {
 		use Source::*;
 		match self {
 			Archive { url, contents } => {
-				let contents: Vec<_> =
-					contents.iter().map(|name| (name.as_str(), cache.join(&name))).collect();
-				from_archive(&url, &contents, status).await
+				let contents: Vec<_> = contents
+					.iter()
+					.map(|name| (name.as_str(), cache.join(file_name(name))))
+					.collect();
+				from_archive(&url, &contents, None, status).await
 			},
 			Git { url, reference, manifest, package, artifacts } => {
 				let artifacts: Vec<_> = artifacts
@@ -101,7 +108,7 @@ impl Source {
 
 /// A binary sourced from GitHub.
 #[derive(Clone, Debug, PartialEq)]
-pub(crate) enum GitHub {
+pub enum GitHub {
 	/// An archive for download from a GitHub release.
 	ReleaseArchive {
 		/// The owner of the repository - i.e. https://github.com/{owner}/repository.
@@ -114,10 +121,13 @@ pub(crate) enum GitHub {
 		tag_format: Option<String>,
 		/// The name of the archive (asset) to download.
 		archive: String,
-		/// The archive contents required, including the binary name.
+		/// The archive contents required, including the binary name, as paths within the archive.
 		contents: Vec<&'static str>,
 		/// If applicable, the latest release tag available.
 		latest: Option<String>,
+		/// If applicable, the expected SHA-256 checksum of the archive. The checksum published by
+		/// GitHub for the release asset is used otherwise, where available.
+		checksum: Option<String>,
 	},
 	/// A source code archive for download from GitHub.
 	SourceCodeArchive {
@@ -142,7 +152,8 @@ impl GitHub {
 	/// # Arguments
 	///
 	/// * `cache` - the cache to be used.
-	/// * `release` - whether any binaries needing to be built should be done so using the release profile.
+	/// * `release` - whether any binaries needing to be built should be done so using the release
+	///   profile.
 	/// * `status` - used to observe status updates.
 	/// * `verbose` - whether verbose output is required.
 	async fn source(
@@ -154,35 +165,68 @@ impl GitHub {
 	) -> Result<(), Error> {
 		use GitHub::*;
 		match self {
-			ReleaseArchive { owner, repository, tag, tag_format, archive, contents, .. } => {
+			ReleaseArchive {
+				owner,
+				repository,
+				tag,
+				tag_format,
+				archive,
+				contents,
+				checksum,
+				..
+			} => {
 				// Complete url and contents based on tag
 				let base_url = format!("https://github.com/{owner}/{repository}/releases");
-				let url = match tag.as_ref() {
-					Some(tag) => {
-						let tag = tag_format.as_ref().map_or_else(
-							|| tag.to_string(),
-							|tag_format| tag_format.replace("{tag}", tag),
-						);
-						format!("{base_url}/download/{tag}/{archive}")
-					},
+				let release = tag.as_ref().map(|tag| {
+					tag_format.as_ref().map_or_else(
+						|| tag.to_string(),
+						|tag_format| tag_format.replace("{tag}", tag),
+					)
+				});
+				let url = match release.as_ref() {
+					Some(release) => format!("{base_url}/download/{release}/{archive}"),
 					None => format!("{base_url}/latest/download/{archive}"),
 				};
 				let contents: Vec<_> = contents
 					.iter()
 					.map(|name| match tag.as_ref() {
-						Some(tag) => (*name, cache.join(&format!("{name}-{tag}"))),
-						None => (*name, cache.join(&name)),
+						Some(tag) => (*name, cache.join(format!("{}-{tag}", file_name(name)))),
+						None => (*name, cache.join(file_name(name))),
 					})
 					.collect();
-				from_archive(&url, &contents, status).await
+				let checksum = match checksum {
+					Some(checksum) => Some(checksum.clone()),
+					None => {
+						let repo = crate::GitHub::parse(&format!(
+							"https://github.com/{owner}/{repository}"
+						))?;
+						match repo.asset_digest(release.as_deref(), archive).await {
+							Ok(None) => {
+								status.warn(&format!(
+									"No checksum is published for {archive}, so it cannot be verified."
+								));
+								None
+							},
+							Ok(checksum) => checksum,
+							// The archive is still sourced when GitHub cannot be reached, such as
+							// when rate limited.
+							Err(e) => {
+								status.warn(&format!(
+									"The checksum published for {archive} could not be retrieved, so it cannot be verified: {e}"
+								));
+								None
+							},
+						}
+					},
+				};
+				from_archive(&url, &contents, checksum.as_deref(), status).await
 			},
 			SourceCodeArchive { owner, repository, reference, manifest, package, artifacts } => {
 				let artifacts: Vec<_> = artifacts
 					.iter()
 					.map(|name| match reference {
-						Some(reference) => {
-							(name.as_str(), cache.join(&format!("{name}-{reference}")))
-						},
+						Some(reference) =>
+							(name.as_str(), cache.join(&format!("{name}-{reference}"))),
 						None => (name.as_str(), cache.join(&name)),
 					})
 					.collect();
@@ -208,17 +252,25 @@ impl GitHub {
 /// # Arguments
 /// * `url` - The url of the archive.
 /// * `contents` - The contents within the archive which are required.
+/// * `checksum` - If applicable, the expected SHA-256 checksum of the archive, verified before the
+///   archive is extracted.
 /// * `status` - Used to observe status updates.
 async fn from_archive(
 	url: &str,
 	contents: &[(&str, PathBuf)],
+	checksum: Option<&str>,
 	status: &impl Status,
 ) -> Result<(), Error> {
 	// Download archive
 	status.update(&format!("Downloading from {url}..."));
 	let response = reqwest::get(url).await?.error_for_status()?;
+	let bytes = response.bytes().await?;
+	if let Some(expected) = checksum {
+		status.update("Verifying checksum...");
+		verify_checksum(url, &bytes, expected)?;
+	}
 	let mut file = tempfile()?;
-	file.write_all(&response.bytes().await?)?;
+	file.write_all(&bytes)?;
 	file.seek(SeekFrom::Start(0))?;
 	// Extract contents
 	status.update("Extracting from archive...");
@@ -234,6 +286,26 @@ async fn from_archive(
 	Ok(())
 }
 
+/// Verifies that the SHA-256 checksum of some downloaded contents matches the expected checksum.
+///
+/// # Arguments
+/// * `url` - The url the contents were downloaded from.
+/// * `contents` - The downloaded contents.
+/// * `expected` - The expected checksum, hex encoded.
+fn verify_checksum(url: &str, contents: &[u8], expected: &str) -> Result<(), Error> {
+	let found = format!("{:x}", Sha256::digest(contents));
+	let expected = expected.trim_start_matches("sha256:").to_lowercase();
+	if found != expected {
+		return Err(Error::ChecksumMismatch { archive: url.to_string(), expected, found });
+	}
+	Ok(())
+}
+
+// The name of a file within an archive, without the path to it.
+fn file_name(path: &str) -> &str {
+	Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path)
+}
+
 /// Source binary by cloning a git repository and then building.
 ///
 /// # Arguments
@@ -337,13 +409,13 @@ async fn from_github_archive(
 	// Prepare archive contents for build
 	let entries: Vec<_> = read_dir(&working_dir)?.take(2).filter_map(|x| x.ok()).collect();
 	match entries.len() {
-		0 => {
+		0 =>
 			return Err(Error::ArchiveError(
 				"The downloaded archive does not contain any entries.".into(),
-			))
-		},
+			)),
 		1 => working_dir = entries[0].path(), // Automatically switch to top level directory
-		_ => {}, // Assume that downloaded archive does not have a top level directory
+		// Assume that downloaded archive does not have a top level directory
+		_ => {},
 	}
 	// Build binaries
 	status.update("Starting build of binary...");
@@ -460,7 +532,8 @@ async fn download(url: &str, dest: &Path) -> Result<(), Error> {
 
 #[cfg(test)]
 pub(super) mod tests {
-	use super::{super::target, GitHub::*, *};
+	use super::{GitHub::*, *};
+	use crate::target;
 	use tempfile::tempdir;
 
 	#[tokio::test]
@@ -538,6 +611,7 @@ pub(super) mod tests {
 			archive,
 			contents: contents.to_vec(),
 			latest: None,
+			checksum: None,
 		})
 		.source(temp_dir.path(), true, &Output, true)
 		.await?;
@@ -565,6 +639,7 @@ pub(super) mod tests {
 			archive,
 			contents: contents.to_vec(),
 			latest: None,
+			checksum: None,
 		})
 		.source(temp_dir.path(), true, &Output, true)
 		.await?;
@@ -642,13 +717,35 @@ pub(super) mod tests {
 			.map(|b| (b, temp_dir.path().join(b)))
 			.collect();
 
-		from_archive(url, &contents, &Output).await?;
+		from_archive(url, &contents, None, &Output).await?;
 		for (_, file) in contents {
 			assert!(file.exists());
 		}
 		Ok(())
 	}
 
+	#[test]
+	fn verify_checksum_works() -> anyhow::Result<()> {
+		let url = "https://example.com/archive.tar.gz";
+		let checksum = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+		verify_checksum(url, b"hello world", checksum)?;
+		verify_checksum(url, b"hello world", &format!("sha256:{}", checksum.to_uppercase()))?;
+		assert!(matches!(
+			verify_checksum(url, b"hello world!", checksum),
+			Err(Error::ChecksumMismatch { expected, .. }) if expected == checksum
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn file_name_works() {
+		assert_eq!(file_name("polkadot"), "polkadot");
+		assert_eq!(
+			file_name("artifacts/substrate-contracts-node-linux/substrate-contracts-node"),
+			"substrate-contracts-node"
+		);
+	}
+
 	#[tokio::test]
 	async fn from_git_works() -> anyhow::Result<()> {
 		let url = "https://github.com/hpaluch/rust-hello-world";
@@ -755,11 +852,12 @@ pub(super) mod tests {
 	}
 }
 
-pub(crate) mod traits {
+pub mod traits {
 	use crate::Error;
 	use strum::EnumProperty;
 
-	pub(crate) trait Source: EnumProperty {
+	#[allow(async_fn_in_trait)]
+	pub trait Source: EnumProperty {
 		/// The name of the binary.
 		fn binary(&self) -> &'static str {
 			self.get_str("Binary").expect("expected specification of `Binary` name")
@@ -816,7 +914,7 @@ pub(crate) mod traits {
 	}
 
 	/// An attempted conversion into a Source.
-	pub(crate) trait TryInto {
+	pub trait TryInto {
 		/// Attempt the conversion.
 		///
 		/// # Arguments
@@ -0,0 +1,748 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{errors::Error, Status};
+use duct::{cmd, ReaderHandle};
+use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::{
+	fs,
+	io::{BufRead, BufReader},
+	path::Path,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+use strum_macros::{AsRefStr, Display};
+
+/// Options for running the tests of a project.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestOpts {
+	/// The packages to test, rather than those selected by default, such as those of the
+	/// parachain of a workspace which also has contracts.
+	pub packages: Vec<String>,
+	/// The features to be enabled, of the form `package/feature` for those of a package.
+	pub features: Vec<String>,
+	/// Only run the tests whose names contain this filter.
+	pub filter: Option<String>,
+	/// Skip the tests whose names contain any of these filters.
+	pub skip: Vec<String>,
+	/// Show the output of the tests as they run, rather than capturing it.
+	pub nocapture: bool,
+	/// Build and run the tests in release mode.
+	pub release: bool,
+}
+
+impl TestOpts {
+	// The arguments used to run the tests with `cargo`.
+	fn args(&self) -> Vec<String> {
+		let mut args = vec!["test".to_string()];
+		if self.release {
+			args.push("--release".to_string());
+		}
+		for package in &self.packages {
+			args.extend(["-p".to_string(), package.clone()]);
+		}
+		if !self.features.is_empty() {
+			args.push(format!("--features={}", self.features.join(",")));
+		}
+		if let Some(filter) = &self.filter {
+			args.push(filter.clone());
+		}
+		let mut harness_args = Vec::new();
+		for skip in &self.skip {
+			harness_args.extend(["--skip".to_string(), skip.clone()]);
+		}
+		if self.nocapture {
+			harness_args.push("--nocapture".to_string());
+		}
+		if !harness_args.is_empty() {
+			args.push("--".to_string());
+			args.extend(harness_args);
+		}
+		args
+	}
+}
+
+/// A handle through which tests can be stopped while they run, such as when they are interrupted.
+#[derive(Clone, Default)]
+pub struct TestHandle(Arc<Mutex<TestProcess>>);
+
+#[derive(Default)]
+struct TestProcess {
+	reader: Option<Arc<ReaderHandle>>,
+	stopped: bool,
+}
+
+impl TestHandle {
+	/// Stops the tests, killing the `cargo test` process if it has been started.
+	pub fn stop(&self) -> Result<(), Error> {
+		let mut process = self.0.lock().map_err(|e| Error::Test(e.to_string()))?;
+		process.stopped = true;
+		if let Some(reader) = &process.reader {
+			reader.kill()?;
+		}
+		Ok(())
+	}
+
+	// Tracks the process running the tests, returning `false` if the tests were already stopped.
+	fn attach(&self, reader: Arc<ReaderHandle>) -> Result<bool, Error> {
+		let mut process = self.0.lock().map_err(|e| Error::Test(e.to_string()))?;
+		process.reader = Some(reader);
+		Ok(!process.stopped)
+	}
+}
+
+/// The status of a test.
+#[derive(AsRefStr, Clone, Copy, Debug, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum TestStatus {
+	/// The test passed.
+	Passed,
+	/// The test failed.
+	Failed,
+	/// The test was ignored.
+	Ignored,
+}
+
+/// The result of a test.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TestResult {
+	/// The test target containing the test, e.g. `unittests src/lib.rs`.
+	pub suite: String,
+	/// The name of the test.
+	pub name: String,
+	/// The status of the test.
+	pub status: TestStatus,
+	/// The duration of the test: as reported by the test harness where available, otherwise the
+	/// time taken for its result to be reported.
+	#[serde(serialize_with = "as_secs")]
+	pub duration: Duration,
+	/// The output captured from the test, which the test harness only reports for failed tests.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<String>,
+}
+
+/// The results of running the tests of a project.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestReport {
+	/// The results of the tests, in the order reported.
+	pub tests: Vec<TestResult>,
+}
+
+impl TestReport {
+	/// The number of tests which passed.
+	pub fn passed(&self) -> usize {
+		self.count(TestStatus::Passed)
+	}
+
+	/// The number of tests which failed.
+	pub fn failed(&self) -> usize {
+		self.count(TestStatus::Failed)
+	}
+
+	/// The number of tests which were ignored.
+	pub fn ignored(&self) -> usize {
+		self.count(TestStatus::Ignored)
+	}
+
+	/// The total duration of the tests.
+	pub fn duration(&self) -> Duration {
+		self.tests.iter().map(|t| t.duration).sum()
+	}
+
+	/// Whether none of the tests failed.
+	pub fn success(&self) -> bool {
+		self.failed() == 0
+	}
+
+	/// A one-line summary of the results.
+	pub fn summary(&self) -> String {
+		format!(
+			"{} passed; {} failed; {} ignored; finished in {:.2}s",
+			self.passed(),
+			self.failed(),
+			self.ignored(),
+			self.duration().as_secs_f64()
+		)
+	}
+
+	/// The report as JSON.
+	pub fn to_json(&self) -> Result<String, Error> {
+		#[derive(Serialize)]
+		struct Report<'a> {
+			success: bool,
+			passed: usize,
+			failed: usize,
+			ignored: usize,
+			#[serde(serialize_with = "as_secs")]
+			duration: Duration,
+			tests: &'a [TestResult],
+		}
+
+		serde_json::to_string_pretty(&Report {
+			success: self.success(),
+			passed: self.passed(),
+			failed: self.failed(),
+			ignored: self.ignored(),
+			duration: self.duration(),
+			tests: &self.tests,
+		})
+		.map_err(|e| Error::Test(e.to_string()))
+	}
+
+	/// The report as JUnit XML, with a test suite for each test target.
+	pub fn to_junit(&self) -> String {
+		let mut suites: Vec<(&str, Vec<&TestResult>)> = Vec::new();
+		for test in &self.tests {
+			match suites.iter_mut().find(|(suite, _)| *suite == test.suite) {
+				Some((_, tests)) => tests.push(test),
+				None => suites.push((&test.suite, vec![test])),
+			}
+		}
+
+		let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+		xml.push_str(&format!(
+			"<testsuites name=\"cargo test\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+			self.tests.len(),
+			self.failed(),
+			self.ignored(),
+			self.duration().as_secs_f64()
+		));
+		for (suite, tests) in suites {
+			let count = |status| tests.iter().filter(|t| t.status == status).count();
+			let duration: Duration = tests.iter().map(|t| t.duration).sum();
+			xml.push_str(&format!(
+				"  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+				escape(suite),
+				tests.len(),
+				count(TestStatus::Failed),
+				count(TestStatus::Ignored),
+				duration.as_secs_f64()
+			));
+			for test in tests {
+				xml.push_str(&format!(
+					"    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+					escape(&test.name),
+					escape(suite),
+					test.duration.as_secs_f64()
+				));
+				match test.status {
+					TestStatus::Failed =>
+						xml.push_str("      <failure message=\"test failed\"/>\n"),
+					TestStatus::Ignored => xml.push_str("      <skipped/>\n"),
+					TestStatus::Passed => {},
+				}
+				if let Some(output) = &test.output {
+					xml.push_str(&format!("      <system-out>{}</system-out>\n", escape(output)));
+				}
+				xml.push_str("    </testcase>\n");
+			}
+			xml.push_str("  </testsuite>\n");
+		}
+		xml.push_str("</testsuites>\n");
+		xml
+	}
+
+	/// Writes the report to a file: as JUnit XML if the file has an `xml` extension, or as JSON
+	/// otherwise.
+	///
+	/// # Arguments
+	///
+	/// * `path` - The path of the file.
+	pub fn write(&self, path: &Path) -> Result<(), Error> {
+		let contents = match path.extension().is_some_and(|e| e == "xml") {
+			true => self.to_junit(),
+			false => self.to_json()?,
+		};
+		fs::write(path, contents)?;
+		Ok(())
+	}
+
+	fn count(&self, status: TestStatus) -> usize {
+		self.tests.iter().filter(|t| t.status == status).count()
+	}
+}
+
+/// Runs the tests of a project with `cargo test`, parsing the output of the test harness into the
+/// results of each test. Returns an error if the tests could not be run, such as when they fail to
+/// compile, but not when tests fail.
+///
+/// # Arguments
+///
+/// * `path` - The path to the project.
+/// * `opts` - Options for running the tests.
+/// * `env` - Environment variables exported to the tests.
+/// * `status` - An observer which receives each line of output as it is produced.
+pub fn run_tests(
+	path: &Path,
+	opts: &TestOpts,
+	env: &[(&str, String)],
+	status: &impl Status,
+) -> Result<TestReport, Error> {
+	run_stoppable_tests(path, opts, env, status, &TestHandle::default())
+}
+
+/// Runs the tests of a project with `cargo test`, as [`run_tests`], where the tests can be stopped
+/// through the handle provided.
+///
+/// # Arguments
+///
+/// * `path` - The path to the project.
+/// * `opts` - Options for running the tests.
+/// * `env` - Environment variables exported to the tests.
+/// * `status` - An observer which receives each line of output as it is produced.
+/// * `handle` - The handle through which the tests can be stopped.
+pub fn run_stoppable_tests(
+	path: &Path,
+	opts: &TestOpts,
+	env: &[(&str, String)],
+	status: &impl Status,
+	handle: &TestHandle,
+) -> Result<TestReport, Error> {
+	let mut command = cmd("cargo", opts.args()).dir(path).stderr_to_stdout().unchecked();
+	for (key, value) in env {
+		command = command.env(key, value);
+	}
+	let process = Arc::new(command.reader()?);
+	if !handle.attach(process.clone())? {
+		process.kill()?;
+		return Err(Error::Test("the tests were stopped".to_string()));
+	}
+	let mut reader = BufReader::new(&*process);
+	let mut parser = OutputParser::new();
+	let mut line = Vec::new();
+	while reader.read_until(b'\n', &mut line)? > 0 {
+		let text = String::from_utf8_lossy(&line);
+		let text = text.trim_end_matches(['\n', '\r']);
+		status.update(text);
+		parser.line(text, Instant::now());
+		line.clear();
+	}
+	let success = reader.get_ref().try_wait()?.is_some_and(|output| output.status.success());
+	let report = parser.finish()?;
+	if !success && report.success() {
+		return Err(Error::Test("cargo test command failed".to_string()));
+	}
+	Ok(report)
+}
+
+// Parses the output of the test harness, line by line, into the results of the tests.
+struct OutputParser {
+	// Matches the result of a test, along with its duration if reported. Output printed by other
+	// tests without a trailing newline may precede it.
+	result: Regex,
+	// Matches the start of a test whose output is not captured, which the test harness reports
+	// before running the test when the tests run one at a time, with the result following the
+	// output of the test.
+	start: Regex,
+	// Matches the result of a started test, following any output of the test.
+	status: Regex,
+	// Matches the summary of the results of a test target.
+	summary: Regex,
+	// The test target currently running.
+	suite: String,
+	// The index of the first result of the test target currently running.
+	suite_start: usize,
+	// When the last result was reported.
+	last: Instant,
+	results: Vec<TestResult>,
+	// The index of the test whose captured output is currently being read, along with the output.
+	capturing: Option<(usize, Vec<String>)>,
+	// The name of the test started but whose result is yet to be reported, along with its output.
+	started: Option<(String, Vec<String>)>,
+	// The test targets whose summary disagrees with the results parsed.
+	unparsed: Vec<String>,
+}
+
+impl OutputParser {
+	fn new() -> Self {
+		const STATUS: &str = r"(ok|FAILED|ignored)(?:, .*?)?(?: <([0-9.]+)s>)?$";
+		let regex = |regex: &str| Regex::new(regex).expect("valid regex; qed");
+		Self {
+			result: regex(&format!(r"test (.+) \.\.\. {STATUS}")),
+			start: regex(r"^test (.+?) \.\.\. (.*)$"),
+			status: regex(&format!(r"^(.*?){STATUS}")),
+			summary: regex(r"^test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored;"),
+			suite: String::new(),
+			suite_start: 0,
+			last: Instant::now(),
+			results: Vec::new(),
+			capturing: None,
+			started: None,
+			unparsed: Vec::new(),
+		}
+	}
+
+	fn line(&mut self, line: &str, now: Instant) {
+		let trimmed = line.trim();
+		if let Some(target) = trimmed.strip_prefix("Running ") {
+			self.end_capture();
+			// Omit the path of the test binary.
+			self.suite = match target.rfind(" (") {
+				Some(index) => target[..index].to_string(),
+				None => target.to_string(),
+			};
+		} else if trimmed.starts_with("Doc-tests ") {
+			self.end_capture();
+			self.suite = trimmed.to_string();
+		} else if line.starts_with("running ") && self.started.is_none() {
+			self.last = now;
+			self.suite_start = self.results.len();
+		} else if let Some(result) = self.result.captures(line) {
+			let status = (&result[2], result.get(3).map(|d| d.as_str()));
+			self.push(result[1].to_string(), status, None, now);
+		} else if let Some((name, mut output)) = self.started.take() {
+			match self.status.captures(line) {
+				Some(status) => {
+					output.push(status[1].to_string());
+					let result = (&status[2], status.get(3).map(|d| d.as_str()));
+					let output = output.join("\n").trim().to_string();
+					self.push(name, result, (!output.is_empty()).then_some(output), now);
+				},
+				None => {
+					output.push(line.to_string());
+					self.started = Some((name, output));
+				},
+			}
+		} else if let Some(start) = self.start.captures(line) {
+			let output = Some(start[2].to_string()).filter(|o| !o.is_empty());
+			self.started = Some((start[1].to_string(), output.into_iter().collect()));
+		} else if let Some(name) =
+			line.strip_prefix("---- ").and_then(|l| l.strip_suffix(" stdout ----"))
+		{
+			self.end_capture();
+			self.capturing = self
+				.results
+				.iter()
+				.rposition(|r| r.name == name && r.suite == self.suite)
+				.map(|index| (index, Vec::new()));
+		} else if let Some(summary) = self.summary.captures(line) {
+			self.end_capture();
+			let parsed = &self.results[self.suite_start..];
+			let count = |status| parsed.iter().filter(|t| t.status == status).count().to_string();
+			if [&summary[1], &summary[2], &summary[3]] !=
+				[
+					count(TestStatus::Passed),
+					count(TestStatus::Failed),
+					count(TestStatus::Ignored),
+				] {
+				self.unparsed.push(self.suite.clone());
+			}
+		} else if line == "failures:" {
+			self.end_capture();
+		} else if let Some((_, output)) = &mut self.capturing {
+			output.push(line.to_string());
+		}
+	}
+
+	// Records the result of a test.
+	fn push(
+		&mut self,
+		name: String,
+		status: (&str, Option<&str>),
+		output: Option<String>,
+		now: Instant,
+	) {
+		let duration = match status.1.and_then(|d| d.parse::<f64>().ok()) {
+			Some(secs) => Duration::from_secs_f64(secs),
+			None => now.saturating_duration_since(self.last),
+		};
+		let status = match status.0 {
+			"ok" => TestStatus::Passed,
+			"FAILED" => TestStatus::Failed,
+			_ => TestStatus::Ignored,
+		};
+		self.last = now;
+		self.results
+			.push(TestResult { suite: self.suite.clone(), name, status, duration, output });
+	}
+
+	fn end_capture(&mut self) {
+		if let Some((index, output)) = self.capturing.take() {
+			let output = output.join("\n").trim_end().to_string();
+			if !output.is_empty() {
+				self.results[index].output = Some(output);
+			}
+		}
+	}
+
+	// The results of the tests, failing if those of any test target could not all be parsed.
+	fn finish(mut self) -> Result<TestReport, Error> {
+		self.end_capture();
+		if !self.unparsed.is_empty() {
+			return Err(Error::Test(format!(
+				"the results of the tests of {} could not be parsed from the output of the test \
+				 harness",
+				self.unparsed.join(", ")
+			)));
+		}
+		Ok(TestReport { tests: self.results })
+	}
+}
+
+// Escapes text for use within XML.
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&apos;")
+}
+
+fn as_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_f64(duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const OUTPUT: &str = r#"   Compiling flipper v0.1.0 (/tmp/flipper)
+    Finished `test` profile [unoptimized + debuginfo] target(s) in 1.00s
+     Running unittests lib.rs (target/debug/deps/flipper-0123456789abcdef)
+
+running 3 tests
+test flipper::tests::default_works ... ok
+test flipper::tests::it_works ... FAILED
+test flipper::tests::slow ... ignored, requires a node
+
+failures:
+
+---- flipper::tests::it_works stdout ----
+thread 'flipper::tests::it_works' panicked at lib.rs:10:5:
+assertion failed: flipper.get()
+
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    flipper::tests::it_works
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+   Doc-tests flipper
+
+running 1 test
+test lib.rs - flipper (line 3) ... ok <0.250s>
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.25s
+"#;
+
+	fn parse(output: &str) -> TestReport {
+		let mut parser = OutputParser::new();
+		let now = Instant::now();
+		for line in output.lines() {
+			parser.line(line, now);
+		}
+		parser.finish().expect("results are parsed")
+	}
+
+	fn result(name: &str, status: TestStatus) -> TestResult {
+		TestResult {
+			suite: "unittests lib.rs".to_string(),
+			name: name.to_string(),
+			status,
+			duration: Duration::from_millis(500),
+			output: None,
+		}
+	}
+
+	#[test]
+	fn test_opts_args_works() {
+		assert_eq!(TestOpts::default().args(), vec!["test"]);
+		let opts = TestOpts {
+			packages: Vec::new(),
+			features: vec!["e2e-tests".to_string(), "std".to_string()],
+			filter: Some("flip".to_string()),
+			skip: vec!["e2e_tests::it_works".to_string()],
+			nocapture: true,
+			release: true,
+		};
+		assert_eq!(
+			opts.args(),
+			vec![
+				"test",
+				"--release",
+				"--features=e2e-tests,std",
+				"flip",
+				"--",
+				"--skip",
+				"e2e_tests::it_works",
+				"--nocapture"
+			]
+		);
+		let opts = TestOpts {
+			packages: vec!["node".to_string(), "runtime".to_string()],
+			features: vec!["runtime/std".to_string()],
+			..Default::default()
+		};
+		assert_eq!(
+			opts.args(),
+			vec!["test", "-p", "node", "-p", "runtime", "--features=runtime/std"]
+		);
+	}
+
+	#[test]
+	fn output_is_parsed() {
+		let report = parse(OUTPUT);
+		let names: Vec<_> = report
+			.tests
+			.iter()
+			.map(|t| (t.suite.as_str(), t.name.as_str(), t.status))
+			.collect();
+		assert_eq!(
+			names,
+			vec![
+				("unittests lib.rs", "flipper::tests::default_works", TestStatus::Passed),
+				("unittests lib.rs", "flipper::tests::it_works", TestStatus::Failed),
+				("unittests lib.rs", "flipper::tests::slow", TestStatus::Ignored),
+				("Doc-tests flipper", "lib.rs - flipper (line 3)", TestStatus::Passed),
+			]
+		);
+		assert_eq!(
+			report.tests[1].output.as_deref(),
+			Some(
+				"thread 'flipper::tests::it_works' panicked at lib.rs:10:5:\nassertion failed: \
+				 flipper.get()\n\nnote: run with `RUST_BACKTRACE=1` environment variable to \
+				 display a backtrace"
+			)
+		);
+		assert_eq!(report.tests[0].output, None);
+		// Durations reported by the test harness are used where available.
+		assert_eq!(report.tests[3].duration, Duration::from_millis(250));
+		assert_eq!((report.passed(), report.failed(), report.ignored()), (2, 1, 1));
+		assert!(!report.success());
+	}
+
+	#[test]
+	fn uncaptured_output_is_parsed() {
+		let report = parse(
+			r#"     Running unittests lib.rs (target/debug/deps/flipper-0123456789abcdef)
+
+running 3 tests
+test flipper::tests::default_works ... flipper created
+flipped
+ok
+test flipper::tests::it_works ... FAILED
+printed without a newlinetest flipper::tests::other ... ok
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+"#,
+		);
+		let names: Vec<_> = report.tests.iter().map(|t| (t.name.as_str(), t.status)).collect();
+		assert_eq!(
+			names,
+			vec![
+				("flipper::tests::default_works", TestStatus::Passed),
+				("flipper::tests::it_works", TestStatus::Failed),
+				("flipper::tests::other", TestStatus::Passed),
+			]
+		);
+		assert_eq!(report.tests[0].output.as_deref(), Some("flipper created\nflipped"));
+	}
+
+	#[test]
+	fn unparsed_results_fail() {
+		let mut parser = OutputParser::new();
+		let now = Instant::now();
+		for line in [
+			"running 2 tests",
+			"test a ... ok",
+			"unexpected output",
+			"test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out",
+		] {
+			parser.line(line, now);
+		}
+		assert!(
+			matches!(parser.finish(), Err(Error::Test(e)) if e.contains("could not be parsed"))
+		);
+	}
+
+	#[test]
+	fn durations_are_measured() {
+		let mut parser = OutputParser::new();
+		let start = Instant::now();
+		parser.line("running 2 tests", start);
+		parser.line("test a ... ok", start + Duration::from_millis(100));
+		parser.line("test b ... ok", start + Duration::from_millis(300));
+		let report = parser.finish().expect("results are parsed");
+		assert_eq!(report.tests[0].duration, Duration::from_millis(100));
+		assert_eq!(report.tests[1].duration, Duration::from_millis(200));
+	}
+
+	#[test]
+	fn to_json_works() -> Result<()> {
+		let report = TestReport {
+			tests: vec![result("a", TestStatus::Passed), result("b", TestStatus::Ignored)],
+		};
+		let json: serde_json::Value = serde_json::from_str(&report.to_json()?)?;
+		assert_eq!(
+			json,
+			serde_json::json!({
+				"success": true,
+				"passed": 1,
+				"failed": 0,
+				"ignored": 1,
+				"duration": 1.0,
+				"tests": [
+					{ "suite": "unittests lib.rs", "name": "a", "status": "passed", "duration": 0.5 },
+					{ "suite": "unittests lib.rs", "name": "b", "status": "ignored", "duration": 0.5 },
+				]
+			})
+		);
+		assert_eq!(report.summary(), "1 passed; 0 failed; 1 ignored; finished in 1.00s");
+		Ok(())
+	}
+
+	#[test]
+	fn to_junit_works() {
+		let mut failed = result("b<T>", TestStatus::Failed);
+		failed.output = Some("left != right & more".to_string());
+		let report = TestReport {
+			tests: vec![result("a", TestStatus::Passed), failed, result("c", TestStatus::Ignored)],
+		};
+		assert_eq!(
+			report.to_junit(),
+			r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="cargo test" tests="3" failures="1" skipped="1" time="1.500">
+  <testsuite name="unittests lib.rs" tests="3" failures="1" skipped="1" time="1.500">
+    <testcase name="a" classname="unittests lib.rs" time="0.500">
+    </testcase>
+    <testcase name="b&lt;T&gt;" classname="unittests lib.rs" time="0.500">
+      <failure message="test failed"/>
+      <system-out>left != right &amp; more</system-out>
+    </testcase>
+    <testcase name="c" classname="unittests lib.rs" time="0.500">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+		);
+	}
+
+	#[test]
+	fn write_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let report = TestReport { tests: vec![result("a", TestStatus::Passed)] };
+		let xml = temp_dir.path().join("report.xml");
+		report.write(&xml)?;
+		assert!(fs::read_to_string(&xml)?.starts_with("<?xml"));
+		let json = temp_dir.path().join("report.json");
+		report.write(&json)?;
+		assert!(fs::read_to_string(&json)?.starts_with('{'));
+		Ok(())
+	}
+
+	#[test]
+	fn stopped_tests_are_not_run() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let handle = TestHandle::default();
+		handle.stop()?;
+		assert!(matches!(
+			run_stoppable_tests(temp_dir.path(), &TestOpts::default(), &[], &(), &handle),
+			Err(Error::Test(e)) if e.contains("stopped")
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,461 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	utils::signer::{Keypair, Scheme},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use sp_core::Pair;
+use std::{
+	fs,
+	io::Write,
+	path::{Path, PathBuf},
+};
+use xsalsa20poly1305::{
+	aead::{Aead, KeyInit},
+	XSalsa20Poly1305,
+};
+
+/// The name of the file, within the pop configuration directory, in which accounts are stored.
+pub const ACCOUNTS_FILE: &str = "accounts.json";
+/// The environment variable from which the password used to unlock stored accounts is read.
+pub const PASSWORD_ENV: &str = "POP_ACCOUNT_PASSWORD";
+
+// The encoding of the keystore, as used by polkadot-js.
+const FORMAT: &str = "pkcs8";
+const ENCRYPTION: [&str; 2] = ["scrypt", "xsalsa20-poly1305"];
+const VERSION: &str = "3";
+// The parameters of the scrypt key derivation, as used by polkadot-js.
+const SCRYPT_LOG_N: u8 = 15;
+// The most costly scrypt key derivation accepted when unlocking an account, bounding the memory
+// and time an untrusted keystore can demand.
+const SCRYPT_MAX_LOG_N: u8 = 20;
+const SCRYPT_P: u32 = 1;
+const SCRYPT_R: u32 = 8;
+// The lengths of the components of an encoded keystore.
+const SALT_LENGTH: usize = 32;
+const SCRYPT_LENGTH: usize = SALT_LENGTH + 12;
+const NONCE_LENGTH: usize = 24;
+// The PKCS8 framing of the secret and public keys.
+const PKCS8_HEADER: [u8; 16] = [48, 83, 2, 1, 1, 48, 5, 6, 3, 43, 101, 112, 4, 34, 4, 32];
+const PKCS8_DIVIDER: [u8; 5] = [161, 35, 3, 33, 0];
+const SECRET_LENGTH: usize = 64;
+const SEED_LENGTH: usize = 32;
+
+/// The encoding of an encrypted account.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Encoding {
+	/// The format and scheme of the encrypted key.
+	pub content: Vec<String>,
+	/// The key derivation function and cipher used to encrypt the key.
+	#[serde(rename = "type")]
+	pub kind: Vec<String>,
+	/// The version of the encoding.
+	pub version: String,
+}
+
+/// Metadata of an account.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Meta {
+	/// The name of the account.
+	#[serde(default)]
+	pub name: String,
+	/// Any other metadata, such as provided by a polkadot-js keystore.
+	#[serde(flatten)]
+	pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An account whose key is encrypted with a password, stored in the format of a polkadot-js JSON
+/// keystore.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Account {
+	/// The address of the account.
+	pub address: String,
+	/// The encrypted key, encoded as base64.
+	pub encoded: String,
+	/// The encoding of the encrypted key.
+	pub encoding: Encoding,
+	/// Metadata of the account.
+	#[serde(default)]
+	pub meta: Meta,
+}
+
+impl Account {
+	/// Creates an account from a secret URI, such as a mnemonic phrase optionally followed by a
+	/// derivation path, encrypting its key with a password.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the account.
+	/// * `suri` - the secret URI of the account.
+	/// * `scheme` - the cryptographic scheme of the account.
+	/// * `password` - the password used to encrypt the key.
+	pub fn new(name: &str, suri: &str, scheme: Scheme, password: &str) -> Result<Self, Error> {
+		Self::encrypt(name, &Keypair::from_uri(suri, scheme)?, password, SCRYPT_LOG_N)
+	}
+
+	/// Imports an account from a polkadot-js JSON keystore, checking that it can be unlocked with
+	/// its password. The key remains encrypted with the password of the keystore.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the account.
+	/// * `keystore` - the contents of the JSON keystore.
+	/// * `password` - the password of the keystore.
+	pub fn import(name: &str, keystore: &str, password: &str) -> Result<Self, Error> {
+		let mut account: Account = serde_json::from_str(keystore)
+			.map_err(|e| Error::Account(format!("invalid keystore: {e}")))?;
+		account.unlock(password)?;
+		account.meta.name = name.to_string();
+		Ok(account)
+	}
+
+	/// The name of the account.
+	pub fn name(&self) -> &str {
+		&self.meta.name
+	}
+
+	/// The cryptographic scheme of the account.
+	pub fn scheme(&self) -> Result<Scheme, Error> {
+		match self.encoding.content.as_slice() {
+			[format, scheme] if format == FORMAT => scheme
+				.parse()
+				.map_err(|_| Error::Account(format!("unsupported scheme: {scheme}"))),
+			content => Err(Error::Account(format!("unsupported content: {}", content.join("/")))),
+		}
+	}
+
+	/// Decrypts the key of the account, returning the keypair used to sign extrinsics.
+	///
+	/// # Arguments
+	///
+	/// * `password` - the password used to encrypt the key.
+	pub fn unlock(&self, password: &str) -> Result<Keypair, Error> {
+		if self.encoding.version != VERSION || self.encoding.kind != ENCRYPTION {
+			return Err(Error::Account(format!(
+				"unsupported encoding: {} (version {})",
+				self.encoding.kind.join("/"),
+				self.encoding.version
+			)));
+		}
+		let scheme = self.scheme()?;
+		let encoded = STANDARD
+			.decode(&self.encoded)
+			.map_err(|e| Error::Account(format!("invalid encoding: {e}")))?;
+		if encoded.len() < SCRYPT_LENGTH + NONCE_LENGTH {
+			return Err(Error::Account("invalid encoding: too short".into()));
+		}
+		let (salt, params) = encoded[..SCRYPT_LENGTH].split_at(SALT_LENGTH);
+		let [n, p, r] = [0, 4, 8].map(|i| {
+			u32::from_le_bytes(params[i..i + 4].try_into().expect("four bytes per parameter"))
+		});
+		if !n.is_power_of_two() || n > 1 << SCRYPT_MAX_LOG_N || p != SCRYPT_P || r != SCRYPT_R {
+			return Err(Error::Account(format!(
+				"unsupported scrypt parameters: N={n}, p={p}, r={r}"
+			)));
+		}
+		let (nonce, ciphertext) = encoded[SCRYPT_LENGTH..].split_at(NONCE_LENGTH);
+		let plaintext = cipher(password, salt, n.trailing_zeros() as u8)?
+			.decrypt(nonce.into(), ciphertext)
+			.map_err(|_| {
+				Error::Account("unable to decrypt the account: invalid password".into())
+			})?;
+
+		// The secret is followed by the divider, and is either a full secret key or a seed.
+		let secret = plaintext
+			.strip_prefix(&PKCS8_HEADER[..])
+			.and_then(|key| {
+				[SECRET_LENGTH, SEED_LENGTH].into_iter().find_map(|length| {
+					key.get(length..)
+						.filter(|rest| rest.starts_with(&PKCS8_DIVIDER))
+						.map(|_| key[..length].to_vec())
+				})
+			})
+			.ok_or_else(|| Error::Account("invalid key format".into()))?;
+		fn pair<P: Pair>(seed: &[u8]) -> Result<P, Error> {
+			P::from_seed_slice(seed).map_err(|e| Error::KeyPairCreation(format!("{:?}", e)))
+		}
+		Ok(match scheme {
+			Scheme::Sr25519 => {
+				let mut secret = secret;
+				divide_scalar_by_cofactor(&mut secret[..32]);
+				Keypair::Sr25519(pair(&secret)?)
+			},
+			// The secret key of an ed25519 keypair is its seed followed by its public key.
+			Scheme::Ed25519 => Keypair::Ed25519(pair(&secret[..SEED_LENGTH])?),
+			Scheme::Ecdsa => Keypair::Ecdsa(pair(&secret)?),
+		})
+	}
+
+	// Encrypts the key of a keypair with a password, using the encoding of polkadot-js.
+	fn encrypt(name: &str, keypair: &Keypair, password: &str, log_n: u8) -> Result<Self, Error> {
+		let salt: [u8; SALT_LENGTH] = rand::random();
+		let nonce: [u8; NONCE_LENGTH] = rand::random();
+
+		let secret = match keypair {
+			Keypair::Sr25519(pair) => {
+				let mut secret = pair.to_raw_vec();
+				multiply_scalar_by_cofactor(&mut secret[..32]);
+				secret
+			},
+			Keypair::Ed25519(pair) => [pair.seed().as_slice(), &pair.public().0].concat(),
+			Keypair::Ecdsa(pair) => pair.seed().to_vec(),
+		};
+		let public = keypair.public();
+		let plaintext = [&PKCS8_HEADER[..], &secret, &PKCS8_DIVIDER, &public].concat();
+		let ciphertext = cipher(password, &salt, log_n)?
+			.encrypt(&nonce.into(), plaintext.as_slice())
+			.map_err(|_| Error::Account("unable to encrypt the account".into()))?;
+
+		let params: Vec<u8> = [1u32 << log_n, SCRYPT_P, SCRYPT_R]
+			.iter()
+			.flat_map(|param| param.to_le_bytes())
+			.collect();
+		Ok(Self {
+			address: keypair.account_id().to_string(),
+			encoded: STANDARD.encode([&salt[..], &params, &nonce, &ciphertext].concat()),
+			encoding: Encoding {
+				content: vec![FORMAT.to_string(), keypair.scheme().to_string()],
+				kind: ENCRYPTION.map(String::from).to_vec(),
+				version: VERSION.to_string(),
+			},
+			meta: Meta { name: name.to_string(), ..Default::default() },
+		})
+	}
+
+	#[cfg(test)]
+	pub(crate) fn testing(name: &str, keypair: &Keypair, password: &str) -> Result<Self, Error> {
+		// Cheap key derivation, to keep tests fast.
+		Self::encrypt(name, keypair, password, 4)
+	}
+}
+
+/// The accounts stored within a file, each encrypted with a password.
+#[derive(Debug)]
+pub struct Accounts {
+	path: PathBuf,
+	accounts: Vec<Account>,
+}
+
+impl Accounts {
+	/// Loads the accounts stored within a file, if it exists.
+	///
+	/// # Arguments
+	///
+	/// * `path` - location of the accounts file.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let accounts = match path.exists() {
+			true => serde_json::from_str(&fs::read_to_string(path)?)
+				.map_err(|e| Error::Account(format!("{}: {e}", path.display())))?,
+			false => Vec::new(),
+		};
+		Ok(Self { path: path.to_path_buf(), accounts })
+	}
+
+	/// Returns the stored accounts.
+	pub fn list(&self) -> &[Account] {
+		&self.accounts
+	}
+
+	/// Returns the account with the specified name, if any.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the account.
+	pub fn get(&self, name: &str) -> Option<&Account> {
+		self.accounts.iter().find(|account| account.name() == name)
+	}
+
+	/// Adds an account, failing if an account with the same name already exists.
+	///
+	/// # Arguments
+	///
+	/// * `account` - the account to be added.
+	pub fn add(&mut self, account: Account) -> Result<(), Error> {
+		if self.get(account.name()).is_some() {
+			return Err(Error::Account(format!(
+				"an account named `{}` already exists",
+				account.name()
+			)));
+		}
+		self.accounts.push(account);
+		Ok(())
+	}
+
+	/// Removes the account with the specified name, returning it.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the account.
+	pub fn remove(&mut self, name: &str) -> Result<Account, Error> {
+		let index = self
+			.accounts
+			.iter()
+			.position(|account| account.name() == name)
+			.ok_or_else(|| Error::Account(format!("no account named `{name}`")))?;
+		Ok(self.accounts.remove(index))
+	}
+
+	/// Saves the accounts to the file they were loaded from, readable only by the current user.
+	pub fn save(&self) -> Result<(), Error> {
+		if let Some(parent) = self.path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		let contents = serde_json::to_string_pretty(&self.accounts)
+			.map_err(|e| Error::Account(e.to_string()))?;
+		// The accounts are written to a temporary file, created readable only by the current user,
+		// which then replaces the file so that the accounts are never readable by others.
+		let dir = self
+			.path
+			.parent()
+			.filter(|p| !p.as_os_str().is_empty())
+			.unwrap_or(Path::new("."));
+		let mut file = tempfile::NamedTempFile::new_in(dir)?;
+		file.write_all(contents.as_bytes())?;
+		file.persist(&self.path).map_err(|e| Error::IO(e.error))?;
+		Ok(())
+	}
+}
+
+/// The location of the accounts file within the pop configuration directory.
+pub fn accounts_file() -> Result<PathBuf, Error> {
+	let config = dirs::config_dir().ok_or_else(|| {
+		Error::Account("the configuration directory could not be determined".into())
+	})?;
+	Ok(config.join("pop").join(ACCOUNTS_FILE))
+}
+
+// Creates the cipher used to encrypt keys, deriving its key from the password.
+fn cipher(password: &str, salt: &[u8], log_n: u8) -> Result<XSalsa20Poly1305, Error> {
+	let params = scrypt::Params::new(log_n, SCRYPT_R, SCRYPT_P, 64)
+		.map_err(|e| Error::Account(format!("invalid scrypt parameters: {e}")))?;
+	let mut key = [0u8; 64];
+	scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+		.map_err(|e| Error::Account(format!("unable to derive key: {e}")))?;
+	Ok(XSalsa20Poly1305::new(key[..32].into()))
+}
+
+// Converts a little-endian scalar to the ed25519 format used by polkadot-js keystores.
+fn multiply_scalar_by_cofactor(scalar: &mut [u8]) {
+	let mut high = 0;
+	for byte in scalar.iter_mut() {
+		let carry = *byte >> 5;
+		*byte = (*byte << 3) | high;
+		high = carry;
+	}
+}
+
+// Converts a little-endian scalar from the ed25519 format used by polkadot-js keystores.
+fn divide_scalar_by_cofactor(scalar: &mut [u8]) {
+	let mut low = 0;
+	for byte in scalar.iter_mut().rev() {
+		let carry = *byte & 0b111;
+		*byte = (*byte >> 3) | low;
+		low = carry << 5;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	fn alice() -> Result<Keypair> {
+		Ok(Keypair::from_uri("//Alice", Scheme::Sr25519)?)
+	}
+
+	#[test]
+	fn cofactor_conversion_works() {
+		let scalar: Vec<u8> = (0..32).map(|i| i * 7).collect();
+		let mut converted = scalar.clone();
+		// The scalar of a keypair is a multiple of the cofactor in the ed25519 format.
+		multiply_scalar_by_cofactor(&mut converted);
+		assert_eq!(converted[0] & 0b111, 0);
+		divide_scalar_by_cofactor(&mut converted);
+		assert_eq!(converted[..31], scalar[..31]);
+	}
+
+	#[test]
+	fn encrypted_account_unlocks_with_password() -> Result<()> {
+		let account = Account::testing("alice", &alice()?, "secret")?;
+		assert_eq!(account.name(), "alice");
+		assert_eq!(account.address, ALICE);
+		assert_eq!(account.unlock("secret")?.account_id().to_string(), ALICE);
+		assert!(matches!(account.unlock("wrong"), Err(Error::Account(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn costly_key_derivation_is_rejected() -> Result<()> {
+		let mut account = Account::testing("alice", &alice()?, "secret")?;
+		let mut encoded = STANDARD.decode(&account.encoded)?;
+		encoded[SALT_LENGTH..SALT_LENGTH + 4]
+			.copy_from_slice(&(1u32 << (SCRYPT_MAX_LOG_N + 1)).to_le_bytes());
+		account.encoded = STANDARD.encode(encoded);
+		assert!(matches!(
+			account.unlock("secret"),
+			Err(Error::Account(e)) if e.starts_with("unsupported scrypt parameters")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn accounts_of_any_scheme_can_be_encrypted() -> Result<()> {
+		for scheme in [Scheme::Ed25519, Scheme::Ecdsa] {
+			let keypair = Keypair::from_uri("//Alice", scheme)?;
+			let account = Account::testing("alice", &keypair, "secret")?;
+			assert_eq!(account.encoding.content, vec!["pkcs8".to_string(), scheme.to_string()]);
+			assert_eq!(account.scheme()?, scheme);
+			assert_eq!(account.address, keypair.account_id().to_string());
+			let unlocked = account.unlock("secret")?;
+			assert_eq!(unlocked.scheme(), scheme);
+			assert_eq!(unlocked.account_id(), keypair.account_id());
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn import_keystore_works() -> Result<()> {
+		let account = Account::testing("exported", &alice()?, "secret")?;
+		let keystore = serde_json::to_string(&account)?;
+		assert!(matches!(Account::import("alice", &keystore, "wrong"), Err(Error::Account(..))));
+		let imported = Account::import("alice", &keystore, "secret")?;
+		assert_eq!(imported.name(), "alice");
+		assert_eq!(imported.encoded, account.encoded);
+
+		let mut unsupported = account.clone();
+		unsupported.encoding.content = vec!["pkcs8".into(), "ethereum".into()];
+		let keystore = serde_json::to_string(&unsupported)?;
+		assert!(matches!(Account::import("alice", &keystore, "secret"), Err(Error::Account(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn accounts_can_be_added_and_removed() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("pop").join(ACCOUNTS_FILE);
+		let mut accounts = Accounts::load(&path)?;
+		assert!(accounts.list().is_empty());
+		accounts.add(Account::testing("alice", &alice()?, "secret")?)?;
+		assert!(matches!(
+			accounts.add(Account::testing("alice", &alice()?, "secret")?),
+			Err(Error::Account(..))
+		));
+		accounts.save()?;
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			assert_eq!(fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+		}
+
+		let mut accounts = Accounts::load(&path)?;
+		assert_eq!(accounts.get("alice").map(|a| a.address.as_str()), Some(ALICE));
+		assert_eq!(accounts.remove("alice")?.name(), "alice");
+		assert!(matches!(accounts.remove("alice"), Err(Error::Account(..))));
+		accounts.save()?;
+		assert!(Accounts::load(&path)?.list().is_empty());
+		Ok(())
+	}
+}
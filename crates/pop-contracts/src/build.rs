@@ -1,11 +1,64 @@
 // SPDX-License-Identifier: GPL-3.0
-use contract_build::{execute, BuildMode, ExecuteArgs};
-use std::path::PathBuf;
+use contract_build::{execute, BuildMode, BuildResult, ExecuteArgs};
+use serde::Serialize;
+use std::{fs, path::PathBuf};
 
 use crate::utils::helpers::get_manifest_path;
 
-/// Build the smart contract located at the specified `path` in `build_release` mode.
-pub fn build_smart_contract(path: &Option<PathBuf>, build_release: bool) -> anyhow::Result<String> {
+/// The artifacts of a contract build.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BuildArtifacts {
+	/// Whether the contract was built in release mode.
+	pub release: bool,
+	/// The directory to which the artifacts were written.
+	pub target_directory: PathBuf,
+	/// The Wasm code of the contract.
+	pub code: Option<PathBuf>,
+	/// The metadata of the contract.
+	pub metadata: Option<PathBuf>,
+	/// The bundle of the code and metadata of the contract.
+	pub bundle: Option<PathBuf>,
+	/// The size of the code in bytes, before optimization.
+	pub original_size: Option<u64>,
+	/// The size of the code in bytes.
+	pub code_size: Option<u64>,
+	/// The hash of the code.
+	pub code_hash: Option<String>,
+}
+
+impl BuildArtifacts {
+	// The artifacts of a build, as reported by `contract_build`, along with the size and hash of
+	// the code.
+	fn from_result(result: &BuildResult) -> anyhow::Result<Self> {
+		let code = match &result.dest_wasm {
+			Some(path) => Some(fs::read(path)?),
+			None => None,
+		};
+		Ok(Self {
+			release: result.build_mode != BuildMode::Debug,
+			target_directory: result.target_directory.clone(),
+			code: result.dest_wasm.clone(),
+			metadata: result.metadata_result.as_ref().map(|m| m.dest_metadata.clone()),
+			bundle: result.metadata_result.as_ref().map(|m| m.dest_bundle.clone()),
+			// Sizes are reported in kilobytes.
+			original_size: result
+				.optimization_result
+				.as_ref()
+				.map(|o| (o.original_size * 1000.0).round() as u64),
+			code_size: code.as_ref().map(|code| code.len() as u64),
+			code_hash: code
+				.as_ref()
+				.map(|code| sp_core::bytes::to_hex(&contract_build::code_hash(code), false)),
+		})
+	}
+}
+
+/// Build the smart contract located at the specified `path` in `build_release` mode, returning
+/// its artifacts.
+pub fn build_smart_contract(
+	path: &Option<PathBuf>,
+	build_release: bool,
+) -> anyhow::Result<BuildArtifacts> {
 	let manifest_path = get_manifest_path(path)?;
 
 	let build_mode = match build_release {
@@ -15,9 +68,55 @@ pub fn build_smart_contract(path: &Option<PathBuf>, build_release: bool) -> anyh
 	// Default values
 	let args = ExecuteArgs { manifest_path, build_mode, ..Default::default() };
 
-	// Execute the build and log the output of the build
+	// Execute the build and collect its artifacts
 	let result = execute(args)?;
-	let formatted_result = result.display();
+	BuildArtifacts::from_result(&result)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use contract_build::{MetadataArtifacts, OptimizationResult};
 
-	Ok(formatted_result)
+	#[test]
+	fn build_artifacts_from_result_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let code = temp_dir.path().join("flipper.wasm");
+		fs::write(&code, [0u8, 97, 115, 109])?;
+		let result = BuildResult {
+			dest_wasm: Some(code.clone()),
+			metadata_result: Some(MetadataArtifacts {
+				dest_metadata: temp_dir.path().join("flipper.json"),
+				dest_bundle: temp_dir.path().join("flipper.contract"),
+			}),
+			target_directory: temp_dir.path().to_path_buf(),
+			optimization_result: Some(OptimizationResult {
+				original_size: 10.5,
+				optimized_size: 0.004,
+			}),
+			build_mode: BuildMode::Release,
+			build_artifact: Default::default(),
+			verbosity: Default::default(),
+			image: None,
+			output_type: Default::default(),
+		};
+		assert_eq!(
+			BuildArtifacts::from_result(&result)?,
+			BuildArtifacts {
+				release: true,
+				target_directory: temp_dir.path().to_path_buf(),
+				code: Some(code),
+				metadata: Some(temp_dir.path().join("flipper.json")),
+				bundle: Some(temp_dir.path().join("flipper.contract")),
+				original_size: Some(10500),
+				code_size: Some(4),
+				code_hash: Some(sp_core::bytes::to_hex(
+					&contract_build::code_hash(&[0u8, 97, 115, 109]),
+					false
+				)),
+			}
+		);
+		Ok(())
+	}
 }
@@ -1,27 +1,38 @@
 // SPDX-License-Identifier: GPL-3.0
-use anyhow::Context;
-use contract_build::Verbosity;
 use contract_extrinsics::{
-	BalanceVariant, CallCommandBuilder, CallExec, DisplayEvents, ErrorVariant,
-	ExtrinsicOptsBuilder, TokenMetadata,
+	BalanceVariant, CallCommandBuilder, CallExec, ErrorVariant, ExtrinsicOptsBuilder, TokenMetadata,
 };
 use ink_env::{DefaultEnvironment, Environment};
+use serde::Serialize;
+use sp_core::bytes::to_hex;
+use sp_runtime::DispatchError;
 use sp_weights::Weight;
 use std::path::PathBuf;
-use subxt::{Config, PolkadotConfig as DefaultConfig};
-use subxt_signer::sr25519::Keypair;
+use subxt::{dynamic::Value, tx::DynamicPayload, Config, PolkadotConfig as DefaultConfig};
 use url::Url;
 
-use crate::utils::{
-	helpers::{get_manifest_path, parse_account, parse_balance},
-	signer::create_signer,
+use crate::{
+	deployments::Deployments,
+	errors::Error,
+	events::{decode_extrinsic_events, ExtrinsicEvent},
+	transaction::{submit_extrinsic, TxEvent, TxOpts},
+	utils::{
+		decode::{decode_message_return, ContractError},
+		helpers::{
+			deposit_charged, estimate_fee, get_manifest_path, parse_account, parse_balance,
+			parse_storage_deposit_limit, storage_deposit_limit_value, weight_value,
+		},
+		metadata::{validate_function_args, FunctionType},
+		signer::{create_signer, Keypair, Scheme},
+	},
 };
 
 /// Attributes for the `call` command.
 pub struct CallOpts {
 	/// Path to the contract build folder.
 	pub path: Option<PathBuf>,
-	/// The address of the the contract to call.
+	/// The address of the the contract to call, or the alias or name of a deployment recorded
+	/// within the contract project.
 	pub contract: String,
 	/// The name of the contract message to call.
 	pub message: String,
@@ -33,10 +44,17 @@ pub struct CallOpts {
 	pub gas_limit: Option<u64>,
 	/// Maximum proof size for the instantiation.
 	pub proof_size: Option<u64>,
+	/// Maximum storage deposit which may be charged for the call, e.g. `1000` or `1.5 UNIT`. If
+	/// not specified, the call may use all of the free balance of the account.
+	pub storage_deposit_limit: Option<String>,
 	/// Websocket endpoint of a node.
 	pub url: url::Url,
-	/// Secret key URI for the account deploying the contract.
+	/// Secret key URI for the account calling the contract.
 	pub suri: String,
+	/// The cryptographic scheme of the account calling the contract.
+	pub scheme: Scheme,
+	/// The password of the stored account, when `suri` is the name of an account.
+	pub password: Option<String>,
 	/// Submit an extrinsic for on-chain execution.
 	pub execute: bool,
 }
@@ -50,19 +68,30 @@ pub struct CallOpts {
 pub async fn set_up_call(
 	call_opts: CallOpts,
 ) -> anyhow::Result<CallExec<DefaultConfig, DefaultEnvironment, Keypair>> {
+	validate_function_args(
+		&call_opts.path,
+		&call_opts.message,
+		&call_opts.args,
+		FunctionType::Message,
+	)?;
 	let token_metadata = TokenMetadata::query::<DefaultConfig>(&call_opts.url).await?;
 	let manifest_path = get_manifest_path(&call_opts.path)?;
-	let signer = create_signer(&call_opts.suri)?;
+	let signer = create_signer(&call_opts.suri, call_opts.scheme, call_opts.password.as_deref())?;
 
 	let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
 		.manifest_path(Some(manifest_path))
 		.url(call_opts.url.clone())
+		.storage_deposit_limit(parse_storage_deposit_limit(
+			&call_opts.storage_deposit_limit,
+			&token_metadata,
+		)?)
 		.done();
 
 	let value: BalanceVariant<<DefaultEnvironment as Environment>::Balance> =
 		parse_balance(&call_opts.value)?;
 
-	let contract: <DefaultConfig as Config>::AccountId = parse_account(&call_opts.contract)?;
+	let contract: <DefaultConfig as Config>::AccountId =
+		resolve_contract(&call_opts.path, &call_opts.contract, &call_opts.url)?;
 
 	let call_exec: CallExec<DefaultConfig, DefaultEnvironment, Keypair> =
 		CallCommandBuilder::new(contract.clone(), &call_opts.message, extrinsic_opts)
@@ -75,6 +104,98 @@ pub async fn set_up_call(
 	return Ok(call_exec);
 }
 
+/// Resolves the address of a contract, falling back to the deployments recorded within the
+/// contract project when an alias or contract name is provided instead of an address.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract project.
+/// * `contract` - the address of the contract, or the alias or name of a deployment.
+/// * `url` - the websocket endpoint of the network.
+pub(crate) fn resolve_contract(
+	path: &Option<PathBuf>,
+	contract: &str,
+	url: &Url,
+) -> Result<<DefaultConfig as Config>::AccountId, Error> {
+	if let Ok(address) = parse_account(contract) {
+		return Ok(address);
+	}
+	let deployments = Deployments::load(path)?;
+	let deployment = deployments.resolve(contract, url).ok_or_else(|| {
+		Error::DeploymentNotFound { contract: contract.to_string(), url: url.to_string() }
+	})?;
+	parse_account(&deployment.address)
+}
+
+/// The result of a dry run of a contract message.
+#[derive(Clone, Debug, Serialize)]
+pub struct DryRunResult {
+	/// The value returned by the message decoded as JSON, or the reason the call reverted or
+	/// failed.
+	pub result: Result<serde_json::Value, ContractError>,
+	/// The output written to the debug buffer by the contract, e.g. via
+	/// `ink::env::debug_println!`.
+	pub debug_message: String,
+	/// The gas consumed by the call.
+	pub gas_consumed: Weight,
+	/// The gas required for the call to succeed, which may exceed the gas consumed.
+	pub gas_required: Weight,
+	/// The storage deposit charged by the call, which is zero if storage deposits are refunded.
+	pub storage_deposit: u128,
+}
+
+/// The result of a contract call submitted on-chain.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CallResult {
+	/// The hash of the block in which the call was included.
+	pub block_hash: String,
+	/// The gas used by the call, as reported by the chain once dispatched.
+	pub gas_used: Option<Weight>,
+	/// The storage deposit charged for the call, as reported by its events: the deposits held
+	/// less any released.
+	pub storage_deposit: u128,
+	/// The events raised by the call, with those emitted by the contract decoded using its
+	/// metadata.
+	pub events: Vec<ExtrinsicEvent>,
+}
+
+impl CallResult {
+	/// The result of a call, as derived from the events it raised.
+	///
+	/// # Arguments
+	///
+	/// * `block_hash` - the hash of the block in which the call was included.
+	/// * `events` - the events raised by the call.
+	pub fn from_events(block_hash: String, events: Vec<ExtrinsicEvent>) -> Self {
+		let gas_used = events.iter().find(|e| e.is("System", "ExtrinsicSuccess")).and_then(|e| {
+			let weight = e.field("dispatch_info")?.get("weight")?;
+			Some(Weight::from_parts(
+				weight.get("ref_time")?.as_u64()?,
+				weight.get("proof_size")?.as_u64()?,
+			))
+		});
+		let deposits = |name| -> u128 {
+			events
+				.iter()
+				.filter(|e| e.is("Contracts", name))
+				.filter_map(|e| balance(e.field("amount")?))
+				.sum()
+		};
+		let storage_deposit = deposits("StorageDepositTransferredAndHeld")
+			.saturating_sub(deposits("StorageDepositTransferredAndReleased"));
+		Self { block_hash, gas_used, storage_deposit, events }
+	}
+}
+
+// A balance decoded as JSON, which is a string if it exceeds the range of JSON numbers.
+fn balance(value: &serde_json::Value) -> Option<u128> {
+	match value {
+		serde_json::Value::Number(number) => number.as_u64().map(u128::from),
+		serde_json::Value::String(balance) => balance.parse().ok(),
+		_ => None,
+	}
+}
+
 /// Simulate a smart contract call without modifying the state of the blockchain.
 ///
 /// # Arguments
@@ -83,28 +204,24 @@ pub async fn set_up_call(
 ///
 pub async fn dry_run_call(
 	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
-) -> anyhow::Result<String> {
+) -> anyhow::Result<DryRunResult> {
 	let call_result = call_exec.call_dry_run().await?;
-	match call_result.result {
-        Ok(ref ret_val) => {
-            let value = call_exec
-				.transcoder()
-				.decode_message_return(
-					call_exec.message(),
-					&mut &ret_val.data[..],
-				)
-				.context(format!(
-					"Failed to decode return value {:?}",
-					&ret_val
-			))?;
-			Ok(value.to_string())
-        }
-        Err(ref _err) => {
-             Err(anyhow::anyhow!(
-                "Pre-submission dry-run failed. Add gas_limit and proof_size manually to skip this step."
-            ))
-        }
-    }
+	let result = match call_result.result {
+		Ok(ref ret_val) => decode_message_return(
+			call_exec.transcoder(),
+			call_exec.message(),
+			&ret_val.data,
+			ret_val.did_revert(),
+		),
+		Err(ref err) => Err(decode_dispatch_error(call_exec, err)),
+	};
+	Ok(DryRunResult {
+		result,
+		debug_message: String::from_utf8_lossy(&call_result.debug_message).to_string(),
+		gas_consumed: call_result.gas_consumed,
+		gas_required: call_result.gas_required,
+		storage_deposit: deposit_charged(&call_result.storage_deposit),
+	})
 }
 
 /// Estimate the gas required for a contract call without modifying the state of the blockchain.
@@ -116,6 +233,19 @@ pub async fn dry_run_call(
 pub async fn dry_run_gas_estimate_call(
 	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
 ) -> anyhow::Result<Weight> {
+	Ok(dry_run_estimate_call(call_exec).await?.0)
+}
+
+/// Estimate the gas and the storage deposit charged for a contract call without modifying the
+/// state of the blockchain.
+///
+/// # Arguments
+///
+/// * `call_exec` - the preprocessed data to call a contract.
+///
+pub async fn dry_run_estimate_call(
+	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> anyhow::Result<(Weight, u128)> {
 	let call_result = call_exec.call_dry_run().await?;
 	match call_result.result {
         Ok(_) => {
@@ -126,38 +256,108 @@ pub async fn dry_run_gas_estimate_call(
             let proof_size = call_exec
                 .proof_size()
                 .unwrap_or_else(|| call_result.gas_required.proof_size());
-            Ok(Weight::from_parts(ref_time, proof_size))
+            Ok((Weight::from_parts(ref_time, proof_size), deposit_charged(&call_result.storage_deposit)))
         }
-        Err(ref _err) => {
+        Err(ref err) => {
              Err(anyhow::anyhow!(
-                "Pre-submission dry-run failed. Add gas_limit and proof_size manually to skip this step."
+                "Pre-submission dry-run failed: {}. Add gas_limit and proof_size manually to skip this step.",
+                decode_dispatch_error(call_exec, err)
             ))
         }
     }
 }
 
-/// Call a smart contract on the blockchain.
+/// Estimate the fee charged for submitting a contract call, excluding any tip.
+///
+/// # Arguments
+///
+/// * `call_exec` - the preprocessed data to call a contract.
+/// * `gas_limit` - maximum amount of gas to be used for this call.
+///
+pub async fn estimate_call_fee(
+	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	gas_limit: Weight,
+) -> anyhow::Result<u128> {
+	let tx = call_payload(call_exec, gas_limit);
+	estimate_fee(call_exec.client(), &tx, call_exec.opts().signer()).await
+}
+
+// The extrinsic calling a contract, as prepared by `set_up_call`.
+fn call_payload(
+	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	gas_limit: Weight,
+) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Contracts",
+		"call",
+		vec![
+			Value::unnamed_variant("Id", [Value::from_bytes(call_exec.contract().0)]),
+			Value::u128(*call_exec.value()),
+			weight_value(gas_limit),
+			storage_deposit_limit_value(call_exec.opts().storage_deposit_limit()),
+			Value::from_bytes(call_exec.call_data()),
+		],
+	)
+}
+
+// Decodes an error which occurred while dispatching a contract call, resolving module errors using
+// the metadata of the chain.
+fn decode_dispatch_error(
+	call_exec: &CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	error: &DispatchError,
+) -> ContractError {
+	let metadata = call_exec.client().metadata();
+	ErrorVariant::from_dispatch_error(error, &metadata)
+		.map(ContractError::from)
+		.unwrap_or_else(|e| ContractError::Other(e.to_string()))
+}
+
+/// Call a smart contract on the blockchain, returning the result of the call.
 ///
 /// # Arguments
 ///
 /// * `call_exec` - struct with the call to be executed.
 /// * `gas_limit` - maximum amount of gas to be used for this call.
 /// * `url` - endpoint of the node which to send the call to.
+/// * `tx_opts` - options controlling the submission of the extrinsic.
+/// * `on_event` - called with each change in the status of the extrinsic.
 ///
 pub async fn call_smart_contract(
 	call_exec: CallExec<DefaultConfig, DefaultEnvironment, Keypair>,
 	gas_limit: Weight,
 	url: &Url,
-) -> anyhow::Result<String, ErrorVariant> {
-	let token_metadata = TokenMetadata::query::<DefaultConfig>(url).await?;
+	tx_opts: &TxOpts,
+	on_event: impl Fn(&TxEvent),
+) -> anyhow::Result<CallResult, ErrorVariant> {
+	let mutates = call_exec
+		.transcoder()
+		.metadata()
+		.spec()
+		.messages()
+		.iter()
+		.find(|message| message.label() == call_exec.message())
+		.is_some_and(|message| message.mutates());
+	if !mutates {
+		return Err(anyhow::anyhow!(
+			"Tried to execute a call on the immutable contract message '{}'. Please do a dry-run instead.",
+			call_exec.message()
+		)
+		.into());
+	}
 	let metadata = call_exec.client().metadata();
-	let events = call_exec.call(Some(gas_limit)).await?;
-	let display_events =
-		DisplayEvents::from_events::<DefaultConfig, DefaultEnvironment>(&events, None, &metadata)?;
-
-	let output =
-		display_events.display_events::<DefaultEnvironment>(Verbosity::Default, &token_metadata)?;
-	Ok(output)
+	let events = submit_extrinsic(
+		call_exec.client(),
+		url,
+		&call_payload(&call_exec, gas_limit),
+		call_exec.opts().signer(),
+		tx_opts,
+		on_event,
+	)
+	.await?;
+	Ok(CallResult::from_events(
+		to_hex(events.block_hash().as_ref(), false),
+		decode_extrinsic_events(&events, &metadata, Some(call_exec.transcoder()))?,
+	))
 }
 
 #[cfg(feature = "unit_contract")]
@@ -175,7 +375,11 @@ mod tests {
 		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		create_smart_contract(
+			"test_contract",
+			temp_contract_dir.as_path(),
+			&crate::ContractTemplate::Standard,
+		)?;
 		Ok(temp_dir)
 	}
 	fn build_smart_contract_test_environment(temp_dir: &TempDir) -> Result<(), Error> {
@@ -196,8 +400,11 @@ mod tests {
 			value: "1000".to_string(),
 			gas_limit: None,
 			proof_size: None,
+			storage_deposit_limit: None,
 			url: Url::parse(CONTRACTS_NETWORK_URL)?,
 			suri: "//Alice".to_string(),
+			scheme: Scheme::Sr25519,
+			password: None,
 			execute: false,
 		};
 		let call = set_up_call(call_opts).await?;
@@ -216,8 +423,11 @@ mod tests {
 			value: "1000".to_string(),
 			gas_limit: None,
 			proof_size: None,
+			storage_deposit_limit: None,
 			url: Url::parse(CONTRACTS_NETWORK_URL)?,
 			suri: "//Alice".to_string(),
+			scheme: Scheme::Sr25519,
+			password: None,
 			execute: false,
 		};
 		let call = set_up_call(call_opts).await;
@@ -237,8 +447,11 @@ mod tests {
 			value: "1000".to_string(),
 			gas_limit: None,
 			proof_size: None,
+			storage_deposit_limit: None,
 			url: Url::parse(CONTRACTS_NETWORK_URL)?,
 			suri: "//Alice".to_string(),
+			scheme: Scheme::Sr25519,
+			password: None,
 			execute: false,
 		};
 		let call = set_up_call(call_opts).await;
@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	utils::{helpers::get_manifest_path, signer::create_signer},
+	ContractInfo, UpOpts,
+};
+use contract_build::{CrateMetadata, Target};
+use serde::{Deserialize, Serialize};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use url::Url;
+
+/// The name of the file, within a contract project, in which its deployments are recorded.
+pub const DEPLOYMENTS_FILE: &str = "deployments.json";
+
+/// A record of a contract deployed to a network.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Deployment {
+	/// The name of the contract.
+	pub name: String,
+	/// An alias which can be used to refer to the deployment instead of its address.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub alias: Option<String>,
+	/// The websocket endpoint of the network the contract is deployed to.
+	pub url: Url,
+	/// The address of the contract.
+	pub address: String,
+	/// The hash of the contract code.
+	pub code_hash: Option<String>,
+	/// The constructor used to instantiate the contract.
+	pub constructor: String,
+	/// The arguments provided to the constructor.
+	pub args: Vec<String>,
+	/// The address of the account which deployed the contract.
+	pub deployer: String,
+	/// The number of the block in which the contract was instantiated, if recorded.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_number: Option<u32>,
+	/// The hash of the block in which the contract was instantiated.
+	pub block_hash: String,
+}
+
+/// The deployments of a contract project, recorded within the project.
+#[derive(Debug)]
+pub struct Deployments {
+	path: PathBuf,
+	deployments: Vec<Deployment>,
+}
+
+impl Deployments {
+	/// Loads the deployments recorded within a contract project.
+	///
+	/// # Arguments
+	///
+	/// * `path` - location of the contract project, defaulting to the current directory.
+	pub fn load(path: &Option<PathBuf>) -> Result<Self, Error> {
+		let path = path.as_deref().unwrap_or(Path::new("./")).join(DEPLOYMENTS_FILE);
+		let deployments = match path.exists() {
+			true => serde_json::from_str(&fs::read_to_string(&path)?)
+				.map_err(|e| Error::Deployments(format!("{}: {e}", path.display())))?,
+			false => Vec::new(),
+		};
+		Ok(Self { path, deployments })
+	}
+
+	/// Records a deployment, replacing the alias of any previous deployment to the same network
+	/// using it.
+	///
+	/// # Arguments
+	///
+	/// * `deployment` - the deployment to be recorded.
+	pub fn add(&mut self, deployment: Deployment) -> Result<(), Error> {
+		if let Some(alias) = &deployment.alias {
+			for existing in self.deployments.iter_mut().filter(|d| d.url == deployment.url) {
+				if existing.alias.as_ref() == Some(alias) {
+					existing.alias = None;
+				}
+			}
+		}
+		self.deployments.push(deployment);
+		self.save()
+	}
+
+	/// The recorded deployments, in the order in which they were deployed.
+	pub fn list(&self) -> &[Deployment] {
+		&self.deployments
+	}
+
+	/// Resolves the latest deployment to a network by its alias or contract name.
+	///
+	/// # Arguments
+	///
+	/// * `contract` - the alias or name of the contract.
+	/// * `url` - the websocket endpoint of the network.
+	pub fn resolve(&self, contract: &str, url: &Url) -> Option<&Deployment> {
+		let mut deployments = self.deployments.iter().rev().filter(|d| &d.url == url);
+		deployments
+			.clone()
+			.find(|d| d.alias.as_deref() == Some(contract))
+			.or_else(|| deployments.find(|d| d.name == contract))
+	}
+
+	/// Updates the code hash recorded for a deployed contract, following an upgrade of its code.
+	/// Returns whether the contract was deployed from the contract project.
+	///
+	/// # Arguments
+	///
+	/// * `address` - the address of the contract.
+	/// * `url` - the websocket endpoint of the network.
+	/// * `code_hash` - the hash of the new contract code.
+	pub fn set_code_hash(
+		&mut self,
+		address: &str,
+		url: &Url,
+		code_hash: &str,
+	) -> Result<bool, Error> {
+		let mut updated = false;
+		for deployment in
+			self.deployments.iter_mut().filter(|d| d.address == address && &d.url == url)
+		{
+			deployment.code_hash = Some(code_hash.to_string());
+			updated = true;
+		}
+		if updated {
+			self.save()?;
+		}
+		Ok(updated)
+	}
+
+	fn save(&self) -> Result<(), Error> {
+		let contents = serde_json::to_string_pretty(&self.deployments)
+			.map_err(|e| Error::Deployments(e.to_string()))?;
+		fs::write(&self.path, contents)?;
+		Ok(())
+	}
+}
+
+/// Records the deployment of a contract within the contract project.
+///
+/// # Arguments
+///
+/// * `up_opts` - attributes of the deployment.
+/// * `contract` - the instantiated contract.
+/// * `alias` - an optional alias to refer to the deployment.
+pub fn record_deployment(
+	up_opts: &UpOpts,
+	contract: &ContractInfo,
+	alias: Option<String>,
+) -> Result<Deployment, Error> {
+	let manifest_path = get_manifest_path(&up_opts.path)?;
+	let name = CrateMetadata::collect(&manifest_path, Target::Wasm)?.root_package.name;
+	let deployment = Deployment {
+		name,
+		alias,
+		url: up_opts.url.clone(),
+		address: contract.address.clone(),
+		code_hash: contract.code_hash.clone(),
+		constructor: up_opts.constructor.clone(),
+		args: up_opts.args.clone(),
+		deployer: create_signer(&up_opts.suri, up_opts.scheme, up_opts.password.as_deref())?
+			.account_id()
+			.to_string(),
+		block_number: Some(contract.block_number),
+		block_hash: contract.block_hash.clone(),
+	};
+	let project = manifest_path.directory().map(Path::to_path_buf);
+	Deployments::load(&project)?.add(deployment.clone())?;
+	Ok(deployment)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn deployment(name: &str, alias: Option<&str>, url: &str, address: &str) -> Deployment {
+		Deployment {
+			name: name.to_string(),
+			alias: alias.map(|a| a.to_string()),
+			url: Url::parse(url).unwrap(),
+			address: address.to_string(),
+			code_hash: Some(
+				"0x80776e60ab6a8b6e8b05b4ed8a5a5d3e2c1d9b5e4e4d3b4b5a3f7c4f9f8d6e1a".to_string(),
+			),
+			constructor: "new".to_string(),
+			args: vec!["false".to_string()],
+			deployer: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+			block_number: Some(42),
+			block_hash: "0x7b6b7a8a1a0ec6d9a2d1b9b1c0f0a7d4e3b8d9c6a5f4e3d2c1b0a9f8e7d6c5b4"
+				.to_string(),
+		}
+	}
+
+	#[test]
+	fn load_without_deployments_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let deployments = Deployments::load(&Some(temp_dir.path().to_path_buf()))?;
+		assert!(deployments.list().is_empty());
+		assert!(!temp_dir.path().join(DEPLOYMENTS_FILE).exists());
+		Ok(())
+	}
+
+	#[test]
+	fn add_and_load_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		let local = deployment("flipper", Some("main"), "ws://localhost:9944", "5Ca");
+		let remote = deployment("flipper", None, "wss://rpc.example.io", "5Cb");
+		let mut deployments = Deployments::load(&path)?;
+		deployments.add(local.clone())?;
+		deployments.add(remote.clone())?;
+		assert_eq!(Deployments::load(&path)?.list(), &[local, remote]);
+		Ok(())
+	}
+
+	#[test]
+	fn add_moves_alias_to_latest_deployment() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		let mut deployments = Deployments::load(&path)?;
+		deployments.add(deployment("flipper", Some("main"), "ws://localhost:9944", "5Ca"))?;
+		deployments.add(deployment("flipper", Some("main"), "wss://rpc.example.io", "5Cb"))?;
+		deployments.add(deployment("flipper", Some("main"), "ws://localhost:9944", "5Cc"))?;
+		let aliases: Vec<_> = deployments.list().iter().map(|d| d.alias.as_deref()).collect();
+		assert_eq!(aliases, [None, Some("main"), Some("main")]);
+		Ok(())
+	}
+
+	#[test]
+	fn resolve_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let local = Url::parse("ws://localhost:9944")?;
+		let mut deployments = Deployments::load(&Some(temp_dir.path().to_path_buf()))?;
+		deployments.add(deployment("flipper", Some("first"), local.as_str(), "5Ca"))?;
+		deployments.add(deployment("flipper", None, local.as_str(), "5Cb"))?;
+		deployments.add(deployment("erc20", Some("token"), "wss://rpc.example.io", "5Cc"))?;
+		// The latest deployment of a contract is resolved by its name.
+		assert_eq!(deployments.resolve("flipper", &local).map(|d| d.address.as_str()), Some("5Cb"));
+		assert_eq!(deployments.resolve("first", &local).map(|d| d.address.as_str()), Some("5Ca"));
+		// Deployments are specific to a network.
+		assert!(deployments.resolve("token", &local).is_none());
+		assert!(deployments.resolve("unknown", &local).is_none());
+		Ok(())
+	}
+
+	#[test]
+	fn set_code_hash_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		let local = Url::parse("ws://localhost:9944")?;
+		let mut deployments = Deployments::load(&path)?;
+		deployments.add(deployment("flipper", None, local.as_str(), "5Ca"))?;
+		deployments.add(deployment("flipper", None, "wss://rpc.example.io", "5Ca"))?;
+		assert!(deployments.set_code_hash("5Ca", &local, "0x01")?);
+		assert!(!deployments.set_code_hash("5Cb", &local, "0x01")?);
+		let code_hashes: Vec<_> =
+			Deployments::load(&path)?.list().iter().map(|d| d.code_hash.clone()).collect();
+		assert_eq!(code_hashes[0].as_deref(), Some("0x01"));
+		assert_ne!(code_hashes[1].as_deref(), Some("0x01"));
+		Ok(())
+	}
+
+	#[test]
+	fn load_fails_for_invalid_file() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::write(temp_dir.path().join(DEPLOYMENTS_FILE), "invalid")?;
+		assert!(matches!(
+			Deployments::load(&Some(temp_dir.path().to_path_buf())),
+			Err(Error::Deployments(_))
+		));
+		Ok(())
+	}
+}
@@ -18,6 +18,33 @@ pub enum Error {
 	#[error("Failed to parse account address: {0}")]
 	AccountAddressParsing(String),
 
+	#[error("Failed to get contract metadata: {0}")]
+	ContractMetadata(#[source] anyhow::Error),
+
+	#[error("Invalid constructor name: {0}")]
+	InvalidConstructorName(String),
+
+	#[error("Invalid message name: {0}")]
+	InvalidMessageName(String),
+
+	#[error("Incorrect number of arguments provided: expected {expected}, {provided} provided")]
+	IncorrectArguments { expected: usize, provided: usize },
+
+	#[error("Invalid value for `{param}`: expected {type_name}, e.g. {example}. {reason}")]
+	InvalidArgument { param: String, type_name: String, example: String, reason: String },
+
+	#[error("Invalid arguments: {0}")]
+	InvalidArguments(String),
+
+	#[error("Failed to read deployments: {0}")]
+	Deployments(String),
+
+	#[error("No deployment of `{contract}` found on {url}")]
+	DeploymentNotFound { contract: String, url: String },
+
+	#[error("Invalid storage field: {0}")]
+	InvalidStorageField(String),
+
 	#[error("Failed to get manifest path: {0}")]
 	ManifestPath(String),
 
@@ -27,6 +54,18 @@ pub enum Error {
 	#[error("Failed to create keypair from URI: {0}")]
 	KeyPairCreation(String),
 
+	#[error("Account error: {0}")]
+	Account(String),
+
+	#[error("Profile error: {0}")]
+	Profile(String),
+
+	#[error("Sandbox error: {0}")]
+	Sandbox(String),
+
+	#[error("Scenario error: {0}")]
+	Scenario(String),
+
 	#[error("Failed to parse hex encoded bytes: {0}")]
 	HexParsing(String),
 
@@ -36,8 +75,14 @@ pub enum Error {
 	#[error("Failed to run {0}")]
 	UpContractsNode(String),
 
-	#[error("Unsupported platform: {os}")]
-	UnsupportedPlatform { os: &'static str },
+	#[error("Failed to stop {0}")]
+	DownContractsNode(String),
+
+	#[error("Configuration error: {0}")]
+	Config(String),
+
+	#[error("{0}")]
+	CommonError(#[from] pop_common::Error),
 
 	#[error("Anyhow error: {0}")]
 	AnyhowError(#[from] anyhow::Error),
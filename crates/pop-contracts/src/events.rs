@@ -0,0 +1,410 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	call::resolve_contract,
+	utils::{decode::value_to_json, metadata::get_metadata},
+};
+use anyhow::anyhow;
+use contract_transcode::{ContractMessageTranscoder, TranscoderBuilder, Value};
+use serde::Serialize;
+use sp_core::bytes::to_hex;
+use std::path::PathBuf;
+use subxt::{
+	backend::{
+		legacy::{rpc_methods::NumberOrHex, LegacyRpcMethods},
+		rpc::RpcClient,
+	},
+	blocks::{Block, ExtrinsicEvents},
+	ext::codec::Decode,
+	Config, Metadata, OnlineClient, PolkadotConfig as DefaultConfig,
+};
+use url::Url;
+
+/// An event emitted by a contract.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ContractEvent {
+	/// The number of the block in which the event was emitted.
+	pub block_number: u32,
+	/// The hash of the block in which the event was emitted.
+	pub block_hash: String,
+	/// The address of the contract which emitted the event.
+	pub contract: String,
+	/// The name of the event, if it could be decoded using the contract metadata.
+	pub name: Option<String>,
+	/// The fields of the event decoded as JSON, or the raw event data as hex if it could not be
+	/// decoded, e.g. as it was emitted by another contract.
+	pub data: serde_json::Value,
+}
+
+/// An event raised by an extrinsic, such as a contract call.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExtrinsicEvent {
+	/// The name of the pallet which raised the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub name: String,
+	/// The fields of the event, in the order defined by the pallet.
+	pub fields: Vec<EventField>,
+}
+
+impl ExtrinsicEvent {
+	/// Whether the event is the specified event of the specified pallet.
+	///
+	/// # Arguments
+	///
+	/// * `pallet` - the name of the pallet.
+	/// * `name` - the name of the event.
+	pub fn is(&self, pallet: &str, name: &str) -> bool {
+		self.pallet == pallet && self.name == name
+	}
+
+	/// The value of a field of the event, if it has a field with the specified name.
+	///
+	/// # Arguments
+	///
+	/// * `name` - the name of the field.
+	pub fn field(&self, name: &str) -> Option<&serde_json::Value> {
+		self.fields.iter().find(|field| field.name == name).map(|field| &field.value)
+	}
+}
+
+/// A field of an event raised by an extrinsic.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EventField {
+	/// The name of the field, or its position if unnamed.
+	pub name: String,
+	/// The value of the field decoded as JSON. The data of an event emitted by a contract is
+	/// decoded using the metadata of the contract, keyed by the name of the event, or is the raw
+	/// data as hex if it could not be decoded.
+	pub value: serde_json::Value,
+}
+
+/// Criteria for the contract events to be returned.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+	/// Only return the events emitted by this contract: its address, or the alias or name of a
+	/// deployment recorded within the contract project.
+	pub contract: Option<String>,
+	/// Only return the events with this name.
+	pub event: Option<String>,
+}
+
+// A filter with the contract resolved to its address.
+struct ResolvedFilter {
+	contract: Option<String>,
+	event: Option<String>,
+}
+
+impl ResolvedFilter {
+	fn new(filter: &EventFilter, path: &Option<PathBuf>, url: &Url) -> anyhow::Result<Self> {
+		let contract = match &filter.contract {
+			Some(contract) => Some(resolve_contract(path, contract, url)?.to_string()),
+			None => None,
+		};
+		Ok(Self { contract, event: filter.event.clone() })
+	}
+
+	fn matches(&self, event: &ContractEvent) -> bool {
+		self.contract.as_ref().is_none_or(|contract| &event.contract == contract) &&
+			self.event.as_ref().is_none_or(|name| event.name.as_ref() == Some(name))
+	}
+}
+
+/// Queries the events emitted by contracts within a range of blocks, decoding them using the
+/// metadata of the contract.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract build folder or metadata file.
+/// * `url` - websocket endpoint of a node.
+/// * `from` - the number of the first block to be scanned.
+/// * `to` - the number of the last block to be scanned, defaulting to the latest block.
+/// * `filter` - criteria for the events to be returned.
+pub async fn query_events(
+	path: &Option<PathBuf>,
+	url: &Url,
+	from: u32,
+	to: Option<u32>,
+	filter: &EventFilter,
+) -> anyhow::Result<Vec<ContractEvent>> {
+	let transcoder = ContractMessageTranscoder::new(get_metadata(path)?);
+	let filter = ResolvedFilter::new(filter, path, url)?;
+	let client = OnlineClient::<DefaultConfig>::from_url(url).await?;
+	let rpc = LegacyRpcMethods::<DefaultConfig>::new(RpcClient::from_url(url).await?);
+	let to = match to {
+		Some(to) => to,
+		None => client.blocks().at_latest().await?.number(),
+	};
+
+	let mut events = Vec::new();
+	for number in from..=to {
+		let hash = rpc
+			.chain_get_block_hash(Some(NumberOrHex::Number(number.into())))
+			.await?
+			.ok_or_else(|| anyhow!("Block {number} not found"))?;
+		let block = client.blocks().at(hash).await?;
+		events.extend(block_events(&block, &transcoder, &filter).await?);
+	}
+	Ok(events)
+}
+
+/// Subscribes to the events emitted by contracts in new blocks, decoding them using the metadata of
+/// the contract. Runs until the subscription ends or handling an event fails.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract build folder or metadata file.
+/// * `url` - websocket endpoint of a node.
+/// * `filter` - criteria for the events to be returned.
+/// * `best` - whether to follow new best blocks, whose events may be reverted, rather than
+///   finalized blocks.
+/// * `on_event` - called with each event, in the order emitted.
+pub async fn subscribe_events(
+	path: &Option<PathBuf>,
+	url: &Url,
+	filter: &EventFilter,
+	best: bool,
+	mut on_event: impl FnMut(ContractEvent) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+	let transcoder = ContractMessageTranscoder::new(get_metadata(path)?);
+	let filter = ResolvedFilter::new(filter, path, url)?;
+	let client = OnlineClient::<DefaultConfig>::from_url(url).await?;
+	let mut blocks = match best {
+		true => client.blocks().subscribe_best().await?,
+		false => client.blocks().subscribe_finalized().await?,
+	};
+	while let Some(block) = blocks.next().await {
+		for event in block_events(&block?, &transcoder, &filter).await? {
+			on_event(event)?;
+		}
+	}
+	Ok(())
+}
+
+// Returns the contract events within a block which match the filter.
+async fn block_events(
+	block: &Block<DefaultConfig, OnlineClient<DefaultConfig>>,
+	transcoder: &ContractMessageTranscoder,
+	filter: &ResolvedFilter,
+) -> anyhow::Result<Vec<ContractEvent>> {
+	let mut contract_events = Vec::new();
+	for event in block.events().await?.iter() {
+		let event = event?;
+		if event.pallet_name() != "Contracts" || event.variant_name() != "ContractEmitted" {
+			continue;
+		}
+		let mut fields = event.field_bytes();
+		let contract = <DefaultConfig as Config>::AccountId::decode(&mut fields)?;
+		let (name, data) = decode_event(transcoder, event.topics(), fields);
+		let event = ContractEvent {
+			block_number: block.number(),
+			block_hash: to_hex(block.hash().as_ref(), false),
+			contract: contract.to_string(),
+			name,
+			data,
+		};
+		if filter.matches(&event) {
+			contract_events.push(event);
+		}
+	}
+	Ok(contract_events)
+}
+
+/// Decodes the events raised by an extrinsic using the metadata of the chain, decoding those
+/// emitted by contracts using the metadata of the contract where provided.
+///
+/// # Arguments
+///
+/// * `events` - the events raised by the extrinsic.
+/// * `metadata` - the metadata of the chain.
+/// * `transcoder` - the transcoder for the contract, if any.
+pub(crate) fn decode_extrinsic_events(
+	events: &ExtrinsicEvents<DefaultConfig>,
+	metadata: &Metadata,
+	transcoder: Option<&ContractMessageTranscoder>,
+) -> anyhow::Result<Vec<ExtrinsicEvent>> {
+	let types = metadata.types();
+	let decoder = TranscoderBuilder::new(types).with_default_custom_type_transcoders().done();
+	let mut decoded = Vec::new();
+	for event in events.iter() {
+		let event = event?;
+		let emitted =
+			event.pallet_name() == "Contracts" && event.variant_name() == "ContractEmitted";
+		let mut data = event.field_bytes();
+		let mut fields = Vec::new();
+		for (i, field) in event.event_metadata().variant.fields.iter().enumerate() {
+			let name = field.name.clone().unwrap_or_else(|| i.to_string());
+			let value = match emitted && name == "data" {
+				true => {
+					let start = data;
+					let raw = Vec::<u8>::decode(&mut data)?;
+					match transcoder {
+						Some(transcoder) => {
+							let encoded = &start[..start.len() - data.len()];
+							match decode_event(transcoder, event.topics(), encoded) {
+								(Some(event), value) => serde_json::json!({ event: value }),
+								(None, value) => value,
+							}
+						},
+						None => serde_json::Value::String(to_hex(&raw, false)),
+					}
+				},
+				false => value_to_json(&decoder.decode(types, field.ty.id, &mut data)?),
+			};
+			fields.push(EventField { name, value });
+		}
+		decoded.push(ExtrinsicEvent {
+			pallet: event.pallet_name().to_string(),
+			name: event.variant_name().to_string(),
+			fields,
+		});
+	}
+	Ok(decoded)
+}
+
+/// Decodes the data of a contract event, identified by its signature topic, returning the name of
+/// the event and its fields. The raw data is returned as hex if the event is not defined by the
+/// metadata, such as for anonymous events or events emitted by another contract.
+///
+/// # Arguments
+///
+/// * `transcoder` - the transcoder for the contract.
+/// * `topics` - the topics of the event, the first of which is its signature topic.
+/// * `data` - the SCALE encoded event data, including its length prefix.
+pub(crate) fn decode_event<Hash: AsRef<[u8]>>(
+	transcoder: &ContractMessageTranscoder,
+	topics: &[Hash],
+	data: &[u8],
+) -> (Option<String>, serde_json::Value) {
+	if let Some(signature_topic) = topics.first() {
+		if let Ok(value) = transcoder.decode_contract_event(signature_topic, &mut &data[..]) {
+			let name = match &value {
+				Value::Map(map) => map.ident(),
+				_ => None,
+			};
+			return (name, value_to_json(&value));
+		}
+	}
+	let raw = Vec::<u8>::decode(&mut &data[..]).unwrap_or_else(|_| data.to_vec());
+	(None, serde_json::Value::String(to_hex(&raw, false)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::CallResult;
+	use anyhow::Result;
+	use serde_json::json;
+	use sp_core::bytes::from_hex;
+	use sp_weights::Weight;
+	use subxt::{ext::codec::Encode, utils::AccountId32};
+
+	const FLIPPED_TOPIC: &str =
+		"0x123875361e0df990c7c906aadfb50ee931d9a0053ccd50be45ccab86174d8cb9";
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	fn testing_transcoder() -> Result<ContractMessageTranscoder> {
+		let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/files/testing.json");
+		Ok(ContractMessageTranscoder::new(get_metadata(&Some(path))?))
+	}
+
+	// Encodes event data as emitted by a contract.
+	fn encode_event_data(fields: impl Encode) -> Vec<u8> {
+		fields.encode().encode()
+	}
+
+	fn event(contract: &str, name: Option<&str>) -> ContractEvent {
+		ContractEvent {
+			block_number: 1,
+			block_hash: "0x00".to_string(),
+			contract: contract.to_string(),
+			name: name.map(|n| n.to_string()),
+			data: json!(null),
+		}
+	}
+
+	fn extrinsic_event(pallet: &str, name: &str, fields: serde_json::Value) -> ExtrinsicEvent {
+		let fields = fields.as_object().cloned().unwrap_or_default();
+		ExtrinsicEvent {
+			pallet: pallet.to_string(),
+			name: name.to_string(),
+			fields: fields.into_iter().map(|(name, value)| EventField { name, value }).collect(),
+		}
+	}
+
+	#[test]
+	fn decode_event_works() -> Result<()> {
+		let transcoder = testing_transcoder()?;
+		let alice: AccountId32 = ALICE.parse()?;
+		let data = encode_event_data((true, alice));
+		assert_eq!(
+			decode_event(&transcoder, &[from_hex(FLIPPED_TOPIC)?], &data),
+			(Some("Flipped".to_string()), json!({ "value": true, "by": ALICE }))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn decode_event_returns_raw_data_for_unknown_events() -> Result<()> {
+		let transcoder = testing_transcoder()?;
+		let data = encode_event_data(42u32);
+		let unknown_topic = [0u8; 32];
+		assert_eq!(decode_event(&transcoder, &[unknown_topic], &data), (None, json!("0x2a000000")));
+		// Anonymous events have no signature topic.
+		assert_eq!(decode_event::<[u8; 32]>(&transcoder, &[], &data), (None, json!("0x2a000000")));
+		Ok(())
+	}
+
+	#[test]
+	fn extrinsic_event_works() {
+		let event = extrinsic_event("Contracts", "Called", json!({ "caller": ALICE }));
+		assert!(event.is("Contracts", "Called"));
+		assert!(!event.is("Contracts", "Instantiated"));
+		assert_eq!(event.field("caller"), Some(&json!(ALICE)));
+		assert_eq!(event.field("contract"), None);
+	}
+
+	#[test]
+	fn call_result_from_events_works() {
+		let events = vec![
+			extrinsic_event(
+				"Contracts",
+				"StorageDepositTransferredAndHeld",
+				json!({ "amount": 500 }),
+			),
+			extrinsic_event(
+				"Contracts",
+				"StorageDepositTransferredAndHeld",
+				json!({ "amount": "300" }),
+			),
+			extrinsic_event(
+				"Contracts",
+				"StorageDepositTransferredAndReleased",
+				json!({ "amount": 100 }),
+			),
+			extrinsic_event(
+				"System",
+				"ExtrinsicSuccess",
+				json!({ "dispatch_info": { "weight": { "ref_time": 1000, "proof_size": 10 } } }),
+			),
+		];
+		let result = CallResult::from_events("0x01".to_string(), events.clone());
+		assert_eq!(result.gas_used, Some(Weight::from_parts(1000, 10)));
+		assert_eq!(result.storage_deposit, 700);
+		assert_eq!(result.events, events);
+		// Deposits released in excess of those held are not charged.
+		let result = CallResult::from_events("0x01".to_string(), events[2..].to_vec());
+		assert_eq!(result.storage_deposit, 0);
+		assert_eq!(CallResult::from_events("0x01".to_string(), vec![]).gas_used, None);
+	}
+
+	#[test]
+	fn filter_matches_works() {
+		let filter = ResolvedFilter { contract: None, event: None };
+		assert!(filter.matches(&event("5Ca", None)));
+		let filter = ResolvedFilter { contract: Some("5Ca".into()), event: Some("Flipped".into()) };
+		assert!(filter.matches(&event("5Ca", Some("Flipped"))));
+		assert!(!filter.matches(&event("5Cb", Some("Flipped"))));
+		assert!(!filter.matches(&event("5Ca", Some("Transfer"))));
+		assert!(!filter.matches(&event("5Ca", None)));
+	}
+}
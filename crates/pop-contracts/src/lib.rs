@@ -1,23 +1,73 @@
 // SPDX-License-Identifier: GPL-3.0
 #![doc = include_str!("../README.md")]
+mod accounts;
 mod build;
 mod call;
+mod deployments;
 mod errors;
+mod events;
 mod new;
+mod profile;
+mod sandbox;
+mod scenario;
+mod storage;
+mod templates;
 mod test;
+mod transaction;
+mod transfer;
 mod up;
+mod upgrade;
 mod utils;
+mod watch;
 
-pub use build::build_smart_contract;
+pub use accounts::{accounts_file, Account, Accounts, ACCOUNTS_FILE, PASSWORD_ENV};
+pub use build::{build_smart_contract, BuildArtifacts};
 pub use call::{
-	call_smart_contract, dry_run_call, dry_run_gas_estimate_call, set_up_call, CallOpts,
+	call_smart_contract, dry_run_call, dry_run_estimate_call, dry_run_gas_estimate_call,
+	estimate_call_fee, set_up_call, CallOpts, CallResult, DryRunResult,
+};
+pub use deployments::{record_deployment, Deployment, Deployments, DEPLOYMENTS_FILE};
+pub use events::{
+	query_events, subscribe_events, ContractEvent, EventField, EventFilter, ExtrinsicEvent,
 };
 pub use new::create_smart_contract;
-pub use test::{test_e2e_smart_contract, test_smart_contract};
+pub use pop_common::{Binary, Status, TestHandle, TestOpts, TestReport, TestResult, TestStatus};
+pub use profile::{
+	profile_contract, profile_sandboxed_contract, FunctionProfile, Metric, Profile, ProfileOpts,
+	Regression,
+};
+pub use sandbox::{Sandbox, SandboxResult};
+pub use scenario::{
+	Action, CallStep, DeployStep, Scenario, ScenarioReport, Step, StepReport, StepStatus,
+};
+pub use storage::{
+	get_contract_storage, ContractStorage, MappingEntry, RawCell, StorageField, StorageNode,
+};
+pub use templates::ContractTemplate;
+pub use test::{
+	test_e2e_smart_contract, test_sandbox_smart_contract, test_smart_contract, CONTRACTS_NODE_ENV,
+	CONTRACTS_NODE_URL_ENV,
+};
+pub use transaction::{TxEvent, TxOpts, WaitFor, FINALIZATION_TIMEOUT};
+pub use transfer::{transfer_balance, TransferOpts, TransferResult};
 pub use up::{
-	dry_run_gas_estimate_instantiate, instantiate_smart_contract, set_up_deployment, UpOpts,
+	dry_run_estimate_instantiate, dry_run_gas_estimate_instantiate, dry_run_upload,
+	estimate_instantiate_fee, estimate_upload_fee, instantiate_smart_contract, set_up_deployment,
+	set_up_upload, upload_smart_contract, ContractInfo, UpOpts,
 };
+pub use upgrade::{compare_storage_layouts, get_code_hash, LayoutChange, DEFAULT_UPGRADE_MESSAGE};
 pub use utils::{
-	contracts_node::{is_chain_alive, run_contracts_node},
-	signer::parse_hex_bytes,
+	contracts_node::{
+		contracts_node_generator, contracts_node_log, contracts_nodes, is_chain_alive,
+		run_contracts_node, start_contracts_node, stop_contracts_nodes, ContractsNode,
+		ContractsNodeOpts, ContractsNodePin, CONFIG_FILE, CONTRACTS_NODES_FILE, DEFAULT_PORT,
+	},
+	decode::{value_to_json, ContractError},
+	helpers::{apply_weight_margin, format_balance, parse_code_hash},
+	metadata::{
+		get_constructors, get_function, get_messages, validate_function_args, ContractFunction,
+		FunctionType, Param,
+	},
+	signer::{find_account, parse_hex_bytes, read_suri, Keypair, Scheme},
 };
+pub use watch::{built_code_hash, wait_for_changes, SourceSnapshot, DEFAULT_POLL_INTERVAL};
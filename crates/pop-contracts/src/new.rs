@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::errors::Error;
+use crate::{errors::Error, utils::git::Git, ContractTemplate};
 use contract_build::new_contract_project;
-use std::path::Path;
+use regex::Regex;
+use std::{fs, path::Path};
+use toml_edit::{value, DocumentMut};
+use walkdir::WalkDir;
 
 /// Create a new smart contract.
 ///
@@ -9,25 +12,163 @@ use std::path::Path;
 ///
 /// * `name` - name for the smart contract to be created.
 /// * `target` - location where the smart contract will be created.
-pub fn create_smart_contract(name: &str, target: &Path) -> Result<(), Error> {
+/// * `template` - template to generate the contract from.
+pub fn create_smart_contract(
+	name: &str,
+	target: &Path,
+	template: &ContractTemplate,
+) -> Result<(), Error> {
 	// Canonicalize the target path to ensure consistency and resolve any symbolic links.
 	let canonicalized_path = target
 		.canonicalize()
 		// If an I/O error occurs during canonicalization, convert it into an Error enum variant.
 		.map_err(|e| Error::IO(e))?;
 
-	// Retrieve the parent directory of the canonicalized path.
-	let parent_path = canonicalized_path
-		.parent()
-		// If the parent directory cannot be retrieved (e.g., if the path has no parent),
-		// return a NewContract variant indicating the failure.
-		.ok_or(Error::NewContract("Failed to get parent directory".to_string()))?;
-
-	// Create a new contract project with the provided name in the parent directory.
-	new_contract_project(&name, Some(parent_path))
-		// If an error occurs during the creation of the contract project,
-		// convert it into a NewContract variant with a formatted error message.
-		.map_err(|e| Error::NewContract(format!("{}", e)))
+	if let ContractTemplate::Standard = template {
+		// Retrieve the parent directory of the canonicalized path.
+		let parent_path = canonicalized_path
+			.parent()
+			// If the parent directory cannot be retrieved (e.g., if the path has no parent),
+			// return a NewContract variant indicating the failure.
+			.ok_or(Error::NewContract("Failed to get parent directory".to_string()))?;
+
+		// Create a new contract project with the provided name in the parent directory.
+		return new_contract_project(&name, Some(parent_path))
+			// If an error occurs during the creation of the contract project,
+			// convert it into a NewContract variant with a formatted error message.
+			.map_err(|e| Error::NewContract(format!("{}", e)));
+	}
+
+	check_contract_name(name)?;
+	match (template, template.repository_url().zip(template.tag())) {
+		(_, Some((url, tag))) => {
+			let temp_dir = tempfile::tempdir()?;
+			Git::clone(url, temp_dir.path(), tag)?;
+			let source = temp_dir.path().join(template.path().unwrap_or_default());
+			copy_template(&source, &canonicalized_path)?;
+		},
+		// The DAO template is bundled with Pop.
+		(ContractTemplate::DAO, None) => {
+			fs::write(
+				canonicalized_path.join("Cargo.toml"),
+				include_str!("../templates/dao/Cargo.templ"),
+			)?;
+			fs::write(
+				canonicalized_path.join("lib.rs"),
+				include_str!("../templates/dao/lib.rs.templ"),
+			)?;
+		},
+		(_, None) =>
+			return Err(Error::NewContract(format!(
+				"No source is available for the {} template",
+				template.name()
+			))),
+	}
+	rename_contract(&canonicalized_path, name)
+}
+
+// Ensures the name is a valid contract name, as required by cargo-contract.
+fn check_contract_name(name: &str) -> Result<(), Error> {
+	if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+		return Err(Error::NewContract(
+			"Contract names can only contain alphanumeric characters and underscores".to_string(),
+		));
+	}
+	if !name.chars().next().is_some_and(|c| c.is_alphabetic()) {
+		return Err(Error::NewContract(
+			"Contract names must begin with an alphabetic character".to_string(),
+		));
+	}
+	Ok(())
+}
+
+// Copies the contents of a template, excluding any git history, to the target directory.
+fn copy_template(source: &Path, target: &Path) -> Result<(), Error> {
+	if !source.join("Cargo.toml").exists() {
+		return Err(Error::NewContract(format!(
+			"No contract found in the template at {}",
+			source.display()
+		)));
+	}
+	for entry in WalkDir::new(source).into_iter().filter_entry(|e| e.file_name() != ".git") {
+		let entry = entry.map_err(|e| Error::NewContract(e.to_string()))?;
+		let destination_path = target.join(
+			entry
+				.path()
+				.strip_prefix(source)
+				.map_err(|e| Error::NewContract(e.to_string()))?,
+		);
+		if entry.file_type().is_dir() {
+			fs::create_dir_all(&destination_path)?;
+		} else {
+			fs::copy(entry.path(), &destination_path)?;
+		}
+	}
+	Ok(())
+}
+
+/// Renames a contract generated from a template: the package within its manifest, its contract
+/// module and its storage struct.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract project.
+/// * `name` - the new name of the contract.
+pub(crate) fn rename_contract(path: &Path, name: &str) -> Result<(), Error> {
+	let manifest_path = path.join("Cargo.toml");
+	let mut manifest = fs::read_to_string(&manifest_path)?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::NewContract(format!("Invalid manifest: {e}")))?;
+	manifest["package"]["name"] = value(name);
+	fs::write(&manifest_path, manifest.to_string())?;
+
+	let lib_path = path.join(
+		manifest
+			.get("lib")
+			.and_then(|lib| lib.get("path"))
+			.and_then(|path| path.as_str())
+			.unwrap_or(if path.join("lib.rs").exists() { "lib.rs" } else { "src/lib.rs" }),
+	);
+	let mut contents = fs::read_to_string(&lib_path)?;
+	let module = Regex::new(r"#\[ink::contract(?:\([^)]*\))?\]\s*(?:pub\s+)?mod\s+(\w+)")
+		.expect("valid regex")
+		.captures(&contents)
+		.map(|c| c[1].to_string());
+	if let Some(module) = module {
+		contents = Regex::new(&format!(r"\bmod\s+{module}\b"))
+			.expect("valid regex")
+			.replace_all(&contents, format!("mod {name}"))
+			.into_owned();
+		contents = Regex::new(&format!(r"\b{module}::"))
+			.expect("valid regex")
+			.replace_all(&contents, format!("{name}::"))
+			.into_owned();
+	}
+	let storage = Regex::new(r"#\[ink\(storage\)\]\s*(?:#\[[^\]]*\]\s*)*pub\s+struct\s+(\w+)")
+		.expect("valid regex")
+		.captures(&contents)
+		.map(|c| c[1].to_string());
+	if let Some(storage) = storage {
+		contents = Regex::new(&format!(r"\b{storage}\b"))
+			.expect("valid regex")
+			.replace_all(&contents, to_upper_camel_case(name))
+			.into_owned();
+	}
+	fs::write(&lib_path, contents)?;
+	Ok(())
+}
+
+// Converts a snake case name, such as `my_contract`, to upper camel case, such as `MyContract`.
+fn to_upper_camel_case(name: &str) -> String {
+	name.split('_')
+		.map(|word| {
+			let mut chars = word.chars();
+			match chars.next() {
+				Some(first) => first.to_uppercase().chain(chars).collect(),
+				None => String::new(),
+			}
+		})
+		.collect()
 }
 
 #[cfg(test)]
@@ -41,7 +182,11 @@ mod tests {
 		let temp_dir = tempfile::tempdir()?;
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		create_smart_contract(
+			"test_contract",
+			temp_contract_dir.as_path(),
+			&ContractTemplate::Standard,
+		)?;
 		Ok(temp_dir)
 	}
 
@@ -63,4 +208,65 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_create_smart_contract_from_bundled_template() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let contract_dir = temp_dir.path().join("my_dao");
+		fs::create_dir(&contract_dir)?;
+		create_smart_contract("my_dao", &contract_dir, &ContractTemplate::DAO)?;
+
+		let manifest = fs::read_to_string(contract_dir.join("Cargo.toml"))?;
+		assert!(manifest.contains("name = \"my_dao\""));
+		let contract = fs::read_to_string(contract_dir.join("lib.rs"))?;
+		assert!(contract.contains("mod my_dao {"));
+		assert!(contract.contains("pub struct MyDao {"));
+		assert!(contract.contains("impl MyDao {"));
+		assert!(!contract.contains("impl Dao {"));
+		Ok(())
+	}
+
+	#[test]
+	fn rename_contract_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			"[package]\nname = \"psp22\"\n\n[lib]\npath = \"src/contract.rs\"\n",
+		)?;
+		fs::create_dir(temp_dir.path().join("src"))?;
+		fs::write(
+			temp_dir.path().join("src/contract.rs"),
+			"#[cfg(feature = \"contract\")]\n#[ink::contract]\nmod token {\n\t#[ink(storage)]\n\t#[derive(Default)]\n\tpub struct Token {}\n\timpl Token {}\n\tfn token() -> token::Token {}\n}\n",
+		)?;
+		rename_contract(temp_dir.path(), "my_token")?;
+
+		let manifest = fs::read_to_string(temp_dir.path().join("Cargo.toml"))?;
+		assert!(manifest.contains("name = \"my_token\""));
+		assert_eq!(
+			fs::read_to_string(temp_dir.path().join("src/contract.rs"))?,
+			"#[cfg(feature = \"contract\")]\n#[ink::contract]\nmod my_token {\n\t#[ink(storage)]\n\t#[derive(Default)]\n\tpub struct MyToken {}\n\timpl MyToken {}\n\tfn token() -> my_token::MyToken {}\n}\n"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn create_smart_contract_fails_for_invalid_name() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(matches!(
+			create_smart_contract("my-dao", temp_dir.path(), &ContractTemplate::DAO),
+			Err(crate::errors::Error::NewContract(..))
+		));
+		assert!(matches!(
+			create_smart_contract("1dao", temp_dir.path(), &ContractTemplate::DAO),
+			Err(crate::errors::Error::NewContract(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn to_upper_camel_case_works() {
+		assert_eq!(to_upper_camel_case("my_contract"), "MyContract");
+		assert_eq!(to_upper_camel_case("flipper"), "Flipper");
+		assert_eq!(to_upper_camel_case("erc_20"), "Erc20");
+	}
 }
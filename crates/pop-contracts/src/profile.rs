@@ -0,0 +1,497 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	call::{dry_run_estimate_call, set_up_call, CallOpts},
+	errors::Error,
+	sandbox::Sandbox,
+	transaction::TxOpts,
+	up::{dry_run_estimate_instantiate, instantiate_smart_contract, set_up_deployment, UpOpts},
+	utils::{
+		metadata::{example_args, get_function, get_messages, FunctionType},
+		signer::Scheme,
+	},
+};
+use pop_common::Status;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+use strum_macros::{AsRefStr, Display};
+use url::Url;
+
+/// Options for profiling the messages of a contract.
+#[derive(Clone, Debug)]
+pub struct ProfileOpts {
+	/// Path to the contract build folder.
+	pub path: Option<PathBuf>,
+	/// The constructor used to deploy the contract.
+	pub constructor: String,
+	/// The arguments of the constructor, with example values generated from the contract metadata
+	/// if none are provided.
+	pub args: Vec<String>,
+	/// The value transferred to the contract when deployed.
+	pub value: String,
+	/// The messages to be profiled, or all messages of the contract if empty.
+	pub messages: Vec<String>,
+	/// The arguments of messages, by message. Example values generated from the contract metadata
+	/// are used for any other message.
+	pub message_args: BTreeMap<String, Vec<String>>,
+	/// Websocket endpoint of a node.
+	pub url: Url,
+	/// Secret key URI for the account deploying and calling the contract.
+	pub suri: String,
+	/// The cryptographic scheme of the account deploying and calling the contract.
+	pub scheme: Scheme,
+	/// The password of the stored account, when `suri` is the name of an account.
+	pub password: Option<String>,
+}
+
+/// The cost of a constructor or message of a contract, as estimated by a dry run.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FunctionProfile {
+	/// The label of the function.
+	pub name: String,
+	/// The arguments with which the function was called.
+	pub args: Vec<String>,
+	/// The computation time required, in picoseconds.
+	pub ref_time: u64,
+	/// The size of the storage proof required, in bytes.
+	pub proof_size: u64,
+	/// The storage deposit charged, which is zero when storage deposits are refunded.
+	pub storage_deposit: u128,
+	/// The reason the dry run failed, in which case no costs are known.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// The costs of deploying a contract and of calling its messages.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Profile {
+	/// The cost of the constructor used to deploy the contract.
+	pub constructor: FunctionProfile,
+	/// The costs of the messages, in the order declared by the contract.
+	pub messages: Vec<FunctionProfile>,
+}
+
+/// A measure of the cost of a contract function.
+#[derive(AsRefStr, Clone, Copy, Debug, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Metric {
+	/// The computation time required.
+	RefTime,
+	/// The size of the storage proof required.
+	ProofSize,
+	/// The storage deposit charged.
+	StorageDeposit,
+}
+
+/// A regression of a contract function compared to a baseline.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Regression {
+	/// An increase in the cost of the function.
+	Cost {
+		/// The label of the function.
+		function: String,
+		/// The measure of cost which increased.
+		metric: Metric,
+		/// The cost within the baseline.
+		baseline: u128,
+		/// The current cost.
+		current: u128,
+	},
+	/// The dry run of the function fails, whereas it succeeded within the baseline.
+	Failure {
+		/// The label of the function.
+		function: String,
+		/// The reason the dry run failed.
+		error: String,
+	},
+}
+
+impl Regression {
+	/// The increase in cost as a percentage of the baseline, which is infinite when the function
+	/// previously had no cost or now fails.
+	pub fn increase(&self) -> f64 {
+		match self {
+			Regression::Cost { baseline: 0, .. } | Regression::Failure { .. } => f64::INFINITY,
+			Regression::Cost { baseline, current, .. } =>
+				(*current as f64 - *baseline as f64) / *baseline as f64 * 100.0,
+		}
+	}
+}
+
+impl std::fmt::Display for Regression {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Regression::Cost { function, metric, baseline, current } =>
+				write!(f, "{function} {metric}: {baseline} -> {current} (+{:.1}%)", self.increase()),
+			Regression::Failure { function, error } => write!(f, "{function} now fails: {error}"),
+		}
+	}
+}
+
+impl Profile {
+	/// Loads a profile, such as a baseline saved by a previous run.
+	///
+	/// # Arguments
+	///
+	/// * `path` - The path of the profile.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		serde_json::from_str(&fs::read_to_string(path)?)
+			.map_err(|e| Error::Profile(format!("{}: {e}", path.display())))
+	}
+
+	/// Saves the profile as JSON, such as for use as a baseline by later runs.
+	///
+	/// # Arguments
+	///
+	/// * `path` - The path of the profile.
+	pub fn save(&self, path: &Path) -> Result<(), Error> {
+		let contents =
+			serde_json::to_string_pretty(self).map_err(|e| Error::Profile(e.to_string()))?;
+		fs::write(path, contents)?;
+		Ok(())
+	}
+
+	/// The costs of the functions which increased by more than the threshold compared to a
+	/// baseline, along with the functions whose dry run now fails but succeeded within the
+	/// baseline. Functions which are not within both profiles, or whose dry run failed within the
+	/// baseline, are otherwise ignored.
+	///
+	/// # Arguments
+	///
+	/// * `baseline` - The profile with which the costs are compared.
+	/// * `threshold` - The percentage by which a cost may increase.
+	pub fn regressions(&self, baseline: &Profile, threshold: f64) -> Vec<Regression> {
+		let functions = [(&self.constructor, &baseline.constructor)].into_iter().chain(
+			self.messages.iter().filter_map(|message| {
+				baseline.messages.iter().find(|b| b.name == message.name).map(|b| (message, b))
+			}),
+		);
+		let mut regressions = Vec::new();
+		for (current, baseline) in functions {
+			if current.name != baseline.name || baseline.error.is_some() {
+				continue;
+			}
+			if let Some(error) = &current.error {
+				regressions.push(Regression::Failure {
+					function: current.name.clone(),
+					error: error.clone(),
+				});
+				continue;
+			}
+			for (metric, current_cost, baseline_cost) in [
+				(Metric::RefTime, current.ref_time as u128, baseline.ref_time as u128),
+				(Metric::ProofSize, current.proof_size as u128, baseline.proof_size as u128),
+				(Metric::StorageDeposit, current.storage_deposit, baseline.storage_deposit),
+			] {
+				if current_cost <= baseline_cost {
+					continue;
+				}
+				let regression = Regression::Cost {
+					function: current.name.clone(),
+					metric,
+					baseline: baseline_cost,
+					current: current_cost,
+				};
+				if regression.increase() > threshold {
+					regressions.push(regression);
+				}
+			}
+		}
+		regressions
+	}
+}
+
+/// Profiles a contract by deploying it, then estimating the cost of each of its messages by a dry
+/// run. Messages whose dry run fails, such as when the example arguments are rejected by the
+/// contract, are recorded with the reason rather than failing the profile.
+///
+/// # Arguments
+///
+/// * `opts` - options for profiling the contract.
+/// * `status` - observer of the progress of the profile.
+pub async fn profile_contract(opts: &ProfileOpts, status: &impl Status) -> anyhow::Result<Profile> {
+	let messages = profiled_messages(opts)?;
+
+	// Deploy the contract, using a random salt so that it can be deployed repeatedly.
+	let args = constructor_args(opts)?;
+	status.update(&format!("Deploying the contract using `{}`...", opts.constructor));
+	let instantiate_exec = set_up_deployment(UpOpts {
+		path: opts.path.clone(),
+		constructor: opts.constructor.clone(),
+		args: args.clone(),
+		value: opts.value.clone(),
+		gas_limit: None,
+		proof_size: None,
+		salt: Some(Bytes::from(rand::random::<[u8; 32]>().to_vec())),
+		storage_deposit_limit: None,
+		url: opts.url.clone(),
+		suri: opts.suri.clone(),
+		scheme: opts.scheme,
+		password: opts.password.clone(),
+		code_hash: None,
+	})
+	.await?;
+	let (weight, storage_deposit) = dry_run_estimate_instantiate(&instantiate_exec).await?;
+	let constructor = FunctionProfile {
+		name: opts.constructor.clone(),
+		args,
+		ref_time: weight.ref_time(),
+		proof_size: weight.proof_size(),
+		storage_deposit,
+		error: None,
+	};
+	let contract = instantiate_smart_contract(instantiate_exec, weight, &TxOpts::default(), |_| {})
+		.await
+		.map_err(|e| Error::Profile(format!("failed to deploy the contract: {e}")))?;
+
+	let mut profiles = Vec::new();
+	for message in messages {
+		status.update(&format!("Profiling `{message}`..."));
+		let args = message_args(opts, &message)?;
+		let mut profile = FunctionProfile { name: message.clone(), args, ..Default::default() };
+		let estimate = async {
+			let call_exec = set_up_call(CallOpts {
+				path: opts.path.clone(),
+				contract: contract.address.clone(),
+				message,
+				args: profile.args.clone(),
+				value: "0".to_string(),
+				gas_limit: None,
+				proof_size: None,
+				storage_deposit_limit: None,
+				url: opts.url.clone(),
+				suri: opts.suri.clone(),
+				scheme: opts.scheme,
+				password: opts.password.clone(),
+				execute: false,
+			})
+			.await?;
+			dry_run_estimate_call(&call_exec).await
+		};
+		match estimate.await {
+			Ok((weight, storage_deposit)) => {
+				profile.ref_time = weight.ref_time();
+				profile.proof_size = weight.proof_size();
+				profile.storage_deposit = storage_deposit;
+			},
+			Err(e) => profile.error = Some(e.to_string()),
+		}
+		profiles.push(profile);
+	}
+	Ok(Profile { constructor, messages: profiles })
+}
+
+/// Profiles a contract within a sandboxed `pallet-contracts` runtime rather than on a node, by
+/// deploying it, then estimating the cost of each of its messages by a dry run. The value
+/// transferred when deploying is in the smallest unit of the token of the sandbox, and the
+/// endpoint and account of `opts` are unused. Messages whose dry run fails are recorded with the
+/// reason rather than failing the profile.
+///
+/// # Arguments
+///
+/// * `opts` - options for profiling the contract.
+/// * `status` - observer of the progress of the profile.
+pub fn profile_sandboxed_contract(
+	opts: &ProfileOpts,
+	status: &impl Status,
+) -> Result<Profile, Error> {
+	let messages = profiled_messages(opts)?;
+	let args = constructor_args(opts)?;
+	let value = opts
+		.value
+		.parse::<u128>()
+		.map_err(|e| Error::BalanceParsing(format!("{}: {e}", opts.value)))?;
+	let mut sandbox = Sandbox::new(&opts.path)?;
+	status.update(&format!("Deploying the contract using `{}`...", opts.constructor));
+	let deployed = sandbox.deploy(&opts.constructor, &args, value)?;
+	let Some(contract) = deployed.address else {
+		let error = deployed.result.err().map(|e| e.to_string()).unwrap_or_default();
+		return Err(Error::Profile(format!("failed to deploy the contract: {error}")));
+	};
+	let constructor = FunctionProfile {
+		name: opts.constructor.clone(),
+		args,
+		ref_time: deployed.gas_required.ref_time(),
+		proof_size: deployed.gas_required.proof_size(),
+		storage_deposit: deployed.storage_deposit,
+		error: None,
+	};
+
+	let mut profiles = Vec::new();
+	for message in messages {
+		status.update(&format!("Profiling `{message}`..."));
+		let args = message_args(opts, &message)?;
+		let mut profile = FunctionProfile { name: message.clone(), args, ..Default::default() };
+		match sandbox.dry_run_call(&contract, &message, &profile.args, 0) {
+			Ok(result) => match result.result {
+				Ok(_) => {
+					profile.ref_time = result.gas_required.ref_time();
+					profile.proof_size = result.gas_required.proof_size();
+					profile.storage_deposit = result.storage_deposit;
+				},
+				Err(e) => profile.error = Some(e.to_string()),
+			},
+			Err(e) => profile.error = Some(e.to_string()),
+		}
+		profiles.push(profile);
+	}
+	Ok(Profile { constructor, messages: profiles })
+}
+
+// The messages to be profiled: those specified, or all messages of the contract.
+fn profiled_messages(opts: &ProfileOpts) -> Result<Vec<String>, Error> {
+	if opts.messages.is_empty() {
+		return Ok(get_messages(&opts.path)?.into_iter().map(|m| m.label).collect());
+	}
+	for message in &opts.messages {
+		get_function(&opts.path, message, FunctionType::Message)?;
+	}
+	Ok(opts.messages.clone())
+}
+
+// The arguments of the constructor: those specified, or example values.
+fn constructor_args(opts: &ProfileOpts) -> Result<Vec<String>, Error> {
+	match opts.args.is_empty() {
+		true => example_args(&opts.path, &opts.constructor, FunctionType::Constructor),
+		false => Ok(opts.args.clone()),
+	}
+}
+
+// The arguments of a message: those specified, or example values.
+fn message_args(opts: &ProfileOpts, message: &str) -> Result<Vec<String>, Error> {
+	match opts.message_args.get(message) {
+		Some(args) => Ok(args.clone()),
+		None => example_args(&opts.path, message, FunctionType::Message),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn function(
+		name: &str,
+		ref_time: u64,
+		proof_size: u64,
+		storage_deposit: u128,
+	) -> FunctionProfile {
+		FunctionProfile {
+			name: name.to_string(),
+			args: Vec::new(),
+			ref_time,
+			proof_size,
+			storage_deposit,
+			error: None,
+		}
+	}
+
+	#[test]
+	fn regressions_works() {
+		let baseline = Profile {
+			constructor: function("new", 1000, 100, 10),
+			messages: vec![function("flip", 1000, 100, 0), function("get", 500, 50, 0)],
+		};
+		let mut failed = function("get", 0, 0, 0);
+		failed.error = Some("reverted".to_string());
+		let current = Profile {
+			constructor: function("new", 1040, 100, 10),
+			messages: vec![
+				function("flip", 1200, 90, 5),
+				failed,
+				function("added", 10_000, 1000, 1000),
+			],
+		};
+		assert_eq!(
+			current.regressions(&baseline, 5.0),
+			vec![
+				Regression::Cost {
+					function: "flip".to_string(),
+					metric: Metric::RefTime,
+					baseline: 1000,
+					current: 1200
+				},
+				Regression::Cost {
+					function: "flip".to_string(),
+					metric: Metric::StorageDeposit,
+					baseline: 0,
+					current: 5
+				},
+				Regression::Failure { function: "get".to_string(), error: "reverted".to_string() },
+			]
+		);
+		// The increase in the cost of the constructor is within a larger threshold.
+		assert_eq!(current.regressions(&baseline, 25.0).len(), 2);
+		// Functions which failed within the baseline are ignored.
+		assert!(current.regressions(&current, 0.0).is_empty());
+	}
+
+	#[test]
+	fn regression_increase_works() {
+		let regression = |baseline, current| Regression::Cost {
+			function: "flip".to_string(),
+			metric: Metric::RefTime,
+			baseline,
+			current,
+		};
+		assert_eq!(regression(1000, 1100).increase(), 10.0);
+		assert_eq!(regression(0, 1).increase(), f64::INFINITY);
+		assert_eq!(regression(1000, 1100).to_string(), "flip ref_time: 1000 -> 1100 (+10.0%)");
+		let failure =
+			Regression::Failure { function: "get".to_string(), error: "reverted".to_string() };
+		assert_eq!(failure.increase(), f64::INFINITY);
+		assert_eq!(failure.to_string(), "get now fails: reverted");
+	}
+
+	#[test]
+	fn profile_sandboxed_contract_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let opts = ProfileOpts {
+			path: crate::sandbox::tests::contract_bundle(temp_dir.path())?,
+			constructor: "new".to_string(),
+			args: Vec::new(),
+			value: "0".to_string(),
+			messages: vec!["flip".to_string(), "get".to_string(), "flips_of".to_string()],
+			message_args: BTreeMap::new(),
+			url: Url::parse("ws://localhost:9944")?,
+			suri: "//Alice".to_string(),
+			scheme: Scheme::Sr25519,
+			password: None,
+		};
+		let profile = profile_sandboxed_contract(&opts, &())?;
+		assert_eq!(profile.constructor.args, vec!["true"]);
+		assert!(profile.constructor.ref_time > 0 && profile.constructor.storage_deposit > 0);
+		let [flip, get, flips_of] = &profile.messages[..] else { panic!("expected 3 messages") };
+		assert!(flip.error.is_none() && flip.ref_time > 0 && flip.proof_size > 0);
+		assert!(get.error.is_none() && get.ref_time > 0);
+		// The message is not implemented by the code of the contract, so reverts.
+		assert!(flips_of.error.is_some());
+
+		let opts = ProfileOpts { value: "1UNIT".to_string(), ..opts };
+		assert!(matches!(profile_sandboxed_contract(&opts, &()), Err(Error::BalanceParsing(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn profile_is_saved_and_loaded() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("profile.json");
+		let mut failed = function("get", 0, 0, 0);
+		failed.error = Some("reverted".to_string());
+		let profile = Profile {
+			constructor: function("new", 1000, 100, u128::MAX),
+			messages: vec![function("flip", 1000, 100, 0), failed],
+		};
+		profile.save(&path)?;
+		assert_eq!(Profile::load(&path)?, profile);
+		fs::write(&path, "{}")?;
+		assert!(matches!(Profile::load(&path), Err(Error::Profile(..))));
+		Ok(())
+	}
+}
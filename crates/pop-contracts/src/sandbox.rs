@@ -0,0 +1,313 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	errors::Error,
+	up::contract_code,
+	utils::{
+		decode::{decode_message_return, value_to_json, ContractError},
+		metadata::{get_artifacts, validate_function_args, FunctionType},
+	},
+};
+use contract_transcode::ContractMessageTranscoder;
+use ink_sandbox::{
+	api::contracts_api::ContractAPI,
+	pallet_contracts::{Determinism, StorageDeposit},
+	AccountId32, DefaultSandbox, DispatchError, Sandbox as _, Ss58Codec,
+};
+use serde_json::json;
+use sp_core::bytes::to_hex;
+use sp_weights::Weight;
+use std::path::PathBuf;
+use subxt::{
+	ext::codec::{Decode, Encode},
+	Metadata,
+};
+
+/// A runtime with pallet-contracts in which a contract is deployed and called without a node,
+/// from an account endowed with a balance.
+pub struct Sandbox {
+	sandbox: DefaultSandbox,
+	// The location of the contract, against whose metadata arguments are validated.
+	path: Option<PathBuf>,
+	code: Vec<u8>,
+	transcoder: ContractMessageTranscoder,
+	// The metadata of the runtime, describing the errors of its pallets.
+	metadata: Metadata,
+	// The number of instances deployed, used as the salt of the next.
+	instances: u64,
+}
+
+/// The result of deploying or calling a contract within a sandbox.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SandboxResult {
+	/// The address of the contract, when deployed.
+	pub address: Option<String>,
+	/// The value returned by a message, or the reason the contract reverted or failed.
+	pub result: Result<serde_json::Value, ContractError>,
+	/// Output written by the contract via `ink::env::debug_println!`.
+	pub debug_message: String,
+	/// The gas consumed.
+	pub gas_consumed: Weight,
+	/// The gas required, which can exceed that consumed as gas can be refunded.
+	pub gas_required: Weight,
+	/// The storage deposit charged, in the smallest unit of the token of the sandbox.
+	pub storage_deposit: u128,
+}
+
+impl Sandbox {
+	/// Loads a contract into a new sandbox.
+	///
+	/// # Arguments
+	///
+	/// * `path` - location of the contract: its build folder or its `.contract` bundle.
+	pub fn new(path: &Option<PathBuf>) -> Result<Self, Error> {
+		let artifacts = get_artifacts(path)?;
+		let transcoder = ContractMessageTranscoder::new(
+			artifacts.ink_project_metadata().map_err(Error::ContractMetadata)?,
+		);
+		let code = contract_code(artifacts).map_err(Error::ContractMetadata)?;
+		let metadata = Metadata::decode(&mut &DefaultSandbox::get_metadata().encode()[..])
+			.map_err(|e| Error::Sandbox(format!("invalid runtime metadata: {e}")))?;
+		Ok(Self {
+			sandbox: DefaultSandbox::default(),
+			path: path.clone(),
+			code,
+			transcoder,
+			metadata,
+			instances: 0,
+		})
+	}
+
+	/// The address of the account deploying and calling contracts.
+	pub fn caller(&self) -> String {
+		DefaultSandbox::default_actor().to_ss58check()
+	}
+
+	/// Deploys an instance of the contract.
+	///
+	/// # Arguments
+	///
+	/// * `constructor` - the label of the constructor.
+	/// * `args` - the arguments of the constructor, encoded as strings.
+	/// * `value` - the balance transferred to the contract, in the smallest unit of the token.
+	pub fn deploy(
+		&mut self,
+		constructor: &str,
+		args: &[String],
+		value: u128,
+	) -> Result<SandboxResult, Error> {
+		validate_function_args(&self.path, constructor, args, FunctionType::Constructor)?;
+		let data = self.transcoder.encode(constructor, args).map_err(Error::AnyhowError)?;
+		self.instances += 1;
+		let deployed = self.sandbox.deploy_contract(
+			self.code.clone(),
+			value,
+			data,
+			self.instances.encode(),
+			DefaultSandbox::default_actor(),
+			DefaultSandbox::default_gas_limit(),
+			None,
+		);
+		let (address, result) = match deployed.result {
+			Ok(instance) if !instance.result.did_revert() =>
+				(Some(instance.account_id.to_ss58check()), Ok(serde_json::Value::Null)),
+			Ok(instance) => {
+				let data = instance.result.data;
+				let error =
+					match self.transcoder.decode_constructor_return(constructor, &mut &data[..]) {
+						Ok(value) => value_to_json(&value),
+						Err(_) => json!(to_hex(&data, false)),
+					};
+				(None, Err(ContractError::Revert(error)))
+			},
+			Err(error) => (None, Err(self.dispatch_error(error))),
+		};
+		Ok(SandboxResult {
+			address,
+			result,
+			debug_message: String::from_utf8_lossy(&deployed.debug_message).to_string(),
+			gas_consumed: weight(deployed.gas_consumed),
+			gas_required: weight(deployed.gas_required),
+			storage_deposit: charged(&deployed.storage_deposit),
+		})
+	}
+
+	/// Calls a message of a deployed contract, changing the state of the sandbox.
+	///
+	/// # Arguments
+	///
+	/// * `address` - the address of the contract.
+	/// * `message` - the label of the message.
+	/// * `args` - the arguments of the message, encoded as strings.
+	/// * `value` - the balance transferred to the contract, in the smallest unit of the token.
+	pub fn call(
+		&mut self,
+		address: &str,
+		message: &str,
+		args: &[String],
+		value: u128,
+	) -> Result<SandboxResult, Error> {
+		self.execute(address, message, args, value, false)
+	}
+
+	/// Calls a message of a deployed contract without changing the state of the sandbox, such as
+	/// to query a value or to estimate the cost of a call.
+	///
+	/// # Arguments
+	///
+	/// * `address` - the address of the contract.
+	/// * `message` - the label of the message.
+	/// * `args` - the arguments of the message, encoded as strings.
+	/// * `value` - the balance transferred to the contract, in the smallest unit of the token.
+	pub fn dry_run_call(
+		&mut self,
+		address: &str,
+		message: &str,
+		args: &[String],
+		value: u128,
+	) -> Result<SandboxResult, Error> {
+		self.execute(address, message, args, value, true)
+	}
+
+	fn execute(
+		&mut self,
+		address: &str,
+		message: &str,
+		args: &[String],
+		value: u128,
+		dry_run: bool,
+	) -> Result<SandboxResult, Error> {
+		let contract = AccountId32::from_ss58check(address)
+			.map_err(|e| Error::AccountAddressParsing(format!("{address}: {e:?}")))?;
+		validate_function_args(&self.path, message, args, FunctionType::Message)?;
+		let data = self.transcoder.encode(message, args).map_err(Error::AnyhowError)?;
+		let call = |sandbox: &mut DefaultSandbox| {
+			sandbox.call_contract(
+				contract,
+				value,
+				data,
+				DefaultSandbox::default_actor(),
+				DefaultSandbox::default_gas_limit(),
+				None,
+				Determinism::Enforced,
+			)
+		};
+		let called = if dry_run { self.sandbox.dry_run(call) } else { call(&mut self.sandbox) };
+		let result = match called.result {
+			Ok(ret) =>
+				decode_message_return(&self.transcoder, message, &ret.data, ret.did_revert()),
+			Err(error) => Err(self.dispatch_error(error)),
+		};
+		Ok(SandboxResult {
+			address: None,
+			result,
+			debug_message: String::from_utf8_lossy(&called.debug_message).to_string(),
+			gas_consumed: weight(called.gas_consumed),
+			gas_required: weight(called.gas_required),
+			storage_deposit: charged(&called.storage_deposit),
+		})
+	}
+
+	// Describes an error raised by the runtime, naming the pallet and error for those raised by a
+	// pallet.
+	fn dispatch_error(&self, error: DispatchError) -> ContractError {
+		if let DispatchError::Module(module) = error {
+			let pallet = self.metadata.pallet_by_index(module.index);
+			if let Some((pallet, variant)) = pallet
+				.and_then(|pallet| Some((pallet, pallet.error_variant_by_index(module.error[0])?)))
+			{
+				return ContractError::Module {
+					pallet: pallet.name().to_string(),
+					error: variant.name.clone(),
+					docs: variant.docs.clone(),
+				};
+			}
+		}
+		ContractError::Other(format!("{error:?}"))
+	}
+}
+
+fn weight(weight: ink_sandbox::Weight) -> Weight {
+	Weight::from_parts(weight.ref_time(), weight.proof_size())
+}
+
+// The storage deposit charged, with nothing charged when the deposit is refunded.
+fn charged(deposit: &StorageDeposit<u128>) -> u128 {
+	match deposit {
+		StorageDeposit::Charge(value) => *value,
+		StorageDeposit::Refund(_) => 0,
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs;
+
+	// Bundles the metadata of `testing.json` with code implementing some of its functions.
+	pub(crate) fn contract_bundle(dir: &std::path::Path) -> Result<Option<PathBuf>> {
+		let files = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/files");
+		let mut bundle: serde_json::Value =
+			serde_json::from_str(&fs::read_to_string(files.join("testing.json"))?)?;
+		let code = wat::parse_file(files.join("flipper.wat"))?;
+		bundle["source"]["wasm"] = json!(to_hex(&code, false));
+		let path = dir.join("testing.contract");
+		fs::write(&path, bundle.to_string())?;
+		Ok(Some(path))
+	}
+
+	#[test]
+	fn deploy_and_call_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut sandbox = Sandbox::new(&contract_bundle(temp_dir.path())?)?;
+
+		let deployed = sandbox.deploy("new", &["true".to_string()], 0)?;
+		assert_eq!(deployed.result, Ok(serde_json::Value::Null));
+		assert!(deployed.gas_consumed.ref_time() > 0);
+		assert!(deployed.storage_deposit > 0);
+		let address = deployed.address.expect("the contract should be deployed");
+
+		let get = sandbox.dry_run_call(&address, "get", &[], 0)?;
+		assert_eq!(get.result, Ok(json!(true)));
+		assert!(get.gas_required.ref_time() > 0);
+		let flip = sandbox.dry_run_call(&address, "flip", &[], 0)?;
+		assert_eq!(flip.result, Ok(serde_json::Value::Null));
+		// A dry run does not change the state of the sandbox.
+		assert_eq!(sandbox.dry_run_call(&address, "get", &[], 0)?.result, Ok(json!(true)));
+		sandbox.call(&address, "flip", &[], 0)?;
+		assert_eq!(sandbox.dry_run_call(&address, "get", &[], 0)?.result, Ok(json!(false)));
+
+		// A message the code does not implement reverts with an ink! error.
+		let flips_of = sandbox.dry_run_call(&address, "flips_of", &[sandbox.caller()], 0)?;
+		assert_eq!(flips_of.result, Err(ContractError::LangError("CouldNotReadInput".to_string())));
+
+		// Each deployment is a new instance of the contract.
+		let default = sandbox.deploy("default", &[], 0)?;
+		assert_ne!(default.address, Some(address));
+		let address = default.address.expect("the contract should be deployed");
+		assert_eq!(sandbox.dry_run_call(&address, "get", &[], 0)?.result, Ok(json!(false)));
+		Ok(())
+	}
+
+	#[test]
+	fn invalid_calls_fail() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let mut sandbox = Sandbox::new(&contract_bundle(temp_dir.path())?)?;
+		assert!(matches!(
+			sandbox.deploy("new", &[], 0),
+			Err(Error::IncorrectArguments { expected: 1, provided: 0 })
+		));
+		assert!(matches!(
+			sandbox.call("invalid", "get", &[], 0),
+			Err(Error::AccountAddressParsing(..))
+		));
+		// The contract does not exist.
+		let result = sandbox.dry_run_call(&sandbox.caller(), "get", &[], 0)?;
+		assert!(matches!(
+			result.result,
+			Err(ContractError::Module { pallet, error, .. })
+				if pallet == "Contracts" && error == "ContractNotFound"
+		));
+		Ok(())
+	}
+}
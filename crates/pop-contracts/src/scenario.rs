@@ -0,0 +1,551 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	call::{call_smart_contract, dry_run_call, dry_run_gas_estimate_call, set_up_call, CallOpts},
+	errors::Error,
+	transaction::TxOpts,
+	up::{dry_run_gas_estimate_instantiate, instantiate_smart_contract, set_up_deployment, UpOpts},
+	utils::{
+		decode::ContractError,
+		metadata::{get_function, FunctionType, Param},
+		signer::{parse_hex_bytes, Scheme},
+	},
+};
+use anyhow::anyhow;
+use pop_common::Status;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+	sync::LazyLock,
+};
+use url::Url;
+
+// A reference to the output of an earlier step, e.g. `${token.address}`.
+static REFERENCE: LazyLock<Regex> =
+	LazyLock::new(|| Regex::new(r"\$\{([\w-]+)((?:\.[\w-]+)*)\}").expect("valid regex"));
+
+/// A scenario of contract deployments and calls, run in order. Later steps can reference the
+/// outputs of earlier steps using `${step.field}`, e.g. `${token.address}` or
+/// `${balance.result}`.
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+	/// Websocket endpoint of the node the scenario is run against.
+	#[serde(default = "default_url")]
+	pub url: Url,
+	/// Secret key URI for the account signing the steps, unless specified by the step.
+	#[serde(default = "default_suri")]
+	pub suri: String,
+	/// The cryptographic scheme of the account signing the steps.
+	#[serde(default)]
+	pub scheme: Scheme,
+	/// The steps of the scenario.
+	#[serde(rename = "step", default)]
+	pub steps: Vec<Step>,
+	/// The passwords of the stored accounts signing the steps, by account name.
+	#[serde(skip)]
+	pub passwords: BTreeMap<String, String>,
+	// The directory containing the scenario, which paths are relative to.
+	#[serde(skip)]
+	dir: PathBuf,
+}
+
+/// A step of a scenario.
+#[derive(Debug, Deserialize)]
+pub struct Step {
+	/// The name of the step, used to reference its outputs.
+	pub name: String,
+	/// Secret key URI for the account signing the step.
+	pub suri: Option<String>,
+	/// The cryptographic scheme of the account signing the step.
+	pub scheme: Option<Scheme>,
+	/// The action performed by the step.
+	#[serde(flatten)]
+	pub action: Action,
+}
+
+/// The action performed by a step of a scenario.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum Action {
+	/// Deploy a contract, with the `address`, `code_hash`, `block_number` and `block_hash` of the
+	/// contract as outputs.
+	Deploy(DeployStep),
+	/// Call a contract, with the `result` of the dry run as output, along with the `events`
+	/// emitted when executed.
+	Call(CallStep),
+}
+
+/// Deploys a contract.
+#[derive(Debug, Deserialize)]
+pub struct DeployStep {
+	/// Path to the contract build folder.
+	pub path: PathBuf,
+	/// The name of the contract constructor to call.
+	#[serde(default = "default_constructor")]
+	pub constructor: String,
+	/// The constructor arguments.
+	#[serde(default)]
+	pub args: Vec<Value>,
+	/// Transfers an initial balance to the instantiated contract.
+	#[serde(default = "default_value")]
+	pub value: String,
+	/// A salt used in the address derivation of the new contract, as hex.
+	pub salt: Option<String>,
+}
+
+/// Calls a contract.
+#[derive(Debug, Deserialize)]
+pub struct CallStep {
+	/// Path to the contract build folder.
+	pub path: PathBuf,
+	/// The address of the contract to call, or the alias or name of a deployment recorded within
+	/// the contract project.
+	pub contract: String,
+	/// The name of the contract message to call.
+	pub message: String,
+	/// The message arguments.
+	#[serde(default)]
+	pub args: Vec<Value>,
+	/// Transfers a balance to the contract.
+	#[serde(default = "default_value")]
+	pub value: String,
+	/// Submit the call for on-chain execution after its dry run, rather than only dry-running it.
+	#[serde(default)]
+	pub execute: bool,
+	/// The value the dry run of the call is expected to return.
+	pub expect: Option<Value>,
+	/// The error the dry run of the call is expected to revert with. The call is not executed.
+	pub expect_revert: Option<Value>,
+}
+
+/// The outcome of a step of a scenario.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+	/// The step succeeded, and any assertions held.
+	Passed,
+	/// The step failed, or one of its assertions did not hold.
+	Failed,
+	/// The step was not run, as an earlier step failed.
+	Skipped,
+}
+
+/// A report of a step of a scenario.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StepReport {
+	/// The name of the step.
+	pub name: String,
+	/// The action performed by the step.
+	pub action: String,
+	/// The outcome of the step.
+	pub status: StepStatus,
+	/// The outputs of the step.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<Value>,
+	/// The reason the step failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// A report of a scenario run.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ScenarioReport {
+	/// Websocket endpoint of the node the scenario was run against.
+	pub url: Url,
+	/// Whether every step of the scenario passed.
+	pub success: bool,
+	/// The reports of the steps of the scenario, in order.
+	pub steps: Vec<StepReport>,
+}
+
+impl Scenario {
+	/// Loads a scenario from a TOML file, with the paths of its steps relative to the file.
+	///
+	/// # Arguments
+	///
+	/// * `path` - location of the scenario file.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let contents = fs::read_to_string(path)?;
+		let mut scenario: Scenario = toml_edit::de::from_str(&contents)
+			.map_err(|e| Error::Scenario(format!("{}: {e}", path.display())))?;
+		scenario.dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+		scenario.validate()?;
+		Ok(scenario)
+	}
+
+	// Checks the names of the steps are unique and their assertions are consistent.
+	fn validate(&self) -> Result<(), Error> {
+		for (i, step) in self.steps.iter().enumerate() {
+			if self.steps[..i].iter().any(|s| s.name == step.name) {
+				return Err(Error::Scenario(format!("duplicate step name `{}`", step.name)));
+			}
+			if let Action::Call(CallStep { expect: Some(_), expect_revert: Some(_), .. }) =
+				&step.action
+			{
+				return Err(Error::Scenario(format!(
+					"step `{}` cannot expect both a value and a revert",
+					step.name
+				)));
+			}
+		}
+		Ok(())
+	}
+
+	/// Runs the steps of the scenario in order, until a step fails.
+	///
+	/// # Arguments
+	///
+	/// * `status` - used to observe the step being run.
+	pub async fn run(&self, status: &impl Status) -> ScenarioReport {
+		let mut outputs = Map::new();
+		let mut steps = Vec::new();
+		let mut success = true;
+		for step in &self.steps {
+			let action = match step.action {
+				Action::Deploy(_) => "deploy",
+				Action::Call(_) => "call",
+			};
+			let mut report = StepReport {
+				name: step.name.clone(),
+				action: action.to_string(),
+				status: StepStatus::Skipped,
+				output: None,
+				error: None,
+			};
+			if success {
+				status.update(&format!("Running step `{}`...", step.name));
+				match self.run_step(step, &outputs).await {
+					Ok(output) => {
+						outputs.insert(step.name.clone(), output.clone());
+						report.status = StepStatus::Passed;
+						report.output = Some(output);
+					},
+					Err(e) => {
+						success = false;
+						report.status = StepStatus::Failed;
+						report.error = Some(e.to_string());
+					},
+				}
+			}
+			steps.push(report);
+		}
+		ScenarioReport { url: self.url.clone(), success, steps }
+	}
+
+	// Runs a step, returning its outputs.
+	async fn run_step(&self, step: &Step, outputs: &Map<String, Value>) -> anyhow::Result<Value> {
+		let suri = step.suri.clone().unwrap_or_else(|| self.suri.clone());
+		let scheme = step.scheme.unwrap_or(self.scheme);
+		let password = self.passwords.get(&suri).cloned();
+		match &step.action {
+			Action::Deploy(deploy) => {
+				let path = Some(self.dir.join(&deploy.path));
+				let constructor =
+					get_function(&path, &deploy.constructor, FunctionType::Constructor)?;
+				let instantiate_exec = set_up_deployment(UpOpts {
+					args: substitute_args(&deploy.args, &constructor.args, outputs)?,
+					path,
+					constructor: deploy.constructor.clone(),
+					value: substitute(&deploy.value, outputs)?,
+					gas_limit: None,
+					proof_size: None,
+					salt: deploy.salt.as_deref().map(parse_hex_bytes).transpose()?,
+					storage_deposit_limit: None,
+					url: self.url.clone(),
+					suri,
+					scheme,
+					password,
+					code_hash: None,
+				})
+				.await?;
+				let weight_limit = dry_run_gas_estimate_instantiate(&instantiate_exec).await?;
+				let contract_info = instantiate_smart_contract(
+					instantiate_exec,
+					weight_limit,
+					&TxOpts::default(),
+					|_| {},
+				)
+				.await
+				.map_err(|e| anyhow!("{e}"))?;
+				Ok(json!({
+					"address": contract_info.address,
+					"code_hash": contract_info.code_hash,
+					"block_number": contract_info.block_number,
+					"block_hash": contract_info.block_hash,
+				}))
+			},
+			Action::Call(call) => {
+				let path = Some(self.dir.join(&call.path));
+				let message = get_function(&path, &call.message, FunctionType::Message)?;
+				let call_exec = set_up_call(CallOpts {
+					args: substitute_args(&call.args, &message.args, outputs)?,
+					path,
+					contract: substitute(&call.contract, outputs)?,
+					message: call.message.clone(),
+					value: substitute(&call.value, outputs)?,
+					gas_limit: None,
+					proof_size: None,
+					storage_deposit_limit: None,
+					url: self.url.clone(),
+					suri,
+					scheme,
+					password,
+					execute: call.execute,
+				})
+				.await?;
+				let dry_run = dry_run_call(&call_exec).await?;
+				check_expectations(call, &dry_run.result)?;
+				let mut output = match &dry_run.result {
+					Ok(value) => json!({ "result": value }),
+					Err(error) => json!({ "error": error.to_string() }),
+				};
+				// Calls expected to revert would fail on-chain, so are only dry-run.
+				if call.execute && dry_run.result.is_ok() {
+					let weight_limit = dry_run_gas_estimate_call(&call_exec).await?;
+					let result = call_smart_contract(
+						call_exec,
+						weight_limit,
+						&self.url,
+						&TxOpts::default(),
+						|_| {},
+					)
+					.await
+					.map_err(|e| anyhow!("{e}"))?;
+					output["events"] = serde_json::to_value(&result.events)?;
+				}
+				Ok(output)
+			},
+		}
+	}
+}
+
+// Checks the result of the dry run of a call matches the expectations of the step.
+fn check_expectations(call: &CallStep, result: &Result<Value, ContractError>) -> Result<(), Error> {
+	match (&call.expect, &call.expect_revert, result) {
+		(Some(expected), _, Ok(value)) if value != expected =>
+			Err(Error::Scenario(format!("expected {expected}, found {value}"))),
+		(Some(expected), _, Err(error)) =>
+			Err(Error::Scenario(format!("expected {expected}, but the call failed: {error}"))),
+		(_, Some(expected), Ok(value)) =>
+			Err(Error::Scenario(format!("expected a revert with {expected}, found {value}"))),
+		(_, Some(expected), Err(ContractError::Revert(error))) if error != expected =>
+			Err(Error::Scenario(format!("expected a revert with {expected}, found {error}"))),
+		(_, Some(_), Err(ContractError::Revert(_))) => Ok(()),
+		(_, Some(expected), Err(error)) => Err(Error::Scenario(format!(
+			"expected a revert with {expected}, but the call failed: {error}"
+		))),
+		(None, None, Err(error)) => Err(Error::Scenario(format!("the call failed: {error}"))),
+		_ => Ok(()),
+	}
+}
+
+// Substitutes references to the outputs of earlier steps within the arguments, which are provided
+// as strings to the transcoder. Arguments for string parameters are quoted, as the transcoder
+// expects, unless already quoted.
+fn substitute_args(
+	args: &[Value],
+	params: &[Param],
+	outputs: &Map<String, Value>,
+) -> Result<Vec<String>, Error> {
+	args.iter()
+		.enumerate()
+		.map(|(i, arg)| match arg {
+			Value::String(arg) => {
+				let arg = substitute(arg, outputs)?;
+				let is_string = params.get(i).is_some_and(|param| param.type_name == "str");
+				let is_quoted = arg.len() > 1 && arg.starts_with('"') && arg.ends_with('"');
+				Ok(match is_string && !is_quoted {
+					true => Value::String(arg).to_string(),
+					false => arg,
+				})
+			},
+			arg => Ok(arg.to_string()),
+		})
+		.collect()
+}
+
+/// Substitutes references to the outputs of earlier steps, of the form `${step.field}`, where the
+/// field may be a path into a JSON output such as `${balance.result.Ok}`.
+///
+/// # Arguments
+///
+/// * `input` - the input containing references.
+/// * `outputs` - the outputs of earlier steps, by step name.
+pub(crate) fn substitute(input: &str, outputs: &Map<String, Value>) -> Result<String, Error> {
+	let mut error = None;
+	let substituted = REFERENCE.replace_all(input, |captures: &Captures| {
+		let mut value = outputs.get(&captures[1]);
+		for field in captures[2].split('.').skip(1) {
+			value = value.and_then(|value| match value {
+				Value::Array(items) => field.parse::<usize>().ok().and_then(|i| items.get(i)),
+				value => value.get(field),
+			});
+		}
+		match value {
+			Some(Value::String(value)) => value.clone(),
+			Some(value) => value.to_string(),
+			None => {
+				error.get_or_insert_with(|| {
+					Error::Scenario(format!("unknown reference `{}`", &captures[0]))
+				});
+				String::new()
+			},
+		}
+	});
+	match error {
+		Some(error) => Err(error),
+		None => Ok(substituted.into_owned()),
+	}
+}
+
+fn default_url() -> Url {
+	Url::parse("ws://localhost:9944").expect("valid url")
+}
+
+fn default_suri() -> String {
+	"//Alice".to_string()
+}
+
+fn default_constructor() -> String {
+	"new".to_string()
+}
+
+fn default_value() -> String {
+	"0".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const SCENARIO: &str = r#"
+url = "ws://localhost:9955"
+
+[[step]]
+name = "flipper"
+action = "deploy"
+path = "./flipper"
+args = [false]
+
+[[step]]
+name = "flip"
+action = "call"
+path = "./flipper"
+contract = "${flipper.address}"
+message = "flip"
+execute = true
+suri = "//Bob"
+
+[[step]]
+name = "get"
+action = "call"
+path = "./flipper"
+contract = "${flipper.address}"
+message = "get"
+expect = true
+"#;
+
+	fn call_step(expect: Option<Value>, expect_revert: Option<Value>) -> CallStep {
+		CallStep {
+			path: PathBuf::new(),
+			contract: String::new(),
+			message: String::new(),
+			args: Vec::new(),
+			value: default_value(),
+			execute: false,
+			expect,
+			expect_revert,
+		}
+	}
+
+	#[test]
+	fn load_scenario_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("scenario.toml");
+		fs::write(&path, SCENARIO)?;
+		let scenario = Scenario::load(&path)?;
+		assert_eq!(scenario.url.as_str(), "ws://localhost:9955/");
+		assert_eq!(scenario.suri, "//Alice");
+		assert_eq!(scenario.dir, temp_dir.path());
+		assert_eq!(scenario.steps.len(), 3);
+		let Action::Deploy(deploy) = &scenario.steps[0].action else {
+			panic!("expected a deployment")
+		};
+		assert_eq!(deploy.constructor, "new");
+		assert_eq!(deploy.args, vec![json!(false)]);
+		let Action::Call(call) = &scenario.steps[1].action else { panic!("expected a call") };
+		assert!(call.execute);
+		assert_eq!(scenario.steps[1].suri.as_deref(), Some("//Bob"));
+		let Action::Call(call) = &scenario.steps[2].action else { panic!("expected a call") };
+		assert_eq!(call.expect, Some(json!(true)));
+		Ok(())
+	}
+
+	#[test]
+	fn load_scenario_rejects_invalid_steps() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("scenario.toml");
+		fs::write(&path, SCENARIO.replace("name = \"get\"", "name = \"flip\""))?;
+		assert!(
+			matches!(Scenario::load(&path), Err(Error::Scenario(e)) if e.contains("duplicate"))
+		);
+		fs::write(&path, format!("{SCENARIO}expect_revert = \"NotOwner\""))?;
+		assert!(matches!(Scenario::load(&path), Err(Error::Scenario(e)) if e.contains("both")));
+		fs::write(&path, SCENARIO.replace("action = \"deploy\"", "action = \"upgrade\""))?;
+		assert!(matches!(Scenario::load(&path), Err(Error::Scenario(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn substitute_works() -> Result<()> {
+		let mut outputs = Map::new();
+		outputs.insert("token".into(), json!({ "address": "5Ca", "result": { "Ok": [1, 2] } }));
+		assert_eq!(substitute("${token.address}", &outputs)?, "5Ca");
+		assert_eq!(substitute("to ${token.address}!", &outputs)?, "to 5Ca!");
+		assert_eq!(substitute("${token.result.Ok}", &outputs)?, "[1,2]");
+		assert_eq!(substitute("${token.result.Ok.1}", &outputs)?, "2");
+		assert_eq!(substitute("no references", &outputs)?, "no references");
+		assert!(matches!(substitute("${token.missing}", &outputs), Err(Error::Scenario(..))));
+		assert!(matches!(substitute("${unknown.address}", &outputs), Err(Error::Scenario(..))));
+		assert_eq!(
+			substitute_args(&[json!("${token.address}"), json!(10), json!(true)], &[], &outputs)?,
+			vec!["5Ca", "10", "true"]
+		);
+		let params = ["AccountId", "str", "str"]
+			.map(|type_name| Param { label: "param".into(), type_name: type_name.into() });
+		assert_eq!(
+			substitute_args(
+				&[json!("${token.address}"), json!("to ${token.address}"), json!("\"quoted\"")],
+				&params,
+				&outputs
+			)?,
+			vec!["5Ca", "\"to 5Ca\"", "\"quoted\""]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn check_expectations_works() {
+		let revert = || Err(ContractError::Revert(json!("NotOwner")));
+		let other = || Err(ContractError::Other("trapped".into()));
+		assert!(check_expectations(&call_step(None, None), &Ok(json!(true))).is_ok());
+		assert!(check_expectations(&call_step(None, None), &revert()).is_err());
+		let expect = call_step(Some(json!(true)), None);
+		assert!(check_expectations(&expect, &Ok(json!(true))).is_ok());
+		assert!(check_expectations(&expect, &Ok(json!(false))).is_err());
+		assert!(check_expectations(&expect, &revert()).is_err());
+		let expect_revert = call_step(None, Some(json!("NotOwner")));
+		assert!(check_expectations(&expect_revert, &revert()).is_ok());
+		assert!(check_expectations(&expect_revert, &Ok(json!(true))).is_err());
+		assert!(check_expectations(&expect_revert, &other()).is_err());
+		assert!(check_expectations(
+			&expect_revert,
+			&Err(ContractError::Revert(json!("InsufficientBalance")))
+		)
+		.is_err());
+	}
+}
@@ -0,0 +1,466 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	call::resolve_contract,
+	errors::Error,
+	utils::{decode::value_to_json, metadata::get_metadata},
+};
+use contract_extrinsics::ContractStorageRpc;
+use contract_transcode::{
+	ink_metadata::layout::{Layout, StructLayout},
+	ContractMessageTranscoder,
+};
+use ink_env::DefaultEnvironment;
+use scale_info::{form::PortableForm, Type};
+use serde::Serialize;
+use sp_core::bytes::to_hex;
+use std::{collections::BTreeMap, path::PathBuf};
+use subxt::{ext::codec::Decode, PolkadotConfig as DefaultConfig};
+use url::Url;
+
+// The number of storage keys fetched per request.
+const KEYS_PER_PAGE: u32 = 1000;
+
+/// The storage of a contract, decoded using the storage layout within its metadata.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ContractStorage {
+	/// The root of the storage of the contract.
+	pub root: StorageNode,
+	/// The storage cells which could not be decoded, e.g. as they are not described by the
+	/// storage layout.
+	pub raw: Vec<RawCell>,
+}
+
+/// A node within the tree of the storage of a contract.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StorageNode {
+	/// A struct, or the variant of an enum, with its fields.
+	Struct { name: String, fields: Vec<StorageField> },
+	/// An array, or the values within a `StorageVec`.
+	Array { values: Vec<StorageNode> },
+	/// A value, which is `null` when it has not been set.
+	Value { value: serde_json::Value },
+	/// A `Mapping`, with the entries stored within it.
+	Mapping { root_key: String, entries: Vec<MappingEntry> },
+	/// A value which could not be decoded. The storage cell holding it is included within the
+	/// raw cells.
+	Undecoded,
+}
+
+/// A field of a struct within the storage of a contract.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StorageField {
+	/// The name of the field.
+	pub name: String,
+	/// The value of the field.
+	pub value: StorageNode,
+}
+
+/// An entry stored within a `Mapping`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MappingEntry {
+	/// The key of the entry.
+	pub key: serde_json::Value,
+	/// The value of the entry.
+	pub value: serde_json::Value,
+}
+
+/// A storage cell, as stored on-chain.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RawCell {
+	/// The storage key of the cell, as hex.
+	pub key: String,
+	/// The value of the cell, as hex.
+	pub value: String,
+}
+
+impl ContractStorage {
+	/// Looks up the value stored within a `Mapping` for a key.
+	///
+	/// # Arguments
+	///
+	/// * `field` - the path of the mapping within the storage, with the names of its parent fields
+	///   separated by `.`, e.g. `balances`.
+	/// * `key` - the key to look up, as shown when listing the entries of the mapping.
+	pub fn lookup(&self, field: &str, key: &str) -> Result<Option<&serde_json::Value>, Error> {
+		let mut node = &self.root;
+		for name in field.split('.') {
+			let StorageNode::Struct { fields, .. } = node else {
+				return Err(Error::InvalidStorageField(format!("`{field}` not found")));
+			};
+			node = fields
+				.iter()
+				.find(|f| f.name == name)
+				.map(|f| &f.value)
+				.ok_or_else(|| Error::InvalidStorageField(format!("`{field}` not found")))?;
+		}
+		let StorageNode::Mapping { entries, .. } = node else {
+			return Err(Error::InvalidStorageField(format!("`{field}` is not a mapping")));
+		};
+		Ok(entries
+			.iter()
+			.find(|entry| key_matches(&entry.key, key))
+			.map(|entry| &entry.value))
+	}
+}
+
+/// Reads the storage of a deployed contract, decoding it using the storage layout within the
+/// metadata of the contract.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract build folder or metadata file.
+/// * `contract` - the address of the contract, or the alias or name of a deployment.
+/// * `url` - websocket endpoint of a node.
+pub async fn get_contract_storage(
+	path: &Option<PathBuf>,
+	contract: &str,
+	url: &Url,
+) -> anyhow::Result<ContractStorage> {
+	let transcoder = ContractMessageTranscoder::new(get_metadata(path)?);
+	let contract = resolve_contract(path, contract, url)?;
+	let rpc = ContractStorageRpc::<DefaultConfig>::new(url).await?;
+	let contract_info = rpc.fetch_contract_info::<DefaultEnvironment>(&contract).await?;
+	let trie_id = contract_info.trie_id();
+
+	let mut cells = Vec::new();
+	// Each page starts after the last key of the previous page, as the values of some keys may be
+	// missing.
+	let mut start_key: Option<Vec<u8>> = None;
+	loop {
+		let keys = rpc
+			.fetch_storage_keys_paged(trie_id, None, KEYS_PER_PAGE, start_key.as_deref(), None)
+			.await?;
+		let values = rpc.fetch_storage_entries(trie_id, &keys, None).await?;
+		let count = keys.len();
+		start_key = keys.last().map(|key| key.0.clone());
+		cells.extend(
+			keys.into_iter()
+				.zip(values)
+				.filter_map(|(key, value)| value.map(|value| (key.0, value.0))),
+		);
+		if count < KEYS_PER_PAGE as usize {
+			break;
+		}
+	}
+	Ok(decode_storage(&transcoder, cells))
+}
+
+/// Decodes the storage cells of a contract using the storage layout within its metadata.
+///
+/// # Arguments
+///
+/// * `transcoder` - the transcoder for the contract.
+/// * `cells` - the keys and values of the storage cells of the contract.
+pub(crate) fn decode_storage(
+	transcoder: &ContractMessageTranscoder,
+	cells: Vec<Cell>,
+) -> ContractStorage {
+	let mut decoder = StorageDecoder { transcoder, cells: BTreeMap::new(), raw: Vec::new() };
+	for (key, value) in cells {
+		match split_key(&key) {
+			Some(parts) => {
+				decoder.cells.insert(parts, (key, value));
+			},
+			None => decoder.raw.push(raw_cell(&key, &value)),
+		}
+	}
+	let root = decoder.decode_layout(transcoder.metadata().layout(), &mut CellInput::Missing);
+	// Any remaining cells are not described by the storage layout.
+	let StorageDecoder { cells, mut raw, .. } = decoder;
+	raw.extend(cells.values().map(|(key, value)| raw_cell(key, value)));
+	ContractStorage { root, raw }
+}
+
+// The key and value of a storage cell.
+type Cell = (Vec<u8>, Vec<u8>);
+
+// The input for decoding the values within a storage cell.
+enum CellInput<'a> {
+	// The remaining bytes of the cell.
+	Bytes(&'a [u8]),
+	// The cell has not been set.
+	Missing,
+	// A value within the cell could not be decoded.
+	Failed,
+}
+
+struct StorageDecoder<'a> {
+	transcoder: &'a ContractMessageTranscoder,
+	// The cells not yet decoded, keyed by their root key and mapping key.
+	cells: BTreeMap<(u32, Vec<u8>), Cell>,
+	raw: Vec<RawCell>,
+}
+
+impl StorageDecoder<'_> {
+	fn decode_layout(
+		&mut self,
+		layout: &Layout<PortableForm>,
+		input: &mut CellInput,
+	) -> StorageNode {
+		match layout {
+			Layout::Root(root) => {
+				let root_key = *root.root_key().key();
+				let registry = self.transcoder.metadata().registry();
+				let ty = registry.resolve(root.ty().id);
+				match ty.map(|ty| ty.path.to_string()).as_deref() {
+					Some("ink_storage::lazy::mapping::Mapping") =>
+						self.decode_mapping(root_key, ty.expect("type resolved above; qed")),
+					Some("ink_storage::lazy::vec::StorageVec") =>
+						self.decode_storage_vec(root_key, ty.expect("type resolved above; qed")),
+					_ => {
+						let cell = self.cells.remove(&(root_key, Vec::new()));
+						let mut input = match &cell {
+							Some((_, value)) => CellInput::Bytes(value),
+							None => CellInput::Missing,
+						};
+						let node = self.decode_layout(root.layout(), &mut input);
+						if let (CellInput::Failed, Some((key, value))) = (input, &cell) {
+							self.raw.push(raw_cell(key, value));
+						}
+						node
+					},
+				}
+			},
+			Layout::Leaf(leaf) => self.decode_value(leaf.ty().id, input),
+			Layout::Struct(layout) => self.decode_struct(layout, input),
+			Layout::Enum(layout) => {
+				let discriminant = match input {
+					CellInput::Bytes(bytes) => match u8::decode(bytes) {
+						Ok(discriminant) => discriminant,
+						Err(_) => {
+							*input = CellInput::Failed;
+							return StorageNode::Undecoded;
+						},
+					},
+					CellInput::Missing =>
+						return StorageNode::Value { value: serde_json::Value::Null },
+					CellInput::Failed => return StorageNode::Undecoded,
+				};
+				match layout.variants().iter().find(|(d, _)| d.value() == discriminant as usize) {
+					Some((_, variant)) => self.decode_struct(variant, input),
+					None => {
+						*input = CellInput::Failed;
+						StorageNode::Undecoded
+					},
+				}
+			},
+			Layout::Array(layout) => StorageNode::Array {
+				values: (0..layout.len())
+					.map(|_| self.decode_layout(layout.layout(), input))
+					.collect(),
+			},
+			Layout::Hash(layout) => self.decode_layout(layout.layout(), input),
+		}
+	}
+
+	fn decode_struct(
+		&mut self,
+		layout: &StructLayout<PortableForm>,
+		input: &mut CellInput,
+	) -> StorageNode {
+		let fields = layout
+			.fields()
+			.iter()
+			.map(|field| StorageField {
+				name: field.name().to_string(),
+				value: self.decode_layout(field.layout(), input),
+			})
+			.collect();
+		StorageNode::Struct { name: layout.name().to_string(), fields }
+	}
+
+	// Decodes a value of the specified type from the input, marking the input as failed if it could
+	// not be decoded.
+	fn decode_value(&self, type_id: u32, input: &mut CellInput) -> StorageNode {
+		match input {
+			CellInput::Bytes(bytes) => match self.transcoder.decode(type_id, bytes) {
+				Ok(value) => StorageNode::Value { value: value_to_json(&value) },
+				Err(_) => {
+					*input = CellInput::Failed;
+					StorageNode::Undecoded
+				},
+			},
+			CellInput::Missing => StorageNode::Value { value: serde_json::Value::Null },
+			CellInput::Failed => StorageNode::Undecoded,
+		}
+	}
+
+	fn decode_mapping(&mut self, root_key: u32, ty: &Type<PortableForm>) -> StorageNode {
+		let (key_ty, value_ty) = (type_param(ty, "K"), type_param(ty, "V"));
+		let mut entries = Vec::new();
+		for (key, value) in self.take_cells(root_key) {
+			let (_, mapping_key) = split_key(&key).expect("cell keys were split when stored; qed");
+			let entry = key_ty.zip(value_ty).and_then(|(key_ty, value_ty)| {
+				Some(MappingEntry {
+					key: value_to_json(
+						&self.transcoder.decode(key_ty, &mut &mapping_key[..]).ok()?,
+					),
+					value: value_to_json(&self.transcoder.decode(value_ty, &mut &value[..]).ok()?),
+				})
+			});
+			match entry {
+				Some(entry) => entries.push(entry),
+				None => self.raw.push(raw_cell(&key, &value)),
+			}
+		}
+		StorageNode::Mapping { root_key: format_root_key(root_key), entries }
+	}
+
+	fn decode_storage_vec(&mut self, root_key: u32, ty: &Type<PortableForm>) -> StorageNode {
+		let value_ty = type_param(ty, "V");
+		let mut values = Vec::new();
+		for (key, value) in self.take_cells(root_key) {
+			let (_, mapping_key) = split_key(&key).expect("cell keys were split when stored; qed");
+			// The length of the vector is stored at the root key, followed by its values keyed by
+			// their index.
+			if mapping_key.is_empty() {
+				continue;
+			}
+			match value_ty.and_then(|ty| self.transcoder.decode(ty, &mut &value[..]).ok()) {
+				Some(value) => values.push(StorageNode::Value { value: value_to_json(&value) }),
+				None => {
+					values.push(StorageNode::Undecoded);
+					self.raw.push(raw_cell(&key, &value));
+				},
+			}
+		}
+		StorageNode::Array { values }
+	}
+
+	// Removes the cells stored under a root key, ordered by their mapping key.
+	fn take_cells(&mut self, root_key: u32) -> Vec<Cell> {
+		let keys: Vec<_> = self.cells.keys().filter(|(key, _)| *key == root_key).cloned().collect();
+		keys.iter().filter_map(|key| self.cells.remove(key)).collect()
+	}
+}
+
+// Splits the key of a storage cell into its root key and mapping key. The key is the
+// `blake2_128` hash of the storage key, followed by the storage key: the SCALE encoded root key
+// followed by the SCALE encoded mapping key, if any.
+fn split_key(key: &[u8]) -> Option<(u32, Vec<u8>)> {
+	let storage_key = key.get(16..)?;
+	let root_key = u32::decode(&mut storage_key.get(..4)?).ok()?;
+	Some((root_key, storage_key[4..].to_vec()))
+}
+
+// Returns the identifier of the type parameter with the specified name.
+fn type_param(ty: &Type<PortableForm>, name: &str) -> Option<u32> {
+	ty.type_params.iter().find(|param| param.name == name)?.ty.map(|ty| ty.id)
+}
+
+// Formats a root key as hex, as shown within the storage layout of the metadata.
+fn format_root_key(root_key: u32) -> String {
+	to_hex(&root_key.to_le_bytes(), false)
+}
+
+fn raw_cell(key: &[u8], value: &[u8]) -> RawCell {
+	RawCell { key: to_hex(key, false), value: to_hex(value, false) }
+}
+
+// Whether the key of a mapping entry matches the specified key, either as a string or as JSON.
+fn key_matches(entry_key: &serde_json::Value, key: &str) -> bool {
+	match entry_key {
+		serde_json::Value::String(entry_key) => entry_key == key,
+		entry_key =>
+			serde_json::from_str::<serde_json::Value>(key).is_ok_and(|key| &key == entry_key),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+	use sp_core::hashing::blake2_128;
+	use subxt::{ext::codec::Encode, utils::AccountId32};
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+	// The root key of the `flips` mapping of the testing contract.
+	const FLIPS: u32 = u32::from_le_bytes([0xce, 0xd2, 0x44, 0xbe]);
+
+	fn testing_transcoder() -> Result<ContractMessageTranscoder> {
+		let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/files/testing.json");
+		Ok(ContractMessageTranscoder::new(get_metadata(&Some(path))?))
+	}
+
+	// Returns the key of a storage cell, as stored on-chain.
+	fn cell_key(root_key: u32, mapping_key: impl Encode) -> Vec<u8> {
+		let storage_key = [root_key.encode(), mapping_key.encode()].concat();
+		[blake2_128(&storage_key).to_vec(), storage_key].concat()
+	}
+
+	fn field(name: &str, value: StorageNode) -> StorageField {
+		StorageField { name: name.to_string(), value }
+	}
+
+	#[test]
+	fn decode_storage_works() -> Result<()> {
+		let (alice, bob): (AccountId32, AccountId32) = (ALICE.parse()?, BOB.parse()?);
+		let unknown = (cell_key(42, ()), vec![1]);
+		let cells = vec![
+			(cell_key(0, ()), (true, 7u32, alice.clone()).encode()),
+			(cell_key(FLIPS, alice.clone()), 3u32.encode()),
+			// A value which is too short to be decoded as a `u32`.
+			(cell_key(FLIPS, bob), vec![1]),
+			unknown.clone(),
+		];
+		let storage = decode_storage(&testing_transcoder()?, cells);
+		assert_eq!(
+			storage.root,
+			StorageNode::Struct {
+				name: "Testing".to_string(),
+				fields: vec![
+					field("value", StorageNode::Value { value: json!(true) }),
+					field("number", StorageNode::Value { value: json!(7) }),
+					field("owner", StorageNode::Value { value: json!(ALICE) }),
+					field(
+						"flips",
+						StorageNode::Mapping {
+							root_key: "0xced244be".to_string(),
+							entries: vec![MappingEntry { key: json!(ALICE), value: json!(3) }],
+						}
+					),
+				],
+			}
+		);
+		assert_eq!(storage.raw.len(), 2);
+		assert!(storage.raw.contains(&raw_cell(&unknown.0, &unknown.1)));
+		assert_eq!(storage.lookup("flips", ALICE)?, Some(&json!(3)));
+		assert_eq!(storage.lookup("flips", BOB)?, None);
+		assert!(matches!(storage.lookup("value", ALICE), Err(Error::InvalidStorageField(..))));
+		assert!(matches!(storage.lookup("unknown", ALICE), Err(Error::InvalidStorageField(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn decode_storage_returns_raw_cells_which_cannot_be_decoded() -> Result<()> {
+		// The root cell is too short to contain the fields of the contract.
+		let root = (cell_key(0, ()), vec![1]);
+		let storage = decode_storage(&testing_transcoder()?, vec![root.clone()]);
+		let StorageNode::Struct { fields, .. } = &storage.root else {
+			panic!("expected the root of the storage to be a struct")
+		};
+		assert_eq!(fields[0].value, StorageNode::Value { value: json!(true) });
+		assert_eq!(fields[1].value, StorageNode::Undecoded);
+		assert_eq!(fields[2].value, StorageNode::Undecoded);
+		assert_eq!(storage.raw, vec![raw_cell(&root.0, &root.1)]);
+		Ok(())
+	}
+
+	#[test]
+	fn decode_storage_of_empty_contract_works() -> Result<()> {
+		let storage = decode_storage(&testing_transcoder()?, Vec::new());
+		let StorageNode::Struct { fields, .. } = &storage.root else {
+			panic!("expected the root of the storage to be a struct")
+		};
+		assert_eq!(fields[0].value, StorageNode::Value { value: json!(null) });
+		assert_eq!(
+			fields[3].value,
+			StorageNode::Mapping { root_key: "0xced244be".to_string(), entries: vec![] }
+		);
+		assert!(storage.raw.is_empty());
+		Ok(())
+	}
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: GPL-3.0
+use strum::{EnumMessage as _, EnumProperty as _, VariantArray as _};
+use strum_macros::{AsRefStr, Display, EnumMessage, EnumProperty, EnumString, VariantArray};
+
+/// Supported contract templates.
+#[derive(
+	AsRefStr,
+	Clone,
+	Default,
+	Debug,
+	Display,
+	EnumMessage,
+	EnumProperty,
+	EnumString,
+	Eq,
+	Hash,
+	PartialEq,
+	VariantArray,
+)]
+pub enum ContractTemplate {
+	/// The flipper contract, generated by cargo-contract.
+	#[default]
+	#[strum(
+		serialize = "standard",
+		message = "Standard",
+		detailed_message = "ink!'s 'Hello World': Flipper"
+	)]
+	Standard,
+	/// A fungible token implementing the PSP22 standard.
+	#[strum(
+		serialize = "psp22",
+		message = "PSP22",
+		detailed_message = "A fungible token implementing the PSP22 standard.",
+		props(Repository = "https://github.com/Cardinal-Cryptography/PSP22", Tag = "v2.0")
+	)]
+	PSP22,
+	/// A non-fungible token implementing the PSP34 standard.
+	#[strum(
+		serialize = "psp34",
+		message = "PSP34",
+		detailed_message = "A non-fungible token implementing the PSP34 standard.",
+		props(Repository = "https://github.com/Cardinal-Cryptography/PSP34", Tag = "v1.0")
+	)]
+	PSP34,
+	/// A wallet requiring the confirmation of several owners to execute transactions.
+	#[strum(
+		serialize = "multisig",
+		message = "Multisig",
+		detailed_message = "A wallet requiring the confirmation of several owners to execute transactions.",
+		props(
+			Repository = "https://github.com/use-ink/ink-examples",
+			Tag = "v5.0.0",
+			Path = "multisig"
+		)
+	)]
+	Multisig,
+	/// A DAO whose members vote on proposals to spend its funds.
+	#[strum(
+		serialize = "dao",
+		message = "DAO",
+		detailed_message = "A DAO whose members vote on proposals to spend its funds."
+	)]
+	DAO,
+	/// A contract calling another contract.
+	#[strum(
+		serialize = "cross-contract",
+		message = "Cross-Contract Caller",
+		detailed_message = "A contract calling another contract.",
+		props(
+			Repository = "https://github.com/use-ink/ink-examples",
+			Tag = "v5.0.0",
+			Path = "cross-contract-calls"
+		)
+	)]
+	CrossContract,
+	/// A proxy delegating calls to an upgradeable implementation contract.
+	#[strum(
+		serialize = "upgradeable-proxy",
+		message = "Upgradeable Proxy",
+		detailed_message = "A proxy delegating calls to an upgradeable implementation contract.",
+		props(
+			Repository = "https://github.com/use-ink/ink-examples",
+			Tag = "v5.0.0",
+			Path = "upgradeable-contracts/delegator"
+		)
+	)]
+	UpgradeableProxy,
+}
+
+impl ContractTemplate {
+	/// Get the list of templates supported.
+	pub fn templates() -> &'static [ContractTemplate] {
+		ContractTemplate::VARIANTS
+	}
+
+	/// Get the template's name.
+	pub fn name(&self) -> &str {
+		self.get_message().unwrap_or_default()
+	}
+
+	/// Get the detailed message of the template.
+	pub fn description(&self) -> &str {
+		self.get_detailed_message().unwrap_or_default()
+	}
+
+	/// Get the template's repository url, if the template is not bundled with Pop.
+	pub fn repository_url(&self) -> Option<&str> {
+		self.get_str("Repository")
+	}
+
+	/// Get the tag of the template's repository from which the template is generated.
+	pub fn tag(&self) -> Option<&str> {
+		self.get_str("Tag")
+	}
+
+	/// Get the location of the template within its repository, if not at the root.
+	pub fn path(&self) -> Option<&str> {
+		self.get_str("Path")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{collections::HashMap, str::FromStr};
+
+	fn templates_names() -> HashMap<String, ContractTemplate> {
+		HashMap::from([
+			("standard".to_string(), ContractTemplate::Standard),
+			("psp22".to_string(), ContractTemplate::PSP22),
+			("psp34".to_string(), ContractTemplate::PSP34),
+			("multisig".to_string(), ContractTemplate::Multisig),
+			("dao".to_string(), ContractTemplate::DAO),
+			("cross-contract".to_string(), ContractTemplate::CrossContract),
+			("upgradeable-proxy".to_string(), ContractTemplate::UpgradeableProxy),
+		])
+	}
+
+	fn templates_urls() -> HashMap<String, Option<&'static str>> {
+		HashMap::from([
+			("standard".to_string(), None),
+			("psp22".to_string(), Some("https://github.com/Cardinal-Cryptography/PSP22")),
+			("psp34".to_string(), Some("https://github.com/Cardinal-Cryptography/PSP34")),
+			("multisig".to_string(), Some("https://github.com/use-ink/ink-examples")),
+			("dao".to_string(), None),
+			("cross-contract".to_string(), Some("https://github.com/use-ink/ink-examples")),
+			("upgradeable-proxy".to_string(), Some("https://github.com/use-ink/ink-examples")),
+		])
+	}
+
+	#[test]
+	fn test_convert_string_to_template() {
+		let template_names = templates_names();
+		// Test the default
+		assert_eq!(ContractTemplate::from_str("").unwrap_or_default(), ContractTemplate::Standard);
+		// Test the rest
+		for template in ContractTemplate::templates() {
+			assert_eq!(
+				&ContractTemplate::from_str(&template.to_string()).unwrap(),
+				template_names.get(&template.to_string()).unwrap()
+			);
+		}
+	}
+
+	#[test]
+	fn test_repository_url() {
+		let template_urls = templates_urls();
+		for template in ContractTemplate::templates() {
+			assert_eq!(
+				&template.repository_url(),
+				template_urls.get(&template.to_string()).unwrap()
+			);
+		}
+	}
+
+	#[test]
+	fn test_repository_templates_are_pinned() {
+		for template in ContractTemplate::templates() {
+			assert_eq!(template.repository_url().is_some(), template.tag().is_some());
+		}
+	}
+
+	#[test]
+	fn test_templates_have_descriptions() {
+		for template in ContractTemplate::templates() {
+			assert!(!template.name().is_empty());
+			assert!(!template.description().is_empty());
+		}
+	}
+}
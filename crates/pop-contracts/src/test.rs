@@ -1,56 +1,332 @@
 // SPDX-License-Identifier: GPL-3.0
 use crate::errors::Error;
-use duct::cmd;
-use std::path::PathBuf;
+use pop_common::{
+	run_stoppable_tests, run_tests, Status, TestHandle, TestOpts, TestReport, TestStatus,
+};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use toml_edit::DocumentMut;
+use url::Url;
+use walkdir::WalkDir;
 
-/// Run unit tests of a smart contract.
+/// The environment variable from which ink! end-to-end tests read the path of the node binary.
+pub const CONTRACTS_NODE_ENV: &str = "CONTRACTS_NODE";
+/// The environment variable from which ink! end-to-end tests read the endpoint of a running node,
+/// which is then used instead of starting a node for each test.
+pub const CONTRACTS_NODE_URL_ENV: &str = "CONTRACTS_NODE_URL";
+// The feature of a contract which enables its end-to-end tests.
+const E2E_FEATURE: &str = "e2e-tests";
+// The features of `ink_e2e` which provide its sandboxed runtime backend, by ink! version.
+const SANDBOX_BACKENDS: [&str; 2] = ["sandbox", "drink"];
+// The argument of `#[ink_e2e::test]` which selects the sandboxed runtime backend for a test.
+const SANDBOX_TEST: &str = "runtime_only";
+
+/// Run unit tests of a smart contract, returning the result of each test.
 ///
 /// # Arguments
 ///
 /// * `path` - location of the smart contract.
-pub fn test_smart_contract(path: &Option<PathBuf>) -> Result<(), Error> {
+/// * `opts` - options for running the tests.
+/// * `status` - observer of the output of the tests.
+pub fn test_smart_contract(
+	path: &Option<PathBuf>,
+	opts: &TestOpts,
+	status: &impl Status,
+) -> Result<TestReport, Error> {
 	// Execute `cargo test` command in the specified directory.
-	cmd("cargo", vec!["test"])
-		.dir(path.clone().unwrap_or_else(|| PathBuf::from("./")))
-		.run()
-		.map_err(|e| Error::TestCommand(format!("Cargo test command failed: {}", e)))?;
-	Ok(())
+	run_tests(&path.clone().unwrap_or_else(|| PathBuf::from("./")), opts, &[], status)
+		.map_err(|e| Error::TestCommand(format!("Cargo test command failed: {}", e)))
 }
 
-/// Run the e2e tests of a smart contract.
+/// Run the e2e tests of a smart contract, returning the result of each test.
 ///
 /// # Arguments
 ///
 /// * `path` - location of the smart contract.
-pub fn test_e2e_smart_contract(path: &Option<PathBuf>) -> Result<(), Error> {
+/// * `opts` - options for running the tests, to which the `e2e-tests` feature is added.
+/// * `node` - the path of the node binary to be used by the tests, if specified.
+/// * `url` - the endpoint of a running node against which the tests are run, if specified.
+/// * `status` - observer of the output of the tests.
+/// * `handle` - the handle through which the tests can be stopped.
+pub fn test_e2e_smart_contract(
+	path: &Option<PathBuf>,
+	opts: &TestOpts,
+	node: Option<&Path>,
+	url: Option<&Url>,
+	status: &impl Status,
+	handle: &TestHandle,
+) -> Result<TestReport, Error> {
 	// Execute `cargo test --features=e2e-tests` command in the specified directory.
-	cmd("cargo", vec!["test", "--features=e2e-tests"])
-		.dir(path.clone().unwrap_or_else(|| PathBuf::from("./")))
-		.run()
+	let mut opts = opts.clone();
+	if !opts.features.iter().any(|f| f == E2E_FEATURE) {
+		opts.features.push(E2E_FEATURE.to_string());
+	}
+	run_stoppable_tests(
+		&path.clone().unwrap_or_else(|| PathBuf::from("./")),
+		&opts,
+		&e2e_env(node, url),
+		status,
+		handle,
+	)
+	.map_err(|e| Error::TestCommand(format!("Cargo test command failed: {}", e)))
+}
+
+/// Run the end-to-end tests of a smart contract which select the sandboxed runtime backend of
+/// ink!, using `#[ink_e2e::test(backend(runtime_only))]`, returning the result of each test. These
+/// run without a node: the end-to-end tests using a node are skipped. The backend must be enabled
+/// by the contract, either on its `ink_e2e` dependency or by one of its features, which is then
+/// enabled for the tests along with the `e2e-tests` feature. Fails if the contract has no such
+/// tests, if none match the filter of `opts`, or if no tests were run.
+///
+/// # Arguments
+///
+/// * `path` - location of the smart contract.
+/// * `opts` - options for running the tests.
+/// * `status` - observer of the output of the tests.
+pub fn test_sandbox_smart_contract(
+	path: &Option<PathBuf>,
+	opts: &TestOpts,
+	status: &impl Status,
+) -> Result<TestReport, Error> {
+	let path = path.clone().unwrap_or_else(|| PathBuf::from("./"));
+	let mut opts = opts.clone();
+	let features = [Some(E2E_FEATURE.to_string()), sandbox_feature(&path.join("Cargo.toml"))?];
+	for feature in features.into_iter().flatten() {
+		if !opts.features.contains(&feature) {
+			opts.features.push(feature);
+		}
+	}
+	opts.skip.extend(node_tests(&e2e_tests(&path)?, opts.filter.as_deref())?);
+	let report = run_tests(&path, &opts, &[], status)
 		.map_err(|e| Error::TestCommand(format!("Cargo test command failed: {}", e)))?;
-	Ok(())
+	if report.tests.iter().all(|test| test.status == TestStatus::Ignored) {
+		return Err(Error::TestCommand("no tests were run within the sandbox".to_string()));
+	}
+	Ok(report)
+}
+
+/// An end-to-end test of a contract.
+#[derive(Clone, Debug, PartialEq)]
+struct E2eTest {
+	/// The name of the test function.
+	name: String,
+	/// Whether the test selects the sandboxed runtime backend, rather than using a node.
+	runtime_only: bool,
+}
+
+// Finds the end-to-end tests within the sources of a contract: the functions annotated with
+// `#[ink_e2e::test]`.
+fn e2e_tests(path: &Path) -> Result<Vec<E2eTest>, Error> {
+	let mut tests = Vec::new();
+	let entries = WalkDir::new(path)
+		.sort_by_file_name()
+		.into_iter()
+		.filter_entry(|e| e.depth() == 0 || !(e.file_type().is_dir() && e.file_name() == "target"));
+	for entry in entries {
+		let entry = entry.map_err(std::io::Error::from)?;
+		if entry.file_type().is_file() && entry.path().extension().is_some_and(|e| e == "rs") {
+			let source = fs::read_to_string(entry.path())?;
+			for (_, test) in
+				source.match_indices("#[ink_e2e::test").map(|(i, _)| source.split_at(i))
+			{
+				let Some((attribute, item)) = test.split_once(']') else { continue };
+				let Some((_, name)) = item.split_once("fn ") else { continue };
+				let name: String =
+					name.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+				if !name.is_empty() {
+					tests.push(E2eTest { name, runtime_only: attribute.contains(SANDBOX_TEST) });
+				}
+			}
+		}
+	}
+	Ok(tests)
+}
+
+// The names of the end-to-end tests to be skipped so that only those within the sandbox run,
+// given the filter selecting the tests to run. Fails if no tests within the sandbox would run, or
+// if a test using a node cannot be skipped without also skipping a test within the sandbox.
+fn node_tests(tests: &[E2eTest], filter: Option<&str>) -> Result<Vec<String>, Error> {
+	let (sandbox, node): (Vec<_>, Vec<_>) = tests.iter().partition(|test| test.runtime_only);
+	if sandbox.is_empty() {
+		return Err(Error::TestCommand(format!(
+			"no tests select the sandboxed runtime backend: annotate them with \
+			 `#[ink_e2e::test(backend({SANDBOX_TEST}))]`"
+		)));
+	}
+	if let Some(filter) = filter {
+		if !sandbox.iter().any(|test| test.name.contains(filter)) {
+			return Err(Error::TestCommand(format!(
+				"no tests selecting the sandboxed runtime backend match `{filter}`"
+			)));
+		}
+	}
+	let mut skip = Vec::new();
+	for test in node {
+		if let Some(sandboxed) = sandbox.iter().find(|s| s.name.contains(&test.name)) {
+			return Err(Error::TestCommand(format!(
+				"`{}` uses a node but cannot be skipped without skipping `{}`: rename either test",
+				test.name, sandboxed.name
+			)));
+		}
+		if !skip.contains(&test.name) {
+			skip.push(test.name.clone());
+		}
+	}
+	Ok(skip)
+}
+
+// Determines how a contract enables the sandboxed runtime backend of `ink_e2e`: directly on the
+// dependency, or by one of the features of the contract, whose name is returned.
+fn sandbox_feature(manifest: &Path) -> Result<Option<String>, Error> {
+	let manifest = fs::read_to_string(manifest)?
+		.parse::<DocumentMut>()
+		.map_err(|e| Error::TestCommand(format!("{}: {e}", manifest.display())))?;
+	let is_backend = |feature: &str| SANDBOX_BACKENDS.contains(&feature);
+
+	for dependencies in ["dependencies", "dev-dependencies"] {
+		let features = manifest
+			.get(dependencies)
+			.and_then(|d| d.get("ink_e2e"))
+			.and_then(|d| d.get("features"))
+			.and_then(|f| f.as_array());
+		if features.is_some_and(|f| f.iter().any(|f| f.as_str().is_some_and(is_backend))) {
+			return Ok(None);
+		}
+	}
+	if let Some(features) = manifest.get("features").and_then(|f| f.as_table_like()) {
+		for (name, enabled) in features.iter() {
+			let enables_backend = enabled.as_array().is_some_and(|enabled| {
+				enabled.iter().filter_map(|f| f.as_str()).any(|f| {
+					f.strip_prefix("ink_e2e")
+						.and_then(|f| f.trim_start_matches('?').strip_prefix('/'))
+						.is_some_and(is_backend)
+				})
+			});
+			if enables_backend {
+				return Ok(Some(name.to_string()));
+			}
+		}
+	}
+	Err(Error::TestCommand(
+		"the sandboxed runtime backend of ink_e2e is not enabled: enable its `sandbox` feature \
+		 (`drink` prior to ink! v5.1) to test within a sandbox"
+			.to_string(),
+	))
+}
+
+// The environment variables exported to the end-to-end tests.
+fn e2e_env(node: Option<&Path>, url: Option<&Url>) -> Vec<(&'static str, String)> {
+	let mut env = Vec::new();
+	if let Some(node) = node {
+		env.push((CONTRACTS_NODE_ENV, node.display().to_string()));
+	}
+	if let Some(url) = url {
+		env.push((CONTRACTS_NODE_URL_ENV, url.to_string()));
+	}
+	env
 }
 
-#[cfg(feature = "unit_contract")]
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use std::fs;
-	use tempfile;
 
+	#[cfg(feature = "unit_contract")]
 	fn setup_test_environment() -> Result<tempfile::TempDir, Error> {
 		let temp_dir = tempfile::tempdir()?;
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		crate::create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		crate::create_smart_contract(
+			"test_contract",
+			temp_contract_dir.as_path(),
+			&crate::ContractTemplate::Standard,
+		)?;
 		Ok(temp_dir)
 	}
 
+	#[test]
+	fn e2e_env_works() -> Result<(), Error> {
+		assert!(e2e_env(None, None).is_empty());
+		let url = Url::parse("ws://localhost:9944").expect("valid url");
+		assert_eq!(
+			e2e_env(Some(Path::new("./substrate-contracts-node")), Some(&url)),
+			vec![
+				(CONTRACTS_NODE_ENV, "./substrate-contracts-node".to_string()),
+				(CONTRACTS_NODE_URL_ENV, "ws://localhost:9944/".to_string())
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn sandbox_feature_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		let manifest = temp_dir.path().join("Cargo.toml");
+		fs::write(&manifest, "[dev-dependencies]\nink_e2e = { version = \"5.0.0\" }\n")?;
+		assert!(matches!(sandbox_feature(&manifest), Err(Error::TestCommand(..))));
+		fs::write(
+			&manifest,
+			"[dev-dependencies]\nink_e2e = { version = \"5.1.0\", features = [\"sandbox\"] }\n",
+		)?;
+		assert_eq!(sandbox_feature(&manifest)?, None);
+		fs::write(
+			&manifest,
+			"[dev-dependencies]\nink_e2e = { version = \"5.0.0\", optional = true }\n\n\
+			 [features]\ne2e-tests = []\nsandbox-tests = [\"ink_e2e?/drink\"]\n",
+		)?;
+		assert_eq!(sandbox_feature(&manifest)?, Some("sandbox-tests".to_string()));
+		Ok(())
+	}
+
+	#[test]
+	fn e2e_tests_works() -> Result<(), Error> {
+		let temp_dir = tempfile::tempdir()?;
+		fs::create_dir_all(temp_dir.path().join("target"))?;
+		fs::write(
+			temp_dir.path().join("lib.rs"),
+			"#[ink_e2e::test]\nasync fn e2e<Client: E2EBackend>() {}\n\n\
+			 #[ink_e2e::test(\n    backend(runtime_only)\n)]\nasync fn sandboxed() {}\n",
+		)?;
+		fs::write(
+			temp_dir.path().join("target/lib.rs"),
+			"#[ink_e2e::test(backend(runtime_only))]\nasync fn built() {}\n",
+		)?;
+		assert_eq!(
+			e2e_tests(temp_dir.path())?,
+			vec![
+				E2eTest { name: "e2e".to_string(), runtime_only: false },
+				E2eTest { name: "sandboxed".to_string(), runtime_only: true }
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn node_tests_works() -> Result<(), Error> {
+		let test = |name: &str, runtime_only| E2eTest { name: name.to_string(), runtime_only };
+		assert!(matches!(node_tests(&[test("e2e", false)], None), Err(Error::TestCommand(..))));
+		let tests = [test("flip_works", false), test("get_works", true), test("flip_works", false)];
+		assert_eq!(node_tests(&tests, None)?, vec!["flip_works"]);
+		assert_eq!(node_tests(&tests, Some("get"))?, vec!["flip_works"]);
+		assert!(matches!(node_tests(&tests, Some("flip")), Err(Error::TestCommand(..))));
+		// A test using a node whose name is contained by that of a test within the sandbox.
+		let tests = [test("flip", false), test("flip_in_sandbox", true)];
+		assert!(matches!(node_tests(&tests, None), Err(Error::TestCommand(..))));
+		Ok(())
+	}
+
+	#[cfg(feature = "unit_contract")]
 	#[test]
 	fn test_contract_test() -> Result<(), Error> {
 		let temp_contract_dir = setup_test_environment()?;
 		// Run unit tests for the smart contract in the temporary contract directory.
-		test_smart_contract(&Some(temp_contract_dir.path().join("test_contract")))?;
+		let report = test_smart_contract(
+			&Some(temp_contract_dir.path().join("test_contract")),
+			&TestOpts::default(),
+			&(),
+		)?;
+		assert!(report.success());
 		Ok(())
 	}
 }
@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::utils::signer::Keypair;
+use contract_extrinsics::ErrorVariant;
+use serde::Serialize;
+use sp_core::bytes::to_hex;
+use std::{
+	fmt::{Display, Formatter},
+	time::Duration,
+};
+use strum_macros::{AsRefStr, EnumString, VariantArray};
+use subxt::{
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	blocks::ExtrinsicEvents,
+	config::DefaultExtrinsicParamsBuilder,
+	error::{RpcError, TransactionError},
+	tx::{TxPayload, TxStatus},
+	OnlineClient, PolkadotConfig as DefaultConfig,
+};
+use tokio::time::{timeout_at, Instant};
+use url::Url;
+
+/// How long to wait for an extrinsic to be finalized once it is included in a block, unless
+/// specified.
+pub const FINALIZATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The status a submitted extrinsic must reach before its submission is complete.
+#[derive(
+	AsRefStr, Clone, Copy, Debug, Default, EnumString, Eq, PartialEq, Serialize, VariantArray,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum WaitFor {
+	/// The extrinsic is included in a best block.
+	#[default]
+	InBlock,
+	/// The block in which the extrinsic is included is finalized. Chains without finality, such
+	/// as a contracts node producing blocks instantly, never reach this status.
+	Finalized,
+}
+
+/// Options controlling the submission of an extrinsic.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TxOpts {
+	/// The status the extrinsic must reach before its submission is complete.
+	pub wait_for: WaitFor,
+	/// The nonce of the extrinsic, such as to submit several extrinsics without waiting for each
+	/// to be included. Defaults to the next nonce of the account, including any extrinsics
+	/// pending within the transaction pool.
+	pub nonce: Option<u64>,
+	/// A tip paid to the block author to prioritise the extrinsic, in the smallest unit of the
+	/// token of the chain.
+	pub tip: u128,
+	/// The number of blocks, from the current best block, for which the extrinsic is valid,
+	/// rounded to a power of two. The extrinsic is immortal if not specified.
+	pub mortality: Option<u64>,
+	/// How long to wait for the extrinsic to be finalized once it is included in a block, when
+	/// waiting for finalization. Defaults to [`FINALIZATION_TIMEOUT`].
+	pub finalization_timeout: Option<Duration>,
+}
+
+/// A change in the status of a submitted extrinsic.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TxEvent {
+	/// The extrinsic was submitted to the node.
+	Submitted {
+		/// The hash of the extrinsic.
+		extrinsic_hash: String,
+	},
+	/// The extrinsic was validated by the transaction pool.
+	Validated,
+	/// The extrinsic was broadcast to other nodes.
+	Broadcast {
+		/// The number of peers it was broadcast to.
+		peers: u32,
+	},
+	/// The extrinsic was included in a best block.
+	InBlock {
+		/// The hash of the block.
+		block_hash: String,
+	},
+	/// The block in which the extrinsic was included is no longer a best block, so the extrinsic
+	/// may be included in another block.
+	Retracted,
+	/// The block in which the extrinsic was included was finalized.
+	Finalized {
+		/// The hash of the block.
+		block_hash: String,
+	},
+}
+
+impl Display for TxEvent {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			TxEvent::Submitted { extrinsic_hash } =>
+				write!(f, "Submitted extrinsic {extrinsic_hash}"),
+			TxEvent::Validated => write!(f, "Validated by the transaction pool"),
+			TxEvent::Broadcast { peers } => write!(f, "Broadcast to {peers} peers"),
+			TxEvent::InBlock { block_hash } => write!(f, "Included in block {block_hash}"),
+			TxEvent::Retracted => write!(f, "No longer included in a best block"),
+			TxEvent::Finalized { block_hash } => write!(f, "Finalized in block {block_hash}"),
+		}
+	}
+}
+
+/// Signs and submits an extrinsic, reporting each change in its status until it reaches the
+/// status specified by the options. Returns the events of the extrinsic, or the error with which
+/// its dispatch failed. Fails if the extrinsic is not finalized within the timeout once included
+/// in a block, when waiting for finalization.
+///
+/// # Arguments
+///
+/// * `client` - the client of the chain.
+/// * `url` - the websocket endpoint of the chain.
+/// * `call` - the extrinsic to be submitted.
+/// * `signer` - the account submitting the extrinsic.
+/// * `opts` - options controlling the submission of the extrinsic.
+/// * `on_event` - called with each change in the status of the extrinsic.
+pub(crate) async fn submit_extrinsic(
+	client: &OnlineClient<DefaultConfig>,
+	url: &Url,
+	call: &impl TxPayload,
+	signer: &Keypair,
+	opts: &TxOpts,
+	on_event: impl Fn(&TxEvent),
+) -> Result<ExtrinsicEvents<DefaultConfig>, ErrorVariant> {
+	// The nonce and mortality are derived from the best block rather than the finalized block, as
+	// development chains may not finalize blocks.
+	let rpc = LegacyRpcMethods::<DefaultConfig>::new(RpcClient::from_url(url).await?);
+	let nonce = match opts.nonce {
+		Some(nonce) => nonce,
+		None => rpc.system_account_next_index(&signer.account_id()).await?,
+	};
+	let mut params =
+		DefaultExtrinsicParamsBuilder::<DefaultConfig>::new().nonce(nonce).tip(opts.tip);
+	if let Some(blocks) = opts.mortality {
+		let header = rpc
+			.chain_get_header(None)
+			.await?
+			.ok_or(ErrorVariant::from("the best block could not be found"))?;
+		params = params.mortal(&header, blocks);
+	}
+
+	let mut progress = client
+		.tx()
+		.create_signed_offline(call, signer, params.build())?
+		.submit_and_watch()
+		.await?;
+	on_event(&TxEvent::Submitted {
+		extrinsic_hash: to_hex(progress.extrinsic_hash().as_ref(), false),
+	});
+	// The deadline for the extrinsic to be finalized, set once it is included in a block.
+	let mut deadline = None;
+	loop {
+		let status = match deadline {
+			Some(deadline) => timeout_at(deadline, progress.next()).await.map_err(|_| {
+				ErrorVariant::from(
+					format!(
+						"the extrinsic was not finalized within {}s of its inclusion in a block",
+						opts.finalization_timeout.unwrap_or(FINALIZATION_TIMEOUT).as_secs()
+					)
+					.as_str(),
+				)
+			})?,
+			None => progress.next().await,
+		};
+		let Some(status) = status else { break };
+		match status? {
+			TxStatus::Validated => on_event(&TxEvent::Validated),
+			TxStatus::Broadcasted { num_peers } =>
+				on_event(&TxEvent::Broadcast { peers: num_peers }),
+			TxStatus::NoLongerInBestBlock => on_event(&TxEvent::Retracted),
+			TxStatus::InBestBlock(tx_in_block) => {
+				on_event(&TxEvent::InBlock {
+					block_hash: to_hex(tx_in_block.block_hash().as_ref(), false),
+				});
+				if opts.wait_for == WaitFor::InBlock {
+					return Ok(tx_in_block.wait_for_success().await?);
+				}
+				let timeout = opts.finalization_timeout.unwrap_or(FINALIZATION_TIMEOUT);
+				deadline.get_or_insert_with(|| Instant::now() + timeout);
+			},
+			TxStatus::InFinalizedBlock(tx_in_block) => {
+				on_event(&TxEvent::Finalized {
+					block_hash: to_hex(tx_in_block.block_hash().as_ref(), false),
+				});
+				return Ok(tx_in_block.wait_for_success().await?);
+			},
+			TxStatus::Error { message } =>
+				return Err(subxt::Error::from(TransactionError::Error(message)).into()),
+			TxStatus::Invalid { message } =>
+				return Err(subxt::Error::from(TransactionError::Invalid(message)).into()),
+			TxStatus::Dropped { message } =>
+				return Err(subxt::Error::from(TransactionError::Dropped(message)).into()),
+		}
+	}
+	Err(subxt::Error::from(RpcError::SubscriptionDropped).into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::json;
+	use std::str::FromStr;
+
+	#[test]
+	fn wait_for_is_parsed() -> Result<()> {
+		assert_eq!(WaitFor::from_str("in-block")?, WaitFor::InBlock);
+		assert_eq!(WaitFor::from_str("finalized")?, WaitFor::Finalized);
+		assert_eq!(WaitFor::Finalized.as_ref(), "finalized");
+		assert!(WaitFor::from_str("included").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn tx_event_is_serialized() -> Result<()> {
+		assert_eq!(
+			serde_json::to_value(TxEvent::Broadcast { peers: 2 })?,
+			json!({"status": "broadcast", "peers": 2})
+		);
+		assert_eq!(
+			serde_json::to_value(TxEvent::InBlock { block_hash: "0x01".to_string() })?,
+			json!({"status": "in_block", "block_hash": "0x01"})
+		);
+		assert_eq!(serde_json::to_value(TxEvent::Retracted)?, json!({"status": "retracted"}));
+		assert_eq!(
+			TxEvent::Finalized { block_hash: "0x01".to_string() }.to_string(),
+			"Finalized in block 0x01"
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	transaction::{submit_extrinsic, TxEvent, TxOpts},
+	utils::{
+		helpers::{parse_account, parse_balance},
+		signer::{create_signer, Keypair, Scheme},
+	},
+};
+use contract_extrinsics::TokenMetadata;
+use sp_core::bytes::to_hex;
+use subxt::{dynamic::Value, tx::DynamicPayload, OnlineClient, PolkadotConfig as DefaultConfig};
+use url::Url;
+
+/// Attributes for a balance transfer.
+pub struct TransferOpts {
+	/// The address of the account receiving the balance.
+	pub dest: String,
+	/// The balance to transfer, e.g. `1000` or `1.5UNIT`.
+	pub value: String,
+	/// Websocket endpoint of a node.
+	pub url: Url,
+	/// Secret key URI for the account transferring the balance.
+	pub suri: String,
+	/// The cryptographic scheme of the account transferring the balance.
+	pub scheme: Scheme,
+	/// The password of the stored account, when `suri` is the name of an account.
+	pub password: Option<String>,
+}
+
+/// The result of a balance transfer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferResult {
+	/// The hash of the extrinsic.
+	pub extrinsic_hash: String,
+	/// The hash of the block in which the transfer was included.
+	pub block_hash: String,
+	/// The balance transferred, in the smallest unit of the token of the chain.
+	pub value: u128,
+}
+
+/// Transfers a balance to an account, keeping the transferring account alive. The transferring
+/// account may use any of the supported key schemes.
+///
+/// # Arguments
+///
+/// * `transfer_opts` - attributes for the transfer.
+/// * `tx_opts` - options controlling the submission of the extrinsic.
+/// * `on_event` - called with each change in the status of the extrinsic.
+pub async fn transfer_balance(
+	transfer_opts: &TransferOpts,
+	tx_opts: &TxOpts,
+	on_event: impl Fn(&TxEvent),
+) -> anyhow::Result<TransferResult> {
+	let signer = create_signer(
+		&transfer_opts.suri,
+		transfer_opts.scheme,
+		transfer_opts.password.as_deref(),
+	)?;
+	transfer(&signer, transfer_opts, tx_opts, on_event).await
+}
+
+// Transfers a balance from the account of the signer.
+async fn transfer(
+	signer: &Keypair,
+	transfer_opts: &TransferOpts,
+	tx_opts: &TxOpts,
+	on_event: impl Fn(&TxEvent),
+) -> anyhow::Result<TransferResult> {
+	let token_metadata = TokenMetadata::query::<DefaultConfig>(&transfer_opts.url).await?;
+	let value = parse_balance(&transfer_opts.value)?.denominate_balance(&token_metadata)?;
+	let tx = transfer_payload(&transfer_opts.dest, value)?;
+	let client = OnlineClient::<DefaultConfig>::from_url(&transfer_opts.url).await?;
+	let events = submit_extrinsic(&client, &transfer_opts.url, &tx, signer, tx_opts, on_event)
+		.await
+		.map_err(|e| anyhow::anyhow!("{e}"))?;
+	Ok(TransferResult {
+		extrinsic_hash: to_hex(events.extrinsic_hash().as_ref(), false),
+		block_hash: to_hex(events.block_hash().as_ref(), false),
+		value,
+	})
+}
+
+// The extrinsic transferring a balance to an account, keeping the transferring account alive.
+fn transfer_payload(dest: &str, value: u128) -> anyhow::Result<DynamicPayload> {
+	let dest = parse_account(dest)?;
+	Ok(subxt::dynamic::tx(
+		"Balances",
+		"transfer_keep_alive",
+		vec![Value::unnamed_variant("Id", [Value::from_bytes(dest.0)]), Value::u128(value)],
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{contracts_node_generator, is_chain_alive, run_contracts_node, ContractsNodeOpts};
+	use anyhow::Result;
+	use subxt::dynamic::At;
+
+	#[test]
+	fn transfer_payload_validates_destination() {
+		assert!(transfer_payload("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", 1).is_ok());
+		assert!(transfer_payload("invalid", 1).is_err());
+	}
+
+	// Queries the free balance of an account.
+	async fn free_balance(client: &OnlineClient<DefaultConfig>, account: &Keypair) -> Result<u128> {
+		let query = subxt::dynamic::storage(
+			"System",
+			"Account",
+			vec![Value::from_bytes(account.account_id().0)],
+		);
+		let account = client.storage().at_latest().await?.fetch_or_default(&query).await?;
+		Ok(account
+			.to_value()?
+			.at("data")
+			.at("free")
+			.and_then(|v| v.as_u128())
+			.unwrap_or_default())
+	}
+
+	#[tokio::test]
+	async fn transfer_balance_signs_with_each_scheme() -> Result<()> {
+		let opts = ContractsNodeOpts { port: 9966, dev: true, temporary: true, log: None };
+		let url = opts.url();
+		assert!(!is_chain_alive(url.clone()).await?);
+		let temp_dir = tempfile::tempdir()?;
+		let binary = contracts_node_generator(temp_dir.path(), None, None).await?;
+		binary.source(false, &(), true).await?;
+		let mut process = run_contracts_node(&binary.path(), &opts).await?;
+		let client = OnlineClient::<DefaultConfig>::from_url(&url).await?;
+
+		let alice = Keypair::from_uri("//Alice", Scheme::Sr25519)?;
+		for scheme in [Scheme::Ed25519, Scheme::Ecdsa] {
+			// Fund an account of the scheme, which then transfers part of the balance back.
+			let account = Keypair::from_uri("//Alice", scheme)?;
+			let opts = |dest: &Keypair, value: &str| TransferOpts {
+				dest: dest.account_id().to_string(),
+				value: value.to_string(),
+				url: url.clone(),
+				suri: "//Alice".to_string(),
+				scheme,
+				password: None,
+			};
+			let funded =
+				transfer(&alice, &opts(&account, "1000000000000"), &TxOpts::default(), |_| {})
+					.await?;
+			assert_eq!(funded.value, 1_000_000_000_000);
+			let returned =
+				transfer_balance(&opts(&alice, "1000"), &TxOpts::default(), |_| {}).await?;
+			assert_eq!(returned.value, 1_000);
+			assert!(free_balance(&client, &account).await? < 1_000_000_000_000 - 1_000);
+		}
+		process.kill()?;
+		Ok(())
+	}
+}
@@ -1,20 +1,34 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::utils::{
-	helpers::{get_manifest_path, parse_balance},
-	signer::create_signer,
+use crate::{
+	transaction::{submit_extrinsic, TxEvent, TxOpts},
+	utils::{
+		helpers::{
+			deposit_charged, estimate_fee, get_manifest_path, parse_balance,
+			parse_storage_deposit_limit, storage_deposit_limit_value, weight_value,
+		},
+		metadata::{validate_function_args, FunctionType},
+		signer::{create_signer, Keypair, Scheme},
+	},
 };
+use contract_build::ManifestPath;
 use contract_extrinsics::{
-	BalanceVariant, ErrorVariant, ExtrinsicOptsBuilder, InstantiateCommandBuilder, InstantiateExec,
-	TokenMetadata,
+	pallet_contracts_primitives::CodeUploadReturnValue, BalanceVariant, Code, ContractArtifacts,
+	ErrorVariant, ExtrinsicOptsBuilder, InstantiateCommandBuilder, InstantiateExec, TokenMetadata,
+	UploadCommandBuilder, UploadExec,
 };
 use ink_env::{DefaultEnvironment, Environment};
+use serde::Serialize;
 use sp_core::Bytes;
 use sp_weights::Weight;
-use std::path::PathBuf;
-use subxt::PolkadotConfig as DefaultConfig;
-use subxt_signer::sr25519::Keypair;
+use std::{io::Write, path::PathBuf};
+use subxt::{
+	dynamic::Value, ext::codec::Decode, tx::DynamicPayload, Config, PolkadotConfig as DefaultConfig,
+};
+use tempfile::NamedTempFile;
+use url::Url;
 
 /// Attributes for the `up` command
+#[derive(Clone)]
 pub struct UpOpts {
 	/// Path to the contract build folder.
 	pub path: Option<PathBuf>,
@@ -31,10 +45,34 @@ pub struct UpOpts {
 	/// A salt used in the address derivation of the new contract. Use to create multiple
 	/// instances of the same contract code from the same account.
 	pub salt: Option<Bytes>,
+	/// Maximum storage deposit which may be charged for the instantiation, e.g. `1000` or
+	/// `1.5 UNIT`. If not specified, the instantiation may use all of the free balance of the
+	/// account.
+	pub storage_deposit_limit: Option<String>,
 	/// Websocket endpoint of a node.
 	pub url: url::Url,
 	/// Secret key URI for the account deploying the contract.
 	pub suri: String,
+	/// The cryptographic scheme of the account deploying the contract.
+	pub scheme: Scheme,
+	/// The password of the stored account, when `suri` is the name of an account.
+	pub password: Option<String>,
+	/// The code hash of contract code already uploaded to the chain. When specified, the contract
+	/// is instantiated from this code hash rather than uploading the contract code.
+	pub code_hash: Option<[u8; 32]>,
+}
+
+/// An instantiated contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ContractInfo {
+	/// The address of the contract.
+	pub address: String,
+	/// The hash of the contract code.
+	pub code_hash: Option<String>,
+	/// The number of the block in which the contract was instantiated.
+	pub block_number: u32,
+	/// The hash of the block in which the contract was instantiated.
+	pub block_hash: String,
 }
 
 /// Prepare `InstantiateExec` data to upload and instantiate a contract.
@@ -47,14 +85,32 @@ pub async fn set_up_deployment(
 	up_opts: UpOpts,
 ) -> anyhow::Result<InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>> {
 	let manifest_path = get_manifest_path(&up_opts.path)?;
+	validate_function_args(
+		&up_opts.path,
+		&up_opts.constructor,
+		&up_opts.args,
+		FunctionType::Constructor,
+	)?;
 
 	let token_metadata = TokenMetadata::query::<DefaultConfig>(&up_opts.url).await?;
 
-	let signer = create_signer(&up_opts.suri)?;
-	let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
-		.manifest_path(Some(manifest_path))
-		.url(up_opts.url.clone())
-		.done();
+	let signer = create_signer(&up_opts.suri, up_opts.scheme, up_opts.password.as_deref())?;
+	// When instantiating from an existing code hash, the contract artifacts are replaced by
+	// metadata which excludes the contract code, so that it is not uploaded again.
+	let code_hash_metadata = match up_opts.code_hash {
+		Some(code_hash) => Some(metadata_with_code_hash(manifest_path.clone(), code_hash)?),
+		None => None,
+	};
+	let extrinsic_opts = match &code_hash_metadata {
+		Some(metadata) => ExtrinsicOptsBuilder::new(signer).file(Some(metadata.path())),
+		None => ExtrinsicOptsBuilder::new(signer).manifest_path(Some(manifest_path)),
+	}
+	.url(up_opts.url.clone())
+	.storage_deposit_limit(parse_storage_deposit_limit(
+		&up_opts.storage_deposit_limit,
+		&token_metadata,
+	)?)
+	.done();
 
 	let value: BalanceVariant<<DefaultEnvironment as Environment>::Balance> =
 		parse_balance(&up_opts.value)?;
@@ -72,6 +128,55 @@ pub async fn set_up_deployment(
 	return Ok(instantiate_exec);
 }
 
+/// Prepare `UploadExec` data to upload a contract without instantiating it.
+///
+/// # Arguments
+///
+/// * `up_opts` - attributes for the `up` command.
+///
+pub async fn set_up_upload(
+	up_opts: UpOpts,
+) -> anyhow::Result<UploadExec<DefaultConfig, DefaultEnvironment, Keypair>> {
+	let manifest_path = get_manifest_path(&up_opts.path)?;
+
+	let token_metadata = TokenMetadata::query::<DefaultConfig>(&up_opts.url).await?;
+
+	let signer = create_signer(&up_opts.suri, up_opts.scheme, up_opts.password.as_deref())?;
+	let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
+		.manifest_path(Some(manifest_path))
+		.url(up_opts.url.clone())
+		.storage_deposit_limit(parse_storage_deposit_limit(
+			&up_opts.storage_deposit_limit,
+			&token_metadata,
+		)?)
+		.done();
+
+	let upload_exec: UploadExec<DefaultConfig, DefaultEnvironment, Keypair> =
+		UploadCommandBuilder::new(extrinsic_opts).done().await?;
+	Ok(upload_exec)
+}
+
+/// Writes a copy of the contract metadata, excluding the contract code and referencing the
+/// specified code hash, to a temporary file.
+///
+/// # Arguments
+///
+/// * `manifest_path` - the manifest path of the contract.
+/// * `code_hash` - the code hash of the contract code already uploaded to the chain.
+///
+fn metadata_with_code_hash(
+	manifest_path: ManifestPath,
+	code_hash: [u8; 32],
+) -> anyhow::Result<NamedTempFile> {
+	let artifacts = ContractArtifacts::from_manifest_or_file(Some(&manifest_path.into()), None)?;
+	let mut metadata = artifacts.metadata()?;
+	metadata.source.hash = code_hash.into();
+	metadata.source.wasm = None;
+	let mut file = tempfile::Builder::new().suffix(".json").tempfile()?;
+	file.write_all(serde_json::to_string(&metadata)?.as_bytes())?;
+	Ok(file)
+}
+
 /// Estimate the gas required for instantiating a contract without modifying the state of the blockchain.
 ///
 /// # Arguments
@@ -81,6 +186,19 @@ pub async fn set_up_deployment(
 pub async fn dry_run_gas_estimate_instantiate(
 	instantiate_exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
 ) -> anyhow::Result<Weight> {
+	Ok(dry_run_estimate_instantiate(instantiate_exec).await?.0)
+}
+
+/// Estimate the gas and the storage deposit charged for instantiating a contract without
+/// modifying the state of the blockchain.
+///
+/// # Arguments
+///
+/// * `instantiate_exec` - the preprocessed data to instantiate a contract.
+///
+pub async fn dry_run_estimate_instantiate(
+	instantiate_exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> anyhow::Result<(Weight, u128)> {
 	let instantiate_result = instantiate_exec.instantiate_dry_run().await?;
 	match instantiate_result.result {
 		Ok(_) => {
@@ -93,7 +211,10 @@ pub async fn dry_run_gas_estimate_instantiate(
 				.args()
 				.proof_size()
 				.unwrap_or_else(|| instantiate_result.gas_required.proof_size());
-			Ok(Weight::from_parts(ref_time, proof_size))
+			Ok((
+				Weight::from_parts(ref_time, proof_size),
+				deposit_charged(&instantiate_result.storage_deposit),
+			))
 		},
 		Err(ref _err) => {
 			Err(anyhow::anyhow!(
@@ -103,17 +224,201 @@ pub async fn dry_run_gas_estimate_instantiate(
 	}
 }
 
+/// Estimate the fee charged for submitting the instantiation of a contract, excluding any tip.
+///
+/// # Arguments
+///
+/// * `instantiate_exec` - the preprocessed data to instantiate a contract.
+/// * `gas_limit` - maximum amount of gas to be used for the instantiation.
+///
+pub async fn estimate_instantiate_fee(
+	instantiate_exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	gas_limit: Weight,
+) -> anyhow::Result<u128> {
+	let tx = instantiate_payload(instantiate_exec, gas_limit)?;
+	estimate_fee(instantiate_exec.client(), &tx, instantiate_exec.opts().signer()).await
+}
+
+/// Estimate the fee charged for submitting the upload of the code of a contract, excluding any tip.
+///
+/// # Arguments
+///
+/// * `upload_exec` - the preprocessed data to upload a contract.
+///
+pub async fn estimate_upload_fee(
+	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> anyhow::Result<u128> {
+	let (tx, _) = upload_payload(upload_exec)?;
+	estimate_fee(upload_exec.client(), &tx, upload_exec.opts().signer()).await
+}
+
+// The extrinsic uploading the code of a contract, as prepared by `set_up_upload`, along with the
+// hash of the code.
+fn upload_payload(
+	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> anyhow::Result<(DynamicPayload, [u8; 32])> {
+	let code = contract_code(upload_exec.opts().contract_artifacts()?)?;
+	let code_hash = contract_build::code_hash(&code);
+	let tx = subxt::dynamic::tx(
+		"Contracts",
+		"upload_code",
+		vec![
+			Value::from_bytes(code),
+			storage_deposit_limit_value(upload_exec.opts().storage_deposit_limit()),
+			Value::unnamed_variant("Enforced", []),
+		],
+	);
+	Ok((tx, code_hash))
+}
+
+// The extrinsic instantiating a contract, as prepared by `set_up_deployment`: either uploading its
+// code or instantiating it from the code hash of code already uploaded.
+fn instantiate_payload(
+	instantiate_exec: &InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	gas_limit: Weight,
+) -> anyhow::Result<DynamicPayload> {
+	let args = instantiate_exec.args();
+	let (call, code) = match args.code() {
+		Code::Upload(_) => (
+			"instantiate_with_code",
+			Value::from_bytes(contract_code(instantiate_exec.opts().contract_artifacts()?)?),
+		),
+		Code::Existing(code_hash) => ("instantiate", Value::from_bytes(code_hash)),
+	};
+	Ok(subxt::dynamic::tx(
+		"Contracts",
+		call,
+		vec![
+			Value::u128(args.value()),
+			weight_value(gas_limit),
+			storage_deposit_limit_value(instantiate_exec.opts().storage_deposit_limit()),
+			code,
+			Value::from_bytes(args.data()),
+			Value::from_bytes(args.salt()),
+		],
+	))
+}
+
+// The Wasm code of a contract, read from its artifacts.
+pub(crate) fn contract_code(artifacts: ContractArtifacts) -> anyhow::Result<Vec<u8>> {
+	artifacts
+		.metadata()?
+		.source
+		.wasm
+		.map(|wasm| wasm.0)
+		.ok_or_else(|| anyhow::anyhow!("the contract code could not be found"))
+}
+
+/// Performs a dry-run for uploading a contract without modifying the state of the blockchain.
+///
+/// # Arguments
+///
+/// * `upload_exec` - the preprocessed data to upload a contract.
+///
+pub async fn dry_run_upload(
+	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+) -> anyhow::Result<
+	CodeUploadReturnValue<
+		<DefaultConfig as Config>::Hash,
+		<DefaultEnvironment as Environment>::Balance,
+	>,
+> {
+	match upload_exec.upload_code_rpc().await? {
+		Ok(result) => Ok(result),
+		Err(ref err) => {
+			let error = ErrorVariant::from_dispatch_error(err, &upload_exec.client().metadata())?;
+			Err(anyhow::anyhow!("Pre-submission dry-run failed: {error}"))
+		},
+	}
+}
+
 /// Instantiate a contract.
 ///
 /// # Arguments
 ///
 /// * `instantiate_exec` - the preprocessed data to instantiate a contract.
 /// * `gas_limit` - maximum amount of gas to be used for this call.
+/// * `tx_opts` - options controlling the submission of the extrinsic.
+/// * `on_event` - called with each change in the status of the extrinsic.
 ///
 pub async fn instantiate_smart_contract(
 	instantiate_exec: InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair>,
 	gas_limit: Weight,
+	tx_opts: &TxOpts,
+	on_event: impl Fn(&TxEvent),
+) -> anyhow::Result<ContractInfo, ErrorVariant> {
+	let code_hash = match instantiate_exec.args().code() {
+		Code::Existing(code_hash) => code_hash.0,
+		Code::Upload(_) => contract_build::code_hash(&contract_code(
+			instantiate_exec.opts().contract_artifacts()?,
+		)?),
+	};
+	let url = Url::parse(&instantiate_exec.opts().url()).map_err(anyhow::Error::from)?;
+	let events = submit_extrinsic(
+		instantiate_exec.client(),
+		&url,
+		&instantiate_payload(&instantiate_exec, gas_limit)?,
+		instantiate_exec.opts().signer(),
+		tx_opts,
+		on_event,
+	)
+	.await?;
+	// The address of the contract is the last instantiated by the extrinsic, as the constructor
+	// may instantiate other contracts.
+	let mut address = None;
+	for event in events.iter() {
+		let event = event?;
+		if event.pallet_name() == "Contracts" && event.variant_name() == "Instantiated" {
+			// The fields of the event are the deployer, followed by the contract.
+			let mut fields = event.field_bytes();
+			let _deployer = <DefaultConfig as Config>::AccountId::decode(&mut fields)
+				.map_err(anyhow::Error::from)?;
+			address = Some(
+				<DefaultConfig as Config>::AccountId::decode(&mut fields)
+					.map_err(anyhow::Error::from)?,
+			);
+		}
+	}
+	let address = address.ok_or(ErrorVariant::from("the contract was not instantiated"))?;
+	let block = instantiate_exec
+		.client()
+		.blocks()
+		.at(events.block_hash())
+		.await
+		.map_err(anyhow::Error::from)?;
+	Ok(ContractInfo {
+		address: address.to_string(),
+		code_hash: Some(sp_core::bytes::to_hex(&code_hash, false)),
+		block_number: block.number(),
+		block_hash: sp_core::bytes::to_hex(events.block_hash().as_ref(), false),
+	})
+}
+
+/// Upload the code of a contract, returning the code hash.
+///
+/// # Arguments
+///
+/// * `upload_exec` - the preprocessed data to upload a contract.
+/// * `tx_opts` - options controlling the submission of the extrinsic.
+/// * `on_event` - called with each change in the status of the extrinsic.
+///
+pub async fn upload_smart_contract(
+	upload_exec: &UploadExec<DefaultConfig, DefaultEnvironment, Keypair>,
+	tx_opts: &TxOpts,
+	on_event: impl Fn(&TxEvent),
 ) -> anyhow::Result<String, ErrorVariant> {
-	let instantiate_result = instantiate_exec.instantiate(Some(gas_limit)).await?;
-	Ok(instantiate_result.contract_address.to_string())
+	// The `CodeStored` event is only raised if the code has not already been uploaded, so the
+	// hash of the code being uploaded is reported instead.
+	let (tx, code_hash) = upload_payload(upload_exec)?;
+	let url = Url::parse(&upload_exec.opts().url()).map_err(anyhow::Error::from)?;
+	submit_extrinsic(
+		upload_exec.client(),
+		&url,
+		&tx,
+		upload_exec.opts().signer(),
+		tx_opts,
+		on_event,
+	)
+	.await?;
+	Ok(sp_core::bytes::to_hex(&code_hash, false))
 }
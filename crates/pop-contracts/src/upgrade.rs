@@ -0,0 +1,419 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{
+	call::resolve_contract,
+	errors::Error,
+	utils::metadata::{format_type, get_metadata},
+};
+use contract_extrinsics::ContractStorageRpc;
+use contract_transcode::ink_metadata::layout::Layout;
+use ink_env::DefaultEnvironment;
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef};
+use serde::Serialize;
+use sp_core::bytes::to_hex;
+use std::{
+	fmt::{self, Display},
+	path::PathBuf,
+};
+use subxt::PolkadotConfig as DefaultConfig;
+use url::Url;
+
+/// The default name of the message used to upgrade a contract, which sets the code hash of the
+/// contract via `set_code_hash`.
+pub const DEFAULT_UPGRADE_MESSAGE: &str = "set_code";
+
+/// A change to the storage layout of a contract which is incompatible with its existing storage.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutChange {
+	/// A field was removed, or its storage key changed.
+	Removed { field: String },
+	/// A field was added to a storage cell which already holds values.
+	Added { field: String },
+	/// A field was moved to another position within its storage cell.
+	Reordered { field: String, from: usize, to: usize },
+	/// The type of a field changed.
+	TypeChanged { field: String, old: String, new: String },
+}
+
+impl Display for LayoutChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LayoutChange::Removed { field } => write!(f, "`{field}` was removed"),
+			LayoutChange::Added { field } => {
+				write!(f, "`{field}` was added to a storage cell holding existing values")
+			},
+			LayoutChange::Reordered { field, from, to } => {
+				write!(f, "`{field}` was moved from position {from} to {to}")
+			},
+			LayoutChange::TypeChanged { field, old, new } => {
+				write!(f, "`{field}` changed type from `{old}` to `{new}`")
+			},
+		}
+	}
+}
+
+// The values stored under a single storage key, in the order in which they are encoded.
+struct Cell {
+	key: u32,
+	path: String,
+	ty: Option<StorageType>,
+	fields: Vec<(String, StorageType)>,
+}
+
+// The type of a value in storage: its name for display, and its full structure for comparison.
+#[derive(PartialEq)]
+struct StorageType {
+	name: String,
+	signature: String,
+}
+
+/// Compares the storage layouts of two versions of a contract, returning the changes which would
+/// prevent the new version from reading the storage of the old one.
+///
+/// Fields within a storage cell are encoded one after the other, so removing, adding, reordering
+/// or changing the type of any of them breaks the decoding of existing values. Adding fields with
+/// their own storage key, such as a `Mapping` or `Lazy`, is compatible.
+///
+/// # Arguments
+///
+/// * `old` - the contract build folder or metadata file of the version being upgraded.
+/// * `new` - the contract build folder or metadata file of the new version.
+pub fn compare_storage_layouts(
+	old: &Option<PathBuf>,
+	new: &Option<PathBuf>,
+) -> Result<Vec<LayoutChange>, Error> {
+	let (old, new) = (get_metadata(old)?, get_metadata(new)?);
+	let old_cells = storage_cells(old.layout(), old.registry());
+	let new_cells = storage_cells(new.layout(), new.registry());
+
+	let mut changes = Vec::new();
+	for old_cell in &old_cells {
+		let Some(new_cell) = new_cells.iter().find(|c| c.key == old_cell.key) else {
+			match old_cell.fields.is_empty() {
+				true => changes.push(LayoutChange::Removed { field: old_cell.path.clone() }),
+				false => changes.extend(
+					old_cell
+						.fields
+						.iter()
+						.map(|(field, _)| LayoutChange::Removed { field: field.clone() }),
+				),
+			}
+			continue;
+		};
+		// A change to the type of a cell, such as the key of a `Mapping`, affects all its values.
+		if let (Some(old_ty), Some(new_ty)) = (&old_cell.ty, &new_cell.ty) {
+			if old_ty != new_ty {
+				changes.push(LayoutChange::TypeChanged {
+					field: old_cell.path.clone(),
+					old: old_ty.name.clone(),
+					new: new_ty.name.clone(),
+				});
+				continue;
+			}
+		}
+		for (from, (field, old_ty)) in old_cell.fields.iter().enumerate() {
+			match new_cell.fields.iter().position(|(f, _)| f == field) {
+				None => changes.push(LayoutChange::Removed { field: field.clone() }),
+				Some(to) if to != from =>
+					changes.push(LayoutChange::Reordered { field: field.clone(), from, to }),
+				Some(to) => {
+					let new_ty = &new_cell.fields[to].1;
+					if old_ty != new_ty {
+						changes.push(LayoutChange::TypeChanged {
+							field: field.clone(),
+							old: old_ty.name.clone(),
+							new: new_ty.name.clone(),
+						});
+					}
+				},
+			}
+		}
+		for (field, _) in &new_cell.fields {
+			if !old_cell.fields.iter().any(|(f, _)| f == field) {
+				changes.push(LayoutChange::Added { field: field.clone() });
+			}
+		}
+	}
+	Ok(changes)
+}
+
+/// The hash of the code currently used by a deployed contract.
+///
+/// # Arguments
+///
+/// * `path` - path to the contract build folder, used to resolve deployments of the project.
+/// * `contract` - the address of the contract, or the alias or name of a deployment.
+/// * `url` - websocket endpoint of a node.
+pub async fn get_code_hash(
+	path: &Option<PathBuf>,
+	contract: &str,
+	url: &Url,
+) -> anyhow::Result<String> {
+	let contract = resolve_contract(path, contract, url)?;
+	let rpc = ContractStorageRpc::<DefaultConfig>::new(url).await?;
+	let contract_info = rpc.fetch_contract_info::<DefaultEnvironment>(&contract).await?;
+	Ok(to_hex(contract_info.code_hash().as_ref(), false))
+}
+
+// Flattens the storage layout of a contract into the cells stored under each storage key.
+fn storage_cells(layout: &Layout<PortableForm>, registry: &PortableRegistry) -> Vec<Cell> {
+	let mut cells = Vec::new();
+	collect_cells(layout, String::new(), None, &mut cells, registry);
+	cells
+}
+
+fn collect_cells(
+	layout: &Layout<PortableForm>,
+	path: String,
+	cell: Option<usize>,
+	cells: &mut Vec<Cell>,
+	registry: &PortableRegistry,
+) {
+	match layout {
+		Layout::Root(root) => {
+			// The type of the root of the contract is the contract itself, so is not compared.
+			let ty = (!path.is_empty()).then(|| storage_type(root.ty().id, registry));
+			cells.push(Cell {
+				key: *root.root_key().key(),
+				path: path.clone(),
+				ty,
+				fields: vec![],
+			});
+			collect_cells(root.layout(), path, Some(cells.len() - 1), cells, registry);
+		},
+		Layout::Leaf(leaf) =>
+			if let Some(cell) = cell {
+				cells[cell].fields.push((path, storage_type(leaf.ty().id, registry)));
+			},
+		Layout::Struct(layout) =>
+			for field in layout.fields() {
+				collect_cells(field.layout(), join(&path, field.name()), cell, cells, registry);
+			},
+		Layout::Enum(layout) =>
+			for variant in layout.variants().values() {
+				let variant_path = format!("{path}::{}", variant.name());
+				for field in variant.fields() {
+					collect_cells(
+						field.layout(),
+						join(&variant_path, field.name()),
+						cell,
+						cells,
+						registry,
+					);
+				}
+			},
+		Layout::Hash(layout) => collect_cells(layout.layout(), path, cell, cells, registry),
+		Layout::Array(layout) =>
+			collect_cells(layout.layout(), format!("{path}[]"), cell, cells, registry),
+	}
+}
+
+fn join(path: &str, field: &str) -> String {
+	match path.is_empty() {
+		true => field.to_string(),
+		false => format!("{path}.{field}"),
+	}
+}
+
+fn storage_type(id: u32, registry: &PortableRegistry) -> StorageType {
+	StorageType {
+		name: registry.resolve(id).map(|ty| format_type(ty, registry)).unwrap_or_default(),
+		signature: type_signature(id, registry, 0),
+	}
+}
+
+// Describes the full structure of a type, so that changes to the fields of a struct or the
+// variants of an enum are detected even when the name of the type is unchanged.
+fn type_signature(id: u32, registry: &PortableRegistry, depth: usize) -> String {
+	const MAX_DEPTH: usize = 8;
+	let Some(ty) = registry.resolve(id) else { return String::new() };
+	if depth > MAX_DEPTH {
+		return format_type(ty, registry);
+	}
+	let signature = |id: u32| type_signature(id, registry, depth + 1);
+	// Type parameters are included, as types such as `Mapping` only use them as markers.
+	let params: Vec<_> =
+		ty.type_params.iter().filter_map(|p| p.ty.map(|ty| signature(ty.id))).collect();
+	let name = match (ty.path.segments.last(), params.is_empty()) {
+		(Some(name), true) => name.to_string(),
+		(Some(name), false) => format!("{name}<{}>", params.join(", ")),
+		(None, _) => String::new(),
+	};
+	match &ty.type_def {
+		TypeDef::Composite(composite) => {
+			let fields: Vec<_> = composite
+				.fields
+				.iter()
+				.map(|field| match &field.name {
+					Some(name) => format!("{name}: {}", signature(field.ty.id)),
+					None => signature(field.ty.id),
+				})
+				.collect();
+			format!("{name}({})", fields.join(", "))
+		},
+		TypeDef::Variant(variant) => {
+			let variants: Vec<_> = variant
+				.variants
+				.iter()
+				.map(|v| {
+					let fields: Vec<_> = v.fields.iter().map(|f| signature(f.ty.id)).collect();
+					format!("{}:{}({})", v.index, v.name, fields.join(", "))
+				})
+				.collect();
+			format!("{name}{{{}}}", variants.join(", "))
+		},
+		TypeDef::Sequence(sequence) => format!("Vec<{}>", signature(sequence.type_param.id)),
+		TypeDef::Array(array) => format!("[{}; {}]", signature(array.type_param.id), array.len),
+		TypeDef::Tuple(tuple) => {
+			let fields: Vec<_> = tuple.fields.iter().map(|field| signature(field.id)).collect();
+			format!("({})", fields.join(", "))
+		},
+		TypeDef::Compact(compact) => format!("Compact<{}>", signature(compact.type_param.id)),
+		_ => format_type(ty, registry),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use serde_json::Value;
+	use std::{fs, path::Path};
+
+	fn testing_metadata() -> Result<Value> {
+		let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/files/testing.json");
+		Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+	}
+
+	// Writes a modified version of the testing metadata and compares the layouts.
+	fn compare(dir: &Path, modify: impl FnOnce(&mut Value)) -> Result<Vec<LayoutChange>> {
+		let old = dir.join("old.json");
+		let new = dir.join("new.json");
+		let mut metadata = testing_metadata()?;
+		fs::write(&old, metadata.to_string())?;
+		modify(&mut metadata);
+		fs::write(&new, metadata.to_string())?;
+		Ok(compare_storage_layouts(&Some(old), &Some(new))?)
+	}
+
+	fn fields(metadata: &mut Value) -> &mut Vec<Value> {
+		metadata["storage"]["root"]["layout"]["struct"]["fields"]
+			.as_array_mut()
+			.unwrap()
+	}
+
+	#[test]
+	fn compare_identical_layouts_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(compare(temp_dir.path(), |_| {})?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn compare_detects_reordered_fields() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let changes = compare(temp_dir.path(), |metadata| fields(metadata).swap(0, 1))?;
+		assert_eq!(
+			changes,
+			[
+				LayoutChange::Reordered { field: "value".into(), from: 0, to: 1 },
+				LayoutChange::Reordered { field: "number".into(), from: 1, to: 0 },
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn compare_detects_changed_types() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let changes = compare(temp_dir.path(), |metadata| {
+			// `number: u32` becomes `number: bool`.
+			fields(metadata)[1]["layout"]["leaf"]["ty"] = 0.into();
+		})?;
+		assert_eq!(
+			changes,
+			[LayoutChange::TypeChanged {
+				field: "number".into(),
+				old: "u32".into(),
+				new: "bool".into()
+			}]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn compare_detects_removed_and_added_fields() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let changes = compare(temp_dir.path(), |metadata| {
+			let owner = fields(metadata).remove(2);
+			// The key of `flips` changes, e.g. by renaming the field.
+			fields(metadata)[2]["layout"]["root"]["root_key"] = "0x01020304".into();
+			fields(metadata).insert(0, owner);
+			fields(metadata)[0]["name"] = "admin".into();
+		})?;
+		assert_eq!(
+			changes,
+			[
+				LayoutChange::Reordered { field: "value".into(), from: 0, to: 1 },
+				LayoutChange::Reordered { field: "number".into(), from: 1, to: 2 },
+				LayoutChange::Removed { field: "owner".into() },
+				LayoutChange::Added { field: "admin".into() },
+				LayoutChange::Removed { field: "flips".into() },
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn compare_detects_changed_mapping_keys() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let changes = compare(temp_dir.path(), |metadata| {
+			// `flips: Mapping<AccountId, u32>` becomes `flips: Mapping<u32, u32>`.
+			let mapping = metadata["types"]
+				.as_array_mut()
+				.unwrap()
+				.iter_mut()
+				.find(|ty| ty["id"] == 5)
+				.unwrap();
+			mapping["type"]["params"][0]["type"] = 1.into();
+		})?;
+		let [LayoutChange::TypeChanged { field, old, new }] = changes.as_slice() else {
+			panic!("unexpected changes: {changes:?}")
+		};
+		assert_eq!(field, "flips");
+		assert!(old.starts_with("Mapping<AccountId, u32"));
+		assert!(new.starts_with("Mapping<u32, u32"));
+		Ok(())
+	}
+
+	#[test]
+	fn compare_allows_new_storage_cells() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let changes = compare(temp_dir.path(), |metadata| {
+			let mut balances = fields(metadata)[3].clone();
+			balances["name"] = "balances".into();
+			balances["layout"]["root"]["root_key"] = "0x01020304".into();
+			balances["layout"]["root"]["layout"]["leaf"]["key"] = "0x01020304".into();
+			fields(metadata).push(balances);
+		})?;
+		assert!(changes.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn layout_change_display_works() {
+		assert_eq!(
+			LayoutChange::TypeChanged {
+				field: "number".into(),
+				old: "u32".into(),
+				new: "bool".into()
+			}
+			.to_string(),
+			"`number` changed type from `u32` to `bool`"
+		);
+		assert_eq!(
+			LayoutChange::Reordered { field: "value".into(), from: 0, to: 1 }.to_string(),
+			"`value` was moved from position 0 to 1"
+		);
+	}
+}
@@ -1,20 +1,215 @@
-use crate::{errors::Error, utils::git::GitHub};
+// SPDX-License-Identifier: GPL-3.0
+use crate::errors::Error;
 use contract_extrinsics::{RawParams, RpcRequest};
-use flate2::read::GzDecoder;
+use pop_common::{
+	sourcing::{
+		self,
+		traits::{Source as _, *},
+		GitHub::ReleaseArchive,
+		Source,
+	},
+	Binary,
+};
+use serde::{Deserialize, Serialize};
 use std::{
-	env::consts::OS,
-	io::{Seek, SeekFrom, Write},
-	path::PathBuf,
-	process::{Child, Command},
-	time::Duration,
+	collections::BTreeMap,
+	env::consts::{ARCH, OS},
+	fs::{self, File},
+	path::{Path, PathBuf},
+	process::{Child, Command, Stdio},
+	time::{Duration, Instant},
 };
-use tar::Archive;
-use tempfile::tempfile;
+use strum_macros::EnumProperty;
 use tokio::time::sleep;
+use toml_edit::{value, DocumentMut, Item, Table};
 
-const SUBSTRATE_CONTRACT_NODE: &str = "https://github.com/paritytech/substrate-contracts-node";
 const BIN_NAME: &str = "substrate-contracts-node";
-const STABLE_VERSION: &str = "v0.41.0";
+// The maximum time to wait for the node to be ready.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+// The release archives of the node by platform, along with the path of the binary within each.
+const ARCHIVES: [(&str, &str); 2] = [
+	(
+		"substrate-contracts-node-linux.tar.gz",
+		"artifacts/substrate-contracts-node-linux/substrate-contracts-node",
+	),
+	(
+		"substrate-contracts-node-mac-universal.tar.gz",
+		"artifacts/substrate-contracts-node-mac/substrate-contracts-node",
+	),
+];
+// The table of the configuration file of a project in which the version of the node is pinned.
+const CONFIG_TABLE: &str = "contracts-node";
+
+/// The default port on which the `substrate-contracts-node` listens for RPC connections.
+pub const DEFAULT_PORT: u16 = 9944;
+/// The name of the file, within the cache, in which the instances of the
+/// `substrate-contracts-node` started in the background are recorded.
+pub const CONTRACTS_NODES_FILE: &str = "contracts-nodes.json";
+
+/// The name of the configuration file of a project, in which the version of the
+/// `substrate-contracts-node` used by the project can be pinned.
+pub const CONFIG_FILE: &str = pop_common::CONFIG_FILE;
+
+/// The `substrate-contracts-node` releases, sourced from GitHub.
+#[derive(Debug, EnumProperty, PartialEq)]
+enum ContractsNodeRelease {
+	/// A node for smart contract development.
+	#[strum(props(
+		Repository = "https://github.com/paritytech/substrate-contracts-node",
+		Binary = "substrate-contracts-node",
+		Fallback = "v0.41.0"
+	))]
+	ContractsNode,
+}
+
+impl TryInto for ContractsNodeRelease {
+	/// Attempt the conversion.
+	///
+	/// # Arguments
+	/// * `tag` - If applicable, a tag used to determine a specific release.
+	/// * `latest` - If applicable, some specifier used to determine the latest source.
+	fn try_into(
+		&self,
+		tag: Option<String>,
+		latest: Option<String>,
+	) -> Result<Source, pop_common::Error> {
+		Ok(match self {
+			ContractsNodeRelease::ContractsNode => {
+				// Source from GitHub release asset
+				let repo = pop_common::GitHub::parse(self.repository())?;
+				let (archive, binary) = archive_by_target()?;
+				Source::GitHub(ReleaseArchive {
+					owner: repo.org,
+					repository: repo.name,
+					tag,
+					tag_format: self.tag_format().map(|t| t.into()),
+					archive: archive.to_string(),
+					contents: vec![binary],
+					latest,
+					checksum: None,
+				})
+			},
+		})
+	}
+}
+
+impl sourcing::traits::Source for ContractsNodeRelease {}
+
+/// The version of the `substrate-contracts-node` pinned by a project, within its configuration
+/// file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ContractsNodePin {
+	/// The release tag of the node, e.g. `v0.41.0`.
+	pub version: String,
+	/// The expected SHA-256 checksums of the release archives of the node, by archive name.
+	#[serde(default)]
+	pub checksums: BTreeMap<String, String>,
+}
+
+impl ContractsNodePin {
+	/// Pins a version of the node, along with the checksums published for its release archives
+	/// where available.
+	///
+	/// # Arguments
+	///
+	/// * `version` - The release tag of the node.
+	///
+	pub async fn new(version: &str) -> Result<Self, Error> {
+		let repo = pop_common::GitHub::parse(ContractsNodeRelease::ContractsNode.repository())?;
+		let mut checksums = BTreeMap::new();
+		for (archive, _) in ARCHIVES {
+			if let Ok(Some(digest)) = repo.asset_digest(Some(version), archive).await {
+				checksums.insert(archive.to_string(), digest);
+			}
+		}
+		Ok(Self { version: version.to_string(), checksums })
+	}
+
+	/// Loads the version pinned by a project, if any.
+	///
+	/// # Arguments
+	///
+	/// * `project` - The path to the project.
+	///
+	pub fn load(project: &Path) -> Result<Option<Self>, Error> {
+		#[derive(Deserialize)]
+		struct Config {
+			#[serde(rename = "contracts-node")]
+			contracts_node: Option<ContractsNodePin>,
+		}
+
+		let path = project.join(CONFIG_FILE);
+		if !path.exists() {
+			return Ok(None);
+		}
+		let config: Config = toml_edit::de::from_str(&fs::read_to_string(&path)?)
+			.map_err(|e| Error::Config(format!("{}: {e}", path.display())))?;
+		Ok(config.contracts_node)
+	}
+
+	/// Records the pinned version within the configuration file of a project, retaining any other
+	/// configuration.
+	///
+	/// # Arguments
+	///
+	/// * `project` - The path to the project.
+	///
+	pub fn save(&self, project: &Path) -> Result<(), Error> {
+		let path = project.join(CONFIG_FILE);
+		let mut config = match path.exists() {
+			true => fs::read_to_string(&path)?
+				.parse::<DocumentMut>()
+				.map_err(|e| Error::Config(format!("{}: {e}", path.display())))?,
+			false => DocumentMut::new(),
+		};
+		let mut table = Table::new();
+		table.insert("version", value(&self.version));
+		if !self.checksums.is_empty() {
+			let mut checksums = Table::new();
+			for (archive, checksum) in &self.checksums {
+				checksums.insert(archive, value(checksum));
+			}
+			table.insert("checksums", Item::Table(checksums));
+		}
+		config.insert(CONFIG_TABLE, Item::Table(table));
+		fs::write(path, config.to_string())?;
+		Ok(())
+	}
+
+	/// The checksum expected for the release archive for the current platform, if known.
+	pub fn checksum(&self) -> Option<&str> {
+		let (archive, _) = archive_by_target().ok()?;
+		self.checksums.get(archive).map(|c| c.as_str())
+	}
+}
+
+/// Initialises the `substrate-contracts-node` binary to be used, which is sourced from its GitHub
+/// releases and cached by version. The latest version cached locally is used when no version is
+/// specified, with the latest version available noted so that a stale binary can be detected.
+///
+/// # Arguments
+///
+/// * `cache` - The path where the binary is cached.
+/// * `version` - The release tag of the version to be used, if pinned.
+/// * `checksum` - The expected SHA-256 checksum of the release archive, if known.
+///
+pub async fn contracts_node_generator(
+	cache: &Path,
+	version: Option<&str>,
+	checksum: Option<&str>,
+) -> Result<Binary, Error> {
+	let release = &ContractsNodeRelease::ContractsNode;
+	let name = release.binary();
+	let releases = release.releases().await?;
+	let tag = Binary::resolve_version(name, version, &releases, cache);
+	// Only set latest when caller has not explicitly specified a version to use
+	let latest = version.is_none().then(|| releases.first().map(|v| v.to_string())).flatten();
+	let mut source = TryInto::try_into(release, tag, latest)?;
+	if let Source::GitHub(ReleaseArchive { checksum: expected, .. }) = &mut source {
+		*expected = checksum.map(|c| c.to_string());
+	}
+	Ok(Binary::Source { name: name.to_string(), source, cache: cache.to_path_buf() })
+}
 
 /// Checks if the specified node is alive and responsive.
 ///
@@ -37,67 +232,236 @@ pub async fn is_chain_alive(url: url::Url) -> Result<bool, Error> {
 	}
 }
 
-/// Runs the latest version of the `substracte-contracts-node` in the background.
+/// Options for running the `substrate-contracts-node`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractsNodeOpts {
+	/// The port on which the node listens for RPC connections.
+	pub port: u16,
+	/// Whether to run a development chain, with pre-funded development accounts.
+	pub dev: bool,
+	/// Whether to use temporary state, which is discarded when the node stops.
+	pub temporary: bool,
+	/// The file to which the output of the node is written, which is discarded if not specified.
+	pub log: Option<PathBuf>,
+}
+
+impl Default for ContractsNodeOpts {
+	fn default() -> Self {
+		Self { port: DEFAULT_PORT, dev: false, temporary: false, log: None }
+	}
+}
+
+impl ContractsNodeOpts {
+	/// The websocket endpoint of the node.
+	pub fn url(&self) -> url::Url {
+		node_url(self.port)
+	}
+
+	// The arguments used to run the node.
+	fn args(&self) -> Vec<String> {
+		let mut args = vec!["--rpc-port".to_string(), self.port.to_string()];
+		if self.dev {
+			args.push("--dev".to_string());
+		}
+		if self.temporary {
+			args.push("--tmp".to_string());
+		}
+		args
+	}
+}
+
+/// An instance of the `substrate-contracts-node` started in the background, as recorded within
+/// the cache.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ContractsNode {
+	/// The process ID of the node.
+	pub pid: u32,
+	/// The port on which the node listens for RPC connections.
+	pub port: u16,
+	/// The file to which the output of the node is written.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub log: Option<PathBuf>,
+}
+
+impl ContractsNode {
+	/// The websocket endpoint of the node.
+	pub fn url(&self) -> url::Url {
+		node_url(self.port)
+	}
+
+	/// Whether the process of the node is still running, rather than having exited with its
+	/// process ID since reused by another process.
+	pub fn is_running(&self) -> bool {
+		Command::new("ps")
+			.args(["-p", &self.pid.to_string(), "-o", "args="])
+			.stderr(Stdio::null())
+			.output()
+			.is_ok_and(|output| {
+				output.status.success() &&
+					String::from_utf8_lossy(&output.stdout).contains(BIN_NAME)
+			})
+	}
+
+	/// Stops the node, unless it is no longer running.
+	pub fn stop(&self) -> Result<(), Error> {
+		// Any other process now using the process ID of the node must not be signalled.
+		if !self.is_running() {
+			return Ok(());
+		}
+		let status =
+			Command::new("kill").arg(self.pid.to_string()).stderr(Stdio::null()).status()?;
+		if !status.success() && self.is_running() {
+			return Err(Error::DownContractsNode(format!(
+				"{BIN_NAME} with process ID {}",
+				self.pid
+			)));
+		}
+		Ok(())
+	}
+}
+
+/// The file, within the cache, to which the output of an instance of the
+/// `substrate-contracts-node` listening on a port is written.
 ///
 /// # Arguments
 ///
-/// * `cache` - The path where the binary will be stored.
-///
-pub async fn run_contracts_node(cache: PathBuf) -> Result<Child, Error> {
-	let cached_file = cache.join(release_folder_by_target()?).join(BIN_NAME);
-	if !cached_file.exists() {
-		let archive = archive_name_by_target()?;
-
-		let latest_version = latest_contract_node_release().await?;
-		let releases_url =
-			format!("{SUBSTRATE_CONTRACT_NODE}/releases/download/{latest_version}/{archive}");
-		// Download archive
-		let response = reqwest::get(releases_url.as_str()).await?.error_for_status()?;
-		let mut file = tempfile()?;
-		file.write_all(&response.bytes().await?)?;
-		file.seek(SeekFrom::Start(0))?;
-		// Extract contents
-		let tar = GzDecoder::new(file);
-		let mut archive = Archive::new(tar);
-		archive.unpack(cache.clone())?;
-	}
-	let process = Command::new(cached_file.display().to_string().as_str()).spawn()?;
-
-	// Wait 5 secs until the node is ready
-	sleep(Duration::from_millis(5000)).await;
-	Ok(process)
+/// * `cache` - The path where the file is written.
+/// * `port` - The port on which the node listens for RPC connections.
+///
+pub fn contracts_node_log(cache: &Path, port: u16) -> PathBuf {
+	cache.join(format!("contracts-node-{port}.log"))
 }
 
-async fn latest_contract_node_release() -> Result<String, Error> {
-	let repo = GitHub::parse(SUBSTRATE_CONTRACT_NODE)?;
-	match repo.get_latest_releases().await {
-		Ok(releases) => {
-			// Fetching latest releases
-			for release in releases {
-				if !release.prerelease {
-					return Ok(release.tag_name);
-				}
-			}
-			// It should never reach this point, but in case we download a default version of polkadot
-			Ok(STABLE_VERSION.to_string())
-		},
-		// If an error with GitHub API return the STABLE_VERSION
-		Err(_) => Ok(STABLE_VERSION.to_string()),
+/// Returns the instances of the `substrate-contracts-node` started in the background which are
+/// still running, forgetting any which have since stopped.
+///
+/// # Arguments
+///
+/// * `cache` - The path where the instances are recorded.
+///
+pub fn contracts_nodes(cache: &Path) -> Result<Vec<ContractsNode>, Error> {
+	let path = cache.join(CONTRACTS_NODES_FILE);
+	let nodes: Vec<ContractsNode> = match path.exists() {
+		true => serde_json::from_str(&fs::read_to_string(&path)?)
+			.map_err(|e| Error::UpContractsNode(format!("{}: {e}", path.display())))?,
+		false => Vec::new(),
+	};
+	let running: Vec<_> = nodes.iter().filter(|node| node.is_running()).cloned().collect();
+	if running.len() != nodes.len() {
+		save_contracts_nodes(cache, &running)?;
+	}
+	Ok(running)
+}
+
+fn save_contracts_nodes(cache: &Path, nodes: &[ContractsNode]) -> Result<(), Error> {
+	let contents = serde_json::to_string_pretty(nodes)
+		.map_err(|e| Error::UpContractsNode(format!("{CONTRACTS_NODES_FILE}: {e}")))?;
+	fs::write(cache.join(CONTRACTS_NODES_FILE), contents)?;
+	Ok(())
+}
+
+/// Starts the `substrate-contracts-node` in the background, waiting until it is ready and
+/// recording it within the cache so that it can be stopped later. Any instance previously started
+/// on the same port is stopped first.
+///
+/// # Arguments
+///
+/// * `cache` - The path where the instance is recorded.
+/// * `binary` - The path to the `substrate-contracts-node` binary.
+/// * `opts` - Options for running the node.
+///
+pub async fn start_contracts_node(
+	cache: &Path,
+	binary: &Path,
+	opts: &ContractsNodeOpts,
+) -> Result<ContractsNode, Error> {
+	stop_contracts_nodes(cache, Some(opts.port))?;
+	let process = run_contracts_node(binary, opts).await?;
+	let node = ContractsNode { pid: process.id(), port: opts.port, log: opts.log.clone() };
+	let mut nodes = contracts_nodes(cache)?;
+	nodes.push(node.clone());
+	save_contracts_nodes(cache, &nodes)?;
+	Ok(node)
+}
+
+/// Stops the instances of the `substrate-contracts-node` started in the background, returning
+/// those stopped.
+///
+/// # Arguments
+///
+/// * `cache` - The path where the instances are recorded.
+/// * `port` - Only stop the instance running on this port, if specified.
+///
+pub fn stop_contracts_nodes(cache: &Path, port: Option<u16>) -> Result<Vec<ContractsNode>, Error> {
+	let (stopped, running): (Vec<_>, Vec<_>) = contracts_nodes(cache)?
+		.into_iter()
+		.partition(|node| port.is_none_or(|port| node.port == port));
+	save_contracts_nodes(cache, &running)?;
+	for node in &stopped {
+		node.stop()?;
 	}
+	Ok(stopped)
 }
 
-fn archive_name_by_target() -> Result<String, Error> {
-	match OS {
-		"macos" => Ok(format!("{}-mac-universal.tar.gz", BIN_NAME)),
-		"linux" => Ok(format!("{}-linux.tar.gz", BIN_NAME)),
-		_ => Err(Error::UnsupportedPlatform { os: OS }),
+/// Runs the `substrate-contracts-node` in the background, waiting until it is ready to accept
+/// connections.
+///
+/// # Arguments
+///
+/// * `binary` - The path to the `substrate-contracts-node` binary, as sourced using
+///   [`contracts_node_generator`].
+/// * `opts` - Options for running the node.
+///
+pub async fn run_contracts_node(binary: &Path, opts: &ContractsNodeOpts) -> Result<Child, Error> {
+	if !binary.exists() {
+		return Err(Error::UpContractsNode(format!(
+			"{BIN_NAME}: the binary was not found at {}",
+			binary.display()
+		)));
+	}
+	let (stdout, stderr) = match &opts.log {
+		Some(log) => {
+			let file = File::create(log)?;
+			(Stdio::from(file.try_clone()?), Stdio::from(file))
+		},
+		None => (Stdio::null(), Stdio::null()),
+	};
+	let mut process =
+		Command::new(binary).args(opts.args()).stdout(stdout).stderr(stderr).spawn()?;
+
+	// Wait until the node is ready, or has exited.
+	let url = opts.url();
+	let start = Instant::now();
+	while !is_chain_alive(url.clone()).await? {
+		if let Some(status) = process.try_wait()? {
+			return Err(Error::UpContractsNode(format!(
+				"{BIN_NAME}: the node exited with {status}"
+			)));
+		}
+		if start.elapsed() > READY_TIMEOUT {
+			process.kill()?;
+			return Err(Error::UpContractsNode(format!(
+				"{BIN_NAME}: the node was not ready within {} seconds",
+				READY_TIMEOUT.as_secs()
+			)));
+		}
+		sleep(Duration::from_millis(500)).await;
 	}
+	Ok(process)
+}
+
+fn node_url(port: u16) -> url::Url {
+	url::Url::parse(&format!("ws://localhost:{port}")).expect("valid url; qed")
 }
-fn release_folder_by_target() -> Result<&'static str, Error> {
-	match OS {
-		"macos" => Ok("artifacts/substrate-contracts-node-mac"),
-		"linux" => Ok("artifacts/substrate-contracts-node-linux"),
-		_ => Err(Error::UnsupportedPlatform { os: OS }),
+
+// The name of the release archive for the current platform, along with the path of the binary
+// within it.
+fn archive_by_target() -> Result<(&'static str, &'static str), pop_common::Error> {
+	match (OS, ARCH) {
+		("linux", "x86_64") => Ok(ARCHIVES[0]),
+		// A universal binary is released for macOS.
+		("macos", _) => Ok(ARCHIVES[1]),
+		_ => Err(pop_common::Error::UnsupportedPlatform { arch: ARCH, os: OS }),
 	}
 }
 
@@ -106,38 +470,105 @@ mod tests {
 	use super::*;
 	use anyhow::{Error, Result};
 
-	#[tokio::test]
-	async fn test_latest_polkadot_release() -> Result<()> {
-		let version = latest_contract_node_release().await?;
-		// Result will change all the time to the current version, check at least starts with v
-		assert!(version.starts_with("v"));
-		Ok(())
-	}
-	#[tokio::test]
-	async fn release_folder_by_target_works() -> Result<()> {
-		let path = release_folder_by_target();
+	#[test]
+	fn archive_by_target_works() {
+		let archive = archive_by_target();
 		if cfg!(target_os = "macos") {
-			assert_eq!(path?, "artifacts/substrate-contracts-node-mac");
-		} else if cfg!(target_os = "linux") {
-			assert_eq!(path?, "artifacts/substrate-contracts-node-linux");
+			assert_eq!(
+				archive.unwrap(),
+				(
+					"substrate-contracts-node-mac-universal.tar.gz",
+					"artifacts/substrate-contracts-node-mac/substrate-contracts-node"
+				)
+			);
+		} else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+			assert_eq!(
+				archive.unwrap(),
+				(
+					"substrate-contracts-node-linux.tar.gz",
+					"artifacts/substrate-contracts-node-linux/substrate-contracts-node"
+				)
+			);
 		} else {
-			assert!(path.is_err())
+			assert!(matches!(archive, Err(pop_common::Error::UnsupportedPlatform { .. })));
 		}
+	}
+
+	#[tokio::test]
+	async fn contracts_node_generator_works() -> Result<(), Error> {
+		let cache = tempfile::tempdir()?;
+		let version = "v0.40.0";
+		let binary = contracts_node_generator(cache.path(), Some(version), Some("0x1234")).await?;
+		assert!(matches!(&binary, Binary::Source { name, source, cache: c }
+			if name == BIN_NAME && c == cache.path() && matches!(source, Source::GitHub(ReleaseArchive {
+				owner, repository, tag, latest: None, checksum, ..
+			}) if owner == "paritytech"
+				&& repository == "substrate-contracts-node"
+				&& tag.as_deref() == Some(version)
+				&& checksum.as_deref() == Some("0x1234"))
+		));
+		assert_eq!(binary.path(), cache.path().join(format!("{BIN_NAME}-{version}")));
+		assert!(!binary.exists());
+		assert!(!binary.stale());
 		Ok(())
 	}
+
 	#[tokio::test]
-	async fn folder_path_by_target() -> Result<()> {
-		let archive = archive_name_by_target();
-		if cfg!(target_os = "macos") {
-			assert_eq!(archive?, "substrate-contracts-node-mac-universal.tar.gz");
-		} else if cfg!(target_os = "linux") {
-			assert_eq!(archive?, "substrate-contracts-node-linux.tar.gz");
-		} else {
-			assert!(archive.is_err())
-		}
+	async fn contracts_node_generator_uses_cached_version() -> Result<(), Error> {
+		let cache = tempfile::tempdir()?;
+		let releases = ContractsNodeRelease::ContractsNode.releases().await?;
+		let cached = releases.last().expect("at least the fallback version");
+		File::create(cache.path().join(format!("{BIN_NAME}-{cached}")))?;
+		let binary = contracts_node_generator(cache.path(), None, None).await?;
+		assert_eq!(binary.version(), Some(cached.as_str()));
+		assert!(binary.exists());
+		assert_eq!(binary.latest(), releases.first().map(|v| v.as_str()));
+		Ok(())
+	}
+
+	#[test]
+	fn contracts_node_pin_is_saved_and_loaded() -> Result<(), Error> {
+		let project = tempfile::tempdir()?;
+		assert_eq!(ContractsNodePin::load(project.path())?, None);
+		// Any other configuration within the file is retained.
+		fs::write(project.path().join(CONFIG_FILE), "[other]\nkey = \"value\"\n")?;
+		assert_eq!(ContractsNodePin::load(project.path())?, None);
+
+		let (archive, _) = ARCHIVES[0];
+		let pin = ContractsNodePin {
+			version: "v0.41.0".to_string(),
+			checksums: BTreeMap::from([(archive.to_string(), "abcd".to_string())]),
+		};
+		pin.save(project.path())?;
+		assert_eq!(ContractsNodePin::load(project.path())?, Some(pin.clone()));
+		let config = fs::read_to_string(project.path().join(CONFIG_FILE))?;
+		assert!(config.contains("[other]"));
+		assert!(config.contains("[contracts-node]\nversion = \"v0.41.0\""));
+
+		let pin = ContractsNodePin { version: "v0.42.0".to_string(), ..Default::default() };
+		pin.save(project.path())?;
+		assert_eq!(ContractsNodePin::load(project.path())?, Some(pin));
 		Ok(())
 	}
 
+	#[test]
+	fn contracts_node_pin_checksum_works() {
+		let pin = ContractsNodePin {
+			version: "v0.41.0".to_string(),
+			checksums: ARCHIVES
+				.iter()
+				.map(|(archive, _)| (archive.to_string(), format!("{archive}-checksum")))
+				.collect(),
+		};
+		match archive_by_target() {
+			Ok((archive, _)) => {
+				assert_eq!(pin.checksum(), Some(format!("{archive}-checksum").as_str()))
+			},
+			Err(_) => assert_eq!(pin.checksum(), None),
+		}
+		assert_eq!(ContractsNodePin::default().checksum(), None);
+	}
+
 	#[tokio::test]
 	async fn is_chain_alive_works() -> Result<(), Error> {
 		let local_url = url::Url::parse("ws://localhost:9944")?;
@@ -147,14 +578,63 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn contracts_nodes_are_recorded_and_stopped() -> Result<(), Error> {
+		let cache = tempfile::tempdir()?;
+		assert!(contracts_nodes(cache.path())?.is_empty());
+		// Stand-ins for the processes of two nodes, one which has since exited, and one whose
+		// process ID is now used by another process.
+		let binary = cache.path().join(BIN_NAME);
+		let sleep = String::from_utf8(Command::new("which").arg("sleep").output()?.stdout)?;
+		fs::copy(sleep.trim(), &binary)?;
+		let mut processes =
+			[Command::new(&binary).arg("30").spawn()?, Command::new(&binary).arg("30").spawn()?];
+		let mut exited = Command::new("true").spawn()?;
+		exited.wait()?;
+		let mut other = Command::new("sleep").arg("30").spawn()?;
+		let nodes = vec![
+			ContractsNode { pid: processes[0].id(), port: 9944, log: None },
+			ContractsNode {
+				pid: processes[1].id(),
+				port: 9945,
+				log: Some(contracts_node_log(cache.path(), 9945)),
+			},
+			ContractsNode { pid: exited.id(), port: 9946, log: None },
+			ContractsNode { pid: other.id(), port: 9947, log: None },
+		];
+		save_contracts_nodes(cache.path(), &nodes)?;
+
+		assert_eq!(contracts_nodes(cache.path())?, nodes[..2]);
+		assert_eq!(nodes[1].url().as_str(), "ws://localhost:9945/");
+		assert_eq!(stop_contracts_nodes(cache.path(), Some(9945))?, nodes[1..2]);
+		processes[1].wait()?;
+		assert_eq!(contracts_nodes(cache.path())?, nodes[..1]);
+		assert_eq!(stop_contracts_nodes(cache.path(), None)?, nodes[..1]);
+		processes[0].wait()?;
+		assert!(contracts_nodes(cache.path())?.is_empty());
+		// The other process is left running.
+		assert!(other.try_wait()?.is_none());
+		other.kill()?;
+		Ok(())
+	}
+
+	#[test]
+	fn contracts_node_opts_args_works() {
+		assert_eq!(ContractsNodeOpts::default().args(), ["--rpc-port", "9944"]);
+		let opts = ContractsNodeOpts { port: 9955, dev: true, temporary: true, log: None };
+		assert_eq!(opts.args(), ["--rpc-port", "9955", "--dev", "--tmp"]);
+		assert_eq!(opts.url().as_str(), "ws://localhost:9955/");
+	}
+
 	#[tokio::test]
 	async fn run_contracts_node_works() -> Result<(), Error> {
 		let local_url = url::Url::parse("ws://localhost:9944")?;
 		assert!(!is_chain_alive(local_url.clone()).await?);
 		// Run the contracts node
 		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
-		let cache = temp_dir.path().join("cache");
-		let mut process = run_contracts_node(cache).await?;
+		let binary = contracts_node_generator(temp_dir.path(), None, None).await?;
+		binary.source(false, &(), true).await?;
+		let mut process = run_contracts_node(&binary.path(), &ContractsNodeOpts::default()).await?;
 		// Check if the node is alive
 		assert!(is_chain_alive(local_url).await?);
 		process.kill()?;
@@ -0,0 +1,270 @@
+// SPDX-License-Identifier: GPL-3.0
+use contract_extrinsics::ErrorVariant;
+use contract_transcode::{ContractMessageTranscoder, Value};
+use serde::Serialize;
+use serde_json::{json, Map};
+use sp_core::bytes::to_hex;
+use std::fmt::{self, Display};
+
+/// The reason a contract call reverted or failed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractError {
+	/// An error raised by ink! itself, e.g. `CouldNotReadInput` when the selector of the message
+	/// is unknown.
+	LangError(String),
+	/// An error returned by the contract, such as a variant of its user-defined error enum.
+	Revert(serde_json::Value),
+	/// An error raised by the runtime module, e.g. `Contracts::ContractTrapped`.
+	Module {
+		/// The pallet which raised the error.
+		pallet: String,
+		/// The name of the error.
+		error: String,
+		/// The documentation of the error.
+		docs: Vec<String>,
+	},
+	/// Any other error which occurred while dispatching the call.
+	Other(String),
+}
+
+impl Display for ContractError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ContractError::LangError(error) => write!(f, "ink! language error: {error}"),
+			ContractError::Revert(error) => write!(f, "Contract reverted: {error}"),
+			ContractError::Module { pallet, error, docs } => {
+				write!(f, "{pallet}::{error}")?;
+				if !docs.is_empty() {
+					write!(f, ": {}", docs.join(" "))?;
+				}
+				Ok(())
+			},
+			ContractError::Other(error) => write!(f, "{error}"),
+		}
+	}
+}
+
+/// Decodes the data returned by a contract message, distinguishing the value returned by the
+/// message from the reason it reverted.
+///
+/// # Arguments
+///
+/// * `transcoder` - the transcoder for the contract.
+/// * `message` - the label of the message which was called.
+/// * `data` - the data returned by the contract.
+/// * `did_revert` - whether the contract reverted the call.
+pub(crate) fn decode_message_return(
+	transcoder: &ContractMessageTranscoder,
+	message: &str,
+	data: &[u8],
+	did_revert: bool,
+) -> Result<serde_json::Value, ContractError> {
+	let value = match transcoder.decode_message_return(message, &mut &data[..]) {
+		Ok(value) => value,
+		Err(_) if did_revert => return Err(ContractError::Revert(json!(to_hex(data, false)))),
+		Err(e) => return Err(ContractError::Other(format!("Failed to decode return value: {e}"))),
+	};
+	// Messages return a `Result<T, LangError>`, with any errors of the message itself returned
+	// (and the call reverted) as an `Err` within `T`.
+	match variant(&value).as_ref().map(|(ident, value)| (ident.as_str(), *value)) {
+		Some(("Ok", Some(value))) if did_revert => {
+			match variant(value).map(|(i, v)| (i == "Err", v)) {
+				Some((true, Some(error))) => Err(ContractError::Revert(value_to_json(error))),
+				_ => Err(ContractError::Revert(value_to_json(value))),
+			}
+		},
+		Some(("Ok", Some(value))) => Ok(value_to_json(value)),
+		Some(("Err", Some(error))) => Err(ContractError::LangError(error.to_string())),
+		_ if did_revert => Err(ContractError::Revert(value_to_json(&value))),
+		_ => Ok(value_to_json(&value)),
+	}
+}
+
+impl From<ErrorVariant> for ContractError {
+	fn from(error: ErrorVariant) -> Self {
+		match error {
+			ErrorVariant::Module(module) => ContractError::Module {
+				pallet: module.pallet,
+				error: module.error,
+				docs: module.docs,
+			},
+			error => ContractError::Other(error.to_string()),
+		}
+	}
+}
+
+/// Converts a value decoded by the contract transcoder into JSON.
+///
+/// Structs become objects, `Option`s become either `null` or their value, the unit type becomes
+/// `null`, unit variants become their name and any other enum variant becomes an object keyed by
+/// the variant name.
+///
+/// # Arguments
+///
+/// * `value` - the value to be converted.
+pub fn value_to_json(value: &Value) -> serde_json::Value {
+	match value {
+		Value::Bool(value) => json!(value),
+		Value::Char(value) => json!(value.to_string()),
+		Value::UInt(value) => match u64::try_from(*value) {
+			Ok(value) => json!(value),
+			Err(_) => json!(value.to_string()),
+		},
+		Value::Int(value) => match i64::try_from(*value) {
+			Ok(value) => json!(value),
+			Err(_) => json!(value.to_string()),
+		},
+		Value::Map(map) => {
+			let fields = map
+				.iter()
+				.map(|(key, value)| {
+					let key = match key {
+						Value::String(key) | Value::Literal(key) => key.clone(),
+						key => key.to_string(),
+					};
+					(key, value_to_json(value))
+				})
+				.collect::<Map<_, _>>();
+			serde_json::Value::Object(fields)
+		},
+		Value::Tuple(tuple) => {
+			let values: Vec<_> = tuple.values().map(value_to_json).collect();
+			match (tuple.ident().as_deref(), values.len()) {
+				(Some("None"), 0) | (None, 0) => serde_json::Value::Null,
+				(Some("Some"), 1) => values[0].clone(),
+				(Some(ident), 0) => json!(ident),
+				(Some(ident), 1) => json!({ ident: values[0] }),
+				(Some(ident), _) => json!({ ident: values }),
+				(None, _) => json!(values),
+			}
+		},
+		Value::String(value) | Value::Literal(value) => json!(value),
+		Value::Seq(seq) => json!(seq.elems().iter().map(value_to_json).collect::<Vec<_>>()),
+		Value::Hex(hex) => json!(hex.as_str()),
+		Value::Unit => serde_json::Value::Null,
+	}
+}
+
+// Returns the name and the single value of an enum variant, such as `Ok(value)`.
+fn variant(value: &Value) -> Option<(String, Option<&Value>)> {
+	match value {
+		Value::Tuple(tuple) => {
+			let ident = tuple.ident()?;
+			let mut values = tuple.values();
+			match (values.next(), values.next()) {
+				(value, None) => Some((ident, value)),
+				_ => None,
+			}
+		},
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::utils::metadata::get_metadata;
+	use anyhow::Result;
+	use contract_transcode::{Map, Tuple};
+	use std::path::PathBuf;
+
+	fn testing_transcoder() -> Result<ContractMessageTranscoder> {
+		let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/files/testing.json");
+		Ok(ContractMessageTranscoder::new(get_metadata(&Some(path))?))
+	}
+
+	#[test]
+	fn value_to_json_works() {
+		assert_eq!(value_to_json(&Value::Bool(true)), json!(true));
+		assert_eq!(value_to_json(&Value::UInt(42)), json!(42));
+		assert_eq!(value_to_json(&Value::UInt(u128::MAX)), json!(u128::MAX.to_string()));
+		assert_eq!(value_to_json(&Value::Int(-1)), json!(-1));
+		assert_eq!(value_to_json(&Value::String("pop".into())), json!("pop"));
+		assert_eq!(value_to_json(&Value::Tuple(Tuple::new(Some("None"), vec![]))), json!(null));
+		assert_eq!(
+			value_to_json(&Value::Tuple(Tuple::new(Some("Some"), vec![Value::UInt(1)]))),
+			json!(1)
+		);
+		assert_eq!(
+			value_to_json(&Value::Tuple(Tuple::new(Some("NotOwner"), vec![]))),
+			json!("NotOwner")
+		);
+		assert_eq!(
+			value_to_json(&Value::Tuple(Tuple::new(Some("Weighted"), vec![Value::UInt(3)]))),
+			json!({ "Weighted": 3 })
+		);
+		assert_eq!(
+			value_to_json(&Value::Tuple(Tuple::new(None, vec![Value::Bool(true), Value::UInt(1)]))),
+			json!([true, 1])
+		);
+		assert_eq!(
+			value_to_json(&Value::Map(Map::new(
+				Some("Details"),
+				[
+					(Value::String("name".into()), Value::String("Alice".into())),
+					(Value::String("amount".into()), Value::UInt(100)),
+				]
+				.into_iter()
+				.collect(),
+			))),
+			json!({ "name": "Alice", "amount": 100 })
+		);
+	}
+
+	#[test]
+	fn decode_message_return_works() -> Result<()> {
+		let transcoder = testing_transcoder()?;
+		// `Ok(true)`
+		assert_eq!(decode_message_return(&transcoder, "get", &[0, 1], false), Ok(json!(true)));
+		// `Ok(3)`
+		assert_eq!(
+			decode_message_return(&transcoder, "flips_of", &[0, 3, 0, 0, 0], false),
+			Ok(json!(3))
+		);
+		// `Ok(Ok(()))`
+		assert_eq!(
+			decode_message_return(&transcoder, "owner_flip", &[0, 0], false),
+			Ok(json!({ "Ok": null }))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn decode_message_return_decodes_reverts() -> Result<()> {
+		let transcoder = testing_transcoder()?;
+		// `Ok(Err(Error::NotOwner))`
+		assert_eq!(
+			decode_message_return(&transcoder, "owner_flip", &[0, 1, 0], true),
+			Err(ContractError::Revert(json!("NotOwner")))
+		);
+		// `Err(LangError::CouldNotReadInput)`
+		assert_eq!(
+			decode_message_return(&transcoder, "get", &[1, 1], true),
+			Err(ContractError::LangError("CouldNotReadInput".to_string()))
+		);
+		// Undecodable data is returned as hex.
+		assert_eq!(
+			decode_message_return(&transcoder, "get", &[], true),
+			Err(ContractError::Revert(json!("0x")))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn contract_error_display_works() {
+		assert_eq!(
+			ContractError::Module {
+				pallet: "Contracts".to_string(),
+				error: "ContractTrapped".to_string(),
+				docs: vec!["Contract trapped during execution.".to_string()],
+			}
+			.to_string(),
+			"Contracts::ContractTrapped: Contract trapped during execution."
+		);
+		assert_eq!(
+			ContractError::Revert(json!("NotOwner")).to_string(),
+			"Contract reverted: \"NotOwner\""
+		);
+	}
+}
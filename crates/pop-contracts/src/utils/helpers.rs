@@ -1,10 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::errors::Error;
-use contract_build::ManifestPath;
-use contract_extrinsics::BalanceVariant;
+use crate::{errors::Error, utils::signer::Keypair};
+use contract_build::{util::decode_hex, ManifestPath};
+use contract_extrinsics::{
+	pallet_contracts_primitives::StorageDeposit, BalanceVariant, TokenMetadata,
+};
 use ink_env::{DefaultEnvironment, Environment};
+use sp_weights::Weight;
 use std::{path::PathBuf, str::FromStr};
-use subxt::{Config, PolkadotConfig as DefaultConfig};
+use subxt::{
+	dynamic::Value, tx::DynamicPayload, Config, OnlineClient, PolkadotConfig as DefaultConfig,
+};
+use url::Url;
 
 pub fn get_manifest_path(path: &Option<PathBuf>) -> Result<ManifestPath, Error> {
 	if let Some(path) = path {
@@ -28,6 +34,101 @@ pub fn parse_account(account: &str) -> Result<<DefaultConfig as Config>::Account
 		.map_err(|e| Error::AccountAddressParsing(format!("{}", e)))
 }
 
+/// Parse a hex encoded code hash.
+pub fn parse_code_hash(code_hash: &str) -> Result<[u8; 32], Error> {
+	let bytes = decode_hex(code_hash).map_err(|e| Error::HexParsing(format!("{}", e)))?;
+	bytes.try_into().map_err(|bytes: Vec<u8>| {
+		Error::HexParsing(format!("expected 32 bytes for the code hash, found {}", bytes.len()))
+	})
+}
+
+/// The storage deposit charged by a dry run, which is zero if storage deposits are refunded.
+pub(crate) fn deposit_charged(deposit: &StorageDeposit<u128>) -> u128 {
+	match deposit {
+		StorageDeposit::Charge(amount) => *amount,
+		StorageDeposit::Refund(_) => 0,
+	}
+}
+
+/// Parse the maximum storage deposit which may be charged, e.g. `1000` or `1.5 UNIT`, denominated
+/// using the token of the chain.
+///
+/// # Arguments
+///
+/// * `limit` - the storage deposit limit, if any.
+/// * `token_metadata` - the token of the chain.
+pub(crate) fn parse_storage_deposit_limit(
+	limit: &Option<String>,
+	token_metadata: &TokenMetadata,
+) -> Result<Option<u128>, Error> {
+	limit
+		.as_ref()
+		.map(|limit| {
+			parse_balance(limit)?
+				.denominate_balance(token_metadata)
+				.map_err(|e| Error::BalanceParsing(format!("{}", e)))
+		})
+		.transpose()
+}
+
+/// Increase an estimated weight by a margin, so that an extrinsic does not run out of gas when the
+/// state of the chain changes between the estimate and its inclusion in a block.
+///
+/// # Arguments
+///
+/// * `weight` - the estimated weight.
+/// * `margin` - the margin, as a percentage of the estimated weight.
+pub fn apply_weight_margin(weight: Weight, margin: u32) -> Weight {
+	let increase = |value: u64| {
+		let value = value as u128 * (100 + margin as u128) / 100;
+		u64::try_from(value).unwrap_or(u64::MAX)
+	};
+	Weight::from_parts(increase(weight.ref_time()), increase(weight.proof_size()))
+}
+
+/// Format a balance using the token of a chain, e.g. `1.5mUNIT`.
+///
+/// # Arguments
+///
+/// * `balance` - the balance to format.
+/// * `url` - the websocket endpoint of the chain.
+pub async fn format_balance(balance: u128, url: &Url) -> anyhow::Result<String> {
+	let token_metadata = TokenMetadata::query::<DefaultConfig>(url).await?;
+	Ok(BalanceVariant::<u128>::from(balance, Some(&token_metadata))?.to_string())
+}
+
+// A weight, as an argument of a dynamic extrinsic.
+pub(crate) fn weight_value(weight: Weight) -> Value {
+	Value::named_composite([
+		("ref_time", Value::u128(weight.ref_time() as u128)),
+		("proof_size", Value::u128(weight.proof_size() as u128)),
+	])
+}
+
+// An optional storage deposit limit, as an argument of a dynamic extrinsic.
+pub(crate) fn storage_deposit_limit_value(limit: Option<u128>) -> Value {
+	match limit {
+		Some(limit) => Value::unnamed_variant("Some", [Value::u128(limit)]),
+		None => Value::unnamed_variant("None", []),
+	}
+}
+
+/// Estimate the fee charged for submitting an extrinsic, excluding any tip.
+///
+/// # Arguments
+///
+/// * `client` - the client of the chain.
+/// * `tx` - the extrinsic to be submitted.
+/// * `signer` - the account submitting the extrinsic.
+pub(crate) async fn estimate_fee(
+	client: &OnlineClient<DefaultConfig>,
+	tx: &DynamicPayload,
+	signer: &Keypair,
+) -> anyhow::Result<u128> {
+	let extrinsic = client.tx().create_signed(tx, signer, Default::default()).await?;
+	Ok(extrinsic.partial_fee_estimate().await?)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -38,7 +139,11 @@ mod tests {
 		let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
 		let temp_contract_dir = temp_dir.path().join("test_contract");
 		fs::create_dir(&temp_contract_dir)?;
-		crate::create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+		crate::create_smart_contract(
+			"test_contract",
+			temp_contract_dir.as_path(),
+			&crate::ContractTemplate::Standard,
+		)?;
 		Ok(temp_dir)
 	}
 
@@ -48,4 +153,46 @@ mod tests {
 		get_manifest_path(&Some(PathBuf::from(temp_dir.path().join("test_contract"))))?;
 		Ok(())
 	}
+
+	#[test]
+	fn test_parse_code_hash() -> Result<(), Error> {
+		let code_hash = "0xbd09e4d0b8a0ef6e8dd7d0ba0a3e2a32b6fba3e2e8b3ff1e0b8ee4f93b1dc4a6";
+		assert_eq!(parse_code_hash(code_hash)?, decode_hex(code_hash)?.as_slice());
+		assert!(parse_code_hash("0x1234").is_err());
+		assert!(parse_code_hash("not hex").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn parse_storage_deposit_limit_works() -> Result<(), Error> {
+		let token_metadata = TokenMetadata { token_decimals: 10, symbol: "UNIT".to_string() };
+		assert_eq!(parse_storage_deposit_limit(&None, &token_metadata)?, None);
+		assert_eq!(
+			parse_storage_deposit_limit(&Some("1000".to_string()), &token_metadata)?,
+			Some(1000)
+		);
+		assert_eq!(
+			parse_storage_deposit_limit(&Some("1.5UNIT".to_string()), &token_metadata)?,
+			Some(15_000_000_000)
+		);
+		assert!(parse_storage_deposit_limit(&Some("many".to_string()), &token_metadata).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn apply_weight_margin_works() {
+		let weight = Weight::from_parts(1_000, 200);
+		assert_eq!(apply_weight_margin(weight, 0), weight);
+		assert_eq!(apply_weight_margin(weight, 10), Weight::from_parts(1_100, 220));
+		assert_eq!(
+			apply_weight_margin(Weight::from_parts(u64::MAX, 1), 50),
+			Weight::from_parts(u64::MAX, 1)
+		);
+	}
+
+	#[test]
+	fn deposit_charged_works() {
+		assert_eq!(deposit_charged(&StorageDeposit::Charge(100)), 100);
+		assert_eq!(deposit_charged(&StorageDeposit::Refund(100)), 0);
+	}
 }
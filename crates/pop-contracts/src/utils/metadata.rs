@@ -0,0 +1,552 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{errors::Error, utils::helpers::get_manifest_path};
+use contract_extrinsics::ContractArtifacts;
+use contract_transcode::{
+	ink_metadata::{InkProject, MessageParamSpec},
+	ContractMessageTranscoder,
+};
+use scale_info::{form::PortableForm, PortableRegistry, Type, TypeDef, TypeDefPrimitive};
+use std::path::PathBuf;
+
+/// Describes a parameter of a contract function.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Param {
+	/// The label of the parameter.
+	pub label: String,
+	/// The type name of the parameter.
+	pub type_name: String,
+}
+
+/// Describes a contract function, either a constructor or a message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractFunction {
+	/// The label of the function.
+	pub label: String,
+	/// Whether the function accepts a value transfer from the caller.
+	pub payable: bool,
+	/// The parameters of the function.
+	pub args: Vec<Param>,
+	/// The documentation of the function.
+	pub docs: String,
+	/// Whether the function is marked as the default.
+	pub default: bool,
+	/// Whether the function mutates the state of the contract. Always `true` for constructors.
+	pub mutates: bool,
+}
+
+/// The type of a contract function.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FunctionType {
+	/// A function used to instantiate a contract.
+	Constructor,
+	/// A function used to call a contract.
+	Message,
+}
+
+/// Extracts the messages of a contract from its metadata.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract.
+pub fn get_messages(path: &Option<PathBuf>) -> Result<Vec<ContractFunction>, Error> {
+	let metadata = get_metadata(path)?;
+	Ok(metadata
+		.spec()
+		.messages()
+		.iter()
+		.map(|message| ContractFunction {
+			label: message.label().to_string(),
+			payable: message.payable(),
+			args: process_args(message.args(), metadata.registry()),
+			docs: process_docs(message.docs()),
+			default: *message.default(),
+			mutates: message.mutates(),
+		})
+		.collect())
+}
+
+/// Extracts the constructors of a contract from its metadata.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract.
+pub fn get_constructors(path: &Option<PathBuf>) -> Result<Vec<ContractFunction>, Error> {
+	let metadata = get_metadata(path)?;
+	Ok(metadata
+		.spec()
+		.constructors()
+		.iter()
+		.map(|constructor| ContractFunction {
+			label: constructor.label().to_string(),
+			payable: *constructor.payable(),
+			args: process_args(constructor.args(), metadata.registry()),
+			docs: process_docs(constructor.docs()),
+			default: *constructor.default(),
+			mutates: true,
+		})
+		.collect())
+}
+
+/// Extracts a specific constructor or message of a contract from its metadata.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract.
+/// * `label` - the label of the function.
+/// * `function_type` - whether the function is a constructor or a message.
+pub fn get_function(
+	path: &Option<PathBuf>,
+	label: &str,
+	function_type: FunctionType,
+) -> Result<ContractFunction, Error> {
+	let functions = match function_type {
+		FunctionType::Constructor => get_constructors(path)?,
+		FunctionType::Message => get_messages(path)?,
+	};
+	functions
+		.into_iter()
+		.find(|f| f.label == label)
+		.ok_or_else(|| match function_type {
+			FunctionType::Constructor => Error::InvalidConstructorName(label.to_string()),
+			FunctionType::Message => Error::InvalidMessageName(label.to_string()),
+		})
+}
+
+/// Validates the arguments for a constructor or message against the types declared in the
+/// contract metadata, so that invalid input is rejected before anything is submitted.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract.
+/// * `label` - the label of the function.
+/// * `args` - the arguments provided for the function, encoded as strings.
+/// * `function_type` - whether the function is a constructor or a message.
+pub fn validate_function_args(
+	path: &Option<PathBuf>,
+	label: &str,
+	args: &[String],
+	function_type: FunctionType,
+) -> Result<(), Error> {
+	let transcoder = ContractMessageTranscoder::new(get_metadata(path)?);
+	let spec = transcoder.metadata().spec();
+	let params = match function_type {
+		FunctionType::Constructor => spec
+			.constructors()
+			.iter()
+			.find(|c| c.label() == label)
+			.map(|c| c.args())
+			.ok_or_else(|| Error::InvalidConstructorName(label.to_string()))?,
+		FunctionType::Message => spec
+			.messages()
+			.iter()
+			.find(|m| m.label() == label)
+			.map(|m| m.args())
+			.ok_or_else(|| Error::InvalidMessageName(label.to_string()))?,
+	};
+	if params.len() != args.len() {
+		return Err(Error::IncorrectArguments { expected: params.len(), provided: args.len() });
+	}
+	let Err(error) = transcoder.encode(label, args) else {
+		return Ok(());
+	};
+
+	// Locate the invalid argument by encoding each provided argument alongside example values
+	// for the others.
+	let registry = transcoder.metadata().registry();
+	let types: Vec<_> = params.iter().map(|param| registry.resolve(param.ty().ty().id)).collect();
+	let examples: Vec<_> = types
+		.iter()
+		.map(|ty| ty.map(|ty| example_value(ty, registry)).unwrap_or_default())
+		.collect();
+	for (i, param) in params.iter().enumerate() {
+		let mut candidate = examples.clone();
+		candidate[i] = args[i].clone();
+		if let Err(e) = transcoder.encode(label, &candidate) {
+			return Err(Error::InvalidArgument {
+				param: param.label().to_string(),
+				type_name: types[i]
+					.map(|ty| format_type(ty, registry))
+					.unwrap_or_else(|| param.ty().display_name().to_string()),
+				example: examples[i].clone(),
+				reason: e.to_string().lines().next().unwrap_or_default().to_string(),
+			});
+		}
+	}
+	Err(Error::InvalidArguments(error.to_string()))
+}
+
+/// Generates example arguments for a constructor or message from the types declared in the
+/// contract metadata, such as for profiling a function when no arguments are provided.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract.
+/// * `label` - the label of the function.
+/// * `function_type` - whether the function is a constructor or a message.
+pub(crate) fn example_args(
+	path: &Option<PathBuf>,
+	label: &str,
+	function_type: FunctionType,
+) -> Result<Vec<String>, Error> {
+	let metadata = get_metadata(path)?;
+	let spec = metadata.spec();
+	let params = match function_type {
+		FunctionType::Constructor => spec
+			.constructors()
+			.iter()
+			.find(|c| c.label() == label)
+			.map(|c| c.args())
+			.ok_or_else(|| Error::InvalidConstructorName(label.to_string()))?,
+		FunctionType::Message => spec
+			.messages()
+			.iter()
+			.find(|m| m.label() == label)
+			.map(|m| m.args())
+			.ok_or_else(|| Error::InvalidMessageName(label.to_string()))?,
+	};
+	let registry = metadata.registry();
+	Ok(params
+		.iter()
+		.map(|param| {
+			registry
+				.resolve(param.ty().ty().id)
+				.map(|ty| example_value(ty, registry))
+				.unwrap_or_default()
+		})
+		.collect())
+}
+
+/// Loads the metadata of the contract at the specified path, which can be either the contract
+/// build folder or a contract artifact file (`.contract` or `.json`).
+pub(crate) fn get_metadata(path: &Option<PathBuf>) -> Result<InkProject, Error> {
+	get_artifacts(path)?.ink_project_metadata().map_err(Error::ContractMetadata)
+}
+
+/// Loads the artifacts of the contract at the specified path, which can be either the contract
+/// build folder or a contract artifact file (`.contract` or `.json`).
+pub(crate) fn get_artifacts(path: &Option<PathBuf>) -> Result<ContractArtifacts, Error> {
+	match path {
+		Some(file) if file.is_file() => ContractArtifacts::from_manifest_or_file(None, Some(file)),
+		_ => {
+			let manifest_path = get_manifest_path(path)?;
+			ContractArtifacts::from_manifest_or_file(Some(&manifest_path.into()), None)
+		},
+	}
+	.map_err(Error::ContractMetadata)
+}
+
+// Describes the parameters of a function, resolving their types from the registry.
+fn process_args(
+	args: &[MessageParamSpec<PortableForm>],
+	registry: &PortableRegistry,
+) -> Vec<Param> {
+	args.iter()
+		.map(|arg| Param {
+			label: arg.label().to_string(),
+			type_name: registry
+				.resolve(arg.ty().ty().id)
+				.map(|ty| format_type(ty, registry))
+				.unwrap_or_else(|| arg.ty().display_name().to_string()),
+		})
+		.collect()
+}
+
+// Joins the lines of documentation into a single string.
+fn process_docs(docs: &[String]) -> String {
+	docs.iter()
+		.map(|line| line.trim())
+		.collect::<Vec<_>>()
+		.join(" ")
+		.trim()
+		.to_string()
+}
+
+/// Formats a type as it would be written in Rust, e.g. `Option<AccountId>`.
+///
+/// # Arguments
+///
+/// * `ty` - the type to be formatted.
+/// * `registry` - the registry containing the type and any type parameters.
+pub(crate) fn format_type(ty: &Type<PortableForm>, registry: &PortableRegistry) -> String {
+	let resolve =
+		|id: u32| registry.resolve(id).map(|ty| format_type(ty, registry)).unwrap_or_default();
+	if let Some(name) = ty.path.segments.last() {
+		let params: Vec<_> =
+			ty.type_params.iter().filter_map(|p| p.ty.map(|ty| resolve(ty.id))).collect();
+		return match params.is_empty() {
+			true => name.to_string(),
+			false => format!("{name}<{}>", params.join(", ")),
+		};
+	}
+	match &ty.type_def {
+		TypeDef::Primitive(primitive) => format!("{primitive:?}").to_lowercase(),
+		TypeDef::Sequence(sequence) => format!("Vec<{}>", resolve(sequence.type_param.id)),
+		TypeDef::Array(array) => format!("[{}; {}]", resolve(array.type_param.id), array.len),
+		TypeDef::Tuple(tuple) => {
+			let fields: Vec<_> = tuple.fields.iter().map(|field| resolve(field.id)).collect();
+			format!("({})", fields.join(", "))
+		},
+		TypeDef::Compact(compact) => format!("Compact<{}>", resolve(compact.type_param.id)),
+		TypeDef::BitSequence(_) => "BitSequence".to_string(),
+		TypeDef::Composite(_) | TypeDef::Variant(_) => String::new(),
+	}
+}
+
+/// Generates an example value for a type, formatted as it would be provided as an argument, e.g.
+/// `Some(0)`.
+///
+/// # Arguments
+///
+/// * `ty` - the type for which an example value is generated.
+/// * `registry` - the registry containing the type and any type parameters.
+pub(crate) fn example_value(ty: &Type<PortableForm>, registry: &PortableRegistry) -> String {
+	let resolve =
+		|id: u32| registry.resolve(id).map(|ty| example_value(ty, registry)).unwrap_or_default();
+	let name = ty.path.segments.last().map(|s| s.as_str()).unwrap_or_default();
+	match name {
+		"AccountId" => return "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+		"Option" =>
+			return ty
+				.type_params
+				.iter()
+				.find_map(|p| p.ty)
+				.map(|ty| format!("Some({})", resolve(ty.id)))
+				.unwrap_or_else(|| "None".to_string()),
+		_ => {},
+	}
+	let fields = |fields: &[scale_info::Field<PortableForm>]| -> String {
+		if fields.is_empty() {
+			String::new()
+		} else if fields.iter().all(|f| f.name.is_some()) {
+			let fields: Vec<_> = fields
+				.iter()
+				.map(|f| format!("{}: {}", f.name.as_deref().unwrap_or_default(), resolve(f.ty.id)))
+				.collect();
+			format!(" {{ {} }}", fields.join(", "))
+		} else {
+			let fields: Vec<_> = fields.iter().map(|f| resolve(f.ty.id)).collect();
+			format!("({})", fields.join(", "))
+		}
+	};
+	let is_byte = |id: u32| {
+		registry
+			.resolve(id)
+			.is_some_and(|ty| ty.type_def == TypeDef::Primitive(TypeDefPrimitive::U8))
+	};
+	match &ty.type_def {
+		TypeDef::Composite(composite) => format!("{name}{}", fields(&composite.fields)),
+		TypeDef::Variant(variant) => variant
+			.variants
+			.first()
+			.map(|v| format!("{}{}", v.name, fields(&v.fields)))
+			.unwrap_or_default(),
+		TypeDef::Sequence(sequence) if is_byte(sequence.type_param.id) => "0x00".to_string(),
+		TypeDef::Sequence(sequence) => format!("[{}]", resolve(sequence.type_param.id)),
+		TypeDef::Array(array) if is_byte(array.type_param.id) => {
+			format!("0x{}", "00".repeat(array.len as usize))
+		},
+		TypeDef::Array(array) => {
+			let elements = vec![resolve(array.type_param.id); array.len as usize];
+			format!("[{}]", elements.join(", "))
+		},
+		TypeDef::Tuple(tuple) => {
+			let fields: Vec<_> = tuple.fields.iter().map(|field| resolve(field.id)).collect();
+			format!("({})", fields.join(", "))
+		},
+		TypeDef::Primitive(primitive) => match primitive {
+			TypeDefPrimitive::Bool => "true".to_string(),
+			TypeDefPrimitive::Char => "'a'".to_string(),
+			TypeDefPrimitive::Str => "\"Hello\"".to_string(),
+			TypeDefPrimitive::I8 |
+			TypeDefPrimitive::I16 |
+			TypeDefPrimitive::I32 |
+			TypeDefPrimitive::I64 |
+			TypeDefPrimitive::I128 |
+			TypeDefPrimitive::I256 => "-1".to_string(),
+			_ => "0".to_string(),
+		},
+		TypeDef::Compact(compact) => resolve(compact.type_param.id),
+		TypeDef::BitSequence(_) => String::new(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	fn testing_contract() -> Option<PathBuf> {
+		Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/files/testing.json"))
+	}
+
+	#[test]
+	fn get_messages_works() -> Result<()> {
+		let messages = get_messages(&testing_contract())?;
+		assert_eq!(messages.len(), 6);
+		assert_eq!(messages[0].label, "flip");
+		assert_eq!(messages[0].docs, "A message that can be called on instantiated contracts. This one flips the value of the stored `bool` from `true` to `false` and vice versa.");
+		assert!(messages[0].mutates);
+		assert!(!messages[0].payable);
+		assert_eq!(messages[1].label, "get");
+		assert!(!messages[1].mutates);
+		assert!(messages[1].args.is_empty());
+		assert_eq!(messages[2].label, "specific_flip");
+		assert!(messages[2].payable);
+		assert_eq!(
+			messages[2].args,
+			vec![
+				Param { label: "new_value".to_string(), type_name: "bool".to_string() },
+				Param { label: "number".to_string(), type_name: "Option<u32>".to_string() },
+			]
+		);
+		assert_eq!(
+			messages[4].args,
+			vec![Param { label: "account".to_string(), type_name: "AccountId".to_string() }]
+		);
+		assert_eq!(
+			messages[5].args,
+			vec![
+				Param { label: "details".to_string(), type_name: "Details".to_string() },
+				Param { label: "kind".to_string(), type_name: "Kind".to_string() },
+				Param {
+					label: "beneficiaries".to_string(),
+					type_name: "Vec<AccountId>".to_string()
+				},
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn get_constructors_works() -> Result<()> {
+		let constructors = get_constructors(&testing_contract())?;
+		assert_eq!(constructors.len(), 2);
+		assert_eq!(constructors[0].label, "new");
+		assert_eq!(
+			constructors[0].args,
+			vec![Param { label: "init_value".to_string(), type_name: "bool".to_string() }]
+		);
+		assert!(!constructors[0].default);
+		assert_eq!(constructors[1].label, "default");
+		assert!(constructors[1].default);
+		assert!(constructors.iter().all(|c| c.mutates));
+		Ok(())
+	}
+
+	#[test]
+	fn get_function_works() -> Result<()> {
+		let message = get_function(&testing_contract(), "get", FunctionType::Message)?;
+		assert_eq!(message.label, "get");
+		let constructor = get_function(&testing_contract(), "new", FunctionType::Constructor)?;
+		assert_eq!(constructor.label, "new");
+		assert!(matches!(
+			get_function(&testing_contract(), "wrong", FunctionType::Message),
+			Err(Error::InvalidMessageName(name)) if name == "wrong"
+		));
+		assert!(matches!(
+			get_function(&testing_contract(), "get", FunctionType::Constructor),
+			Err(Error::InvalidConstructorName(name)) if name == "get"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn example_args_works() -> Result<()> {
+		let path = testing_contract();
+		let args = example_args(&path, "specific_flip", FunctionType::Message)?;
+		assert_eq!(args, vec!["true", "Some(0)"]);
+		// The example arguments are valid for the function.
+		validate_function_args(&path, "specific_flip", &args, FunctionType::Message)?;
+		assert!(example_args(&path, "default", FunctionType::Constructor)?.is_empty());
+		assert!(matches!(
+			example_args(&path, "missing", FunctionType::Message),
+			Err(Error::InvalidMessageName(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn validate_function_args_works() -> Result<()> {
+		let path = testing_contract();
+		let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+		validate_function_args(&path, "new", &args(&["true"]), FunctionType::Constructor)?;
+		validate_function_args(&path, "default", &[], FunctionType::Constructor)?;
+		validate_function_args(
+			&path,
+			"specific_flip",
+			&args(&["false", "Some(2)"]),
+			FunctionType::Message,
+		)?;
+		validate_function_args(
+			&path,
+			"specific_flip",
+			&args(&["false", "None"]),
+			FunctionType::Message,
+		)?;
+		validate_function_args(
+			&path,
+			"register",
+			&args(&[
+				"Details { name: \"Alice\", amount: 100 }",
+				"Weighted(3)",
+				"[5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY]",
+			]),
+			FunctionType::Message,
+		)?;
+		Ok(())
+	}
+
+	#[test]
+	fn validate_function_args_fails_for_invalid_args() -> Result<()> {
+		let path = testing_contract();
+		let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+		assert!(matches!(
+			validate_function_args(&path, "new", &[], FunctionType::Constructor),
+			Err(Error::IncorrectArguments { expected: 1, provided: 0 })
+		));
+		assert!(matches!(
+			validate_function_args(&path, "wrong", &[], FunctionType::Message),
+			Err(Error::InvalidMessageName(name)) if name == "wrong"
+		));
+		assert!(matches!(
+			validate_function_args(&path, "new", &args(&["yes"]), FunctionType::Constructor),
+			Err(Error::InvalidArgument { param, type_name, example, .. })
+				if param == "init_value" && type_name == "bool" && example == "true"
+		));
+		assert!(matches!(
+			validate_function_args(&path, "specific_flip", &args(&["true", "2"]), FunctionType::Message),
+			Err(Error::InvalidArgument { param, type_name, example, .. })
+				if param == "number" && type_name == "Option<u32>" && example == "Some(0)"
+		));
+		assert!(matches!(
+			validate_function_args(&path, "flips_of", &args(&["alice"]), FunctionType::Message),
+			Err(Error::InvalidArgument { param, type_name, example, .. })
+				if param == "account" && type_name == "AccountId" &&
+					example == "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+		));
+		let error = validate_function_args(
+			&path,
+			"register",
+			&args(&["Details { name: \"Alice\" }", "Simple", "[]"]),
+			FunctionType::Message,
+		)
+		.unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Invalid value for `details`: expected Details, e.g. Details { name: \"Hello\", amount: 0 }. Missing a field named `amount`"
+		);
+		assert!(matches!(
+			validate_function_args(&path, "register", &args(&["Details { name: \"Alice\", amount: 1 }", "Heavy", "[]"]), FunctionType::Message),
+			Err(Error::InvalidArgument { param, type_name, example, .. })
+				if param == "kind" && type_name == "Kind" && example == "Simple"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn get_metadata_fails_for_missing_artifacts() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		assert!(get_messages(&Some(temp_dir.path().to_path_buf())).is_err());
+		Ok(())
+	}
+}
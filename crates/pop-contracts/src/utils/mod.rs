@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0
 pub mod contracts_node;
+pub mod decode;
 pub mod git;
 pub mod helpers;
+pub mod metadata;
 pub mod signer;
@@ -1,15 +1,204 @@
 // SPDX-License-Identifier: GPL-3.0
-use crate::errors::Error;
+use crate::{
+	accounts::{accounts_file, Account, Accounts},
+	errors::Error,
+};
 use contract_build::util::decode_hex;
-use sp_core::Bytes;
-use subxt_signer::{sr25519::Keypair, SecretUri};
+use serde::{Deserialize, Serialize};
+use sp_core::{blake2_256, ecdsa, ed25519, sr25519, Bytes, Pair};
+use std::{env, fs, path::Path};
+use strum_macros::{AsRefStr, Display, EnumString, VariantArray};
+use subxt::{
+	tx::Signer,
+	utils::{AccountId32, MultiAddress, MultiSignature},
+	PolkadotConfig as DefaultConfig,
+};
 
-/// Create a Signer from a secret URI.
-pub(crate) fn create_signer(suri: &str) -> Result<Keypair, Error> {
-	let uri = <SecretUri as std::str::FromStr>::from_str(suri)
-		.map_err(|e| Error::ParseSecretURI(format!("{}", e)))?;
-	let keypair = Keypair::from_uri(&uri).map_err(|e| Error::KeyPairCreation(format!("{}", e)))?;
-	Ok(keypair)
+/// The cryptographic scheme of a keypair.
+#[derive(
+	AsRefStr,
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	Deserialize,
+	Display,
+	EnumString,
+	Eq,
+	PartialEq,
+	Serialize,
+	VariantArray,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Scheme {
+	/// Schnorr signatures on the Ristretto group, as used by most Polkadot accounts.
+	#[default]
+	Sr25519,
+	/// Edwards-curve signatures.
+	Ed25519,
+	/// ECDSA signatures on the secp256k1 curve, with accounts identified by the hash of the
+	/// public key.
+	Ecdsa,
+}
+
+/// A keypair used to sign extrinsics, of any of the supported schemes.
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Keypair {
+	/// An sr25519 keypair.
+	Sr25519(sr25519::Pair),
+	/// An ed25519 keypair.
+	Ed25519(ed25519::Pair),
+	/// An ecdsa keypair.
+	Ecdsa(ecdsa::Pair),
+}
+
+impl Keypair {
+	/// Creates a keypair of the specified scheme from a secret URI.
+	///
+	/// # Arguments
+	///
+	/// * `suri` - the secret URI.
+	/// * `scheme` - the cryptographic scheme of the keypair.
+	pub fn from_uri(suri: &str, scheme: Scheme) -> Result<Self, Error> {
+		fn pair<P: Pair>(suri: &str) -> Result<P, Error> {
+			P::from_string(suri, None).map_err(|e| Error::KeyPairCreation(format!("{:?}", e)))
+		}
+		Ok(match scheme {
+			Scheme::Sr25519 => Keypair::Sr25519(pair(suri)?),
+			Scheme::Ed25519 => Keypair::Ed25519(pair(suri)?),
+			Scheme::Ecdsa => Keypair::Ecdsa(pair(suri)?),
+		})
+	}
+
+	/// The cryptographic scheme of the keypair.
+	pub fn scheme(&self) -> Scheme {
+		match self {
+			Keypair::Sr25519(_) => Scheme::Sr25519,
+			Keypair::Ed25519(_) => Scheme::Ed25519,
+			Keypair::Ecdsa(_) => Scheme::Ecdsa,
+		}
+	}
+
+	/// The public key of the keypair.
+	pub fn public(&self) -> Vec<u8> {
+		match self {
+			Keypair::Sr25519(pair) => pair.public().0.to_vec(),
+			Keypair::Ed25519(pair) => pair.public().0.to_vec(),
+			Keypair::Ecdsa(pair) => pair.public().0.to_vec(),
+		}
+	}
+
+	/// The account identifier of the keypair: its public key, or the hash of its public key for
+	/// ecdsa keypairs.
+	pub fn account_id(&self) -> AccountId32 {
+		match self {
+			Keypair::Sr25519(pair) => AccountId32(pair.public().0),
+			Keypair::Ed25519(pair) => AccountId32(pair.public().0),
+			Keypair::Ecdsa(pair) => AccountId32(blake2_256(&pair.public().0)),
+		}
+	}
+}
+
+impl Signer<DefaultConfig> for Keypair {
+	fn account_id(&self) -> AccountId32 {
+		self.account_id()
+	}
+
+	fn address(&self) -> MultiAddress<AccountId32, ()> {
+		self.account_id().into()
+	}
+
+	fn sign(&self, signer_payload: &[u8]) -> MultiSignature {
+		match self {
+			Keypair::Sr25519(pair) => MultiSignature::Sr25519(pair.sign(signer_payload).0),
+			Keypair::Ed25519(pair) => MultiSignature::Ed25519(pair.sign(signer_payload).0),
+			Keypair::Ecdsa(pair) => MultiSignature::Ecdsa(pair.sign(signer_payload).0),
+		}
+	}
+}
+
+/// Create a Signer from a secret URI, the name of an account in the account store, or a source of
+/// the secret URI: `env:VAR` to read it from an environment variable or `file:path` to read it from
+/// a file.
+///
+/// The account store is only consulted when `suri` is not a secret URI. Stored accounts are
+/// unlocked using the password provided, and use the scheme they were stored with.
+///
+/// # Arguments
+///
+/// * `suri` - the secret URI, account name or source of the secret URI.
+/// * `scheme` - the cryptographic scheme of the keypair created from a secret URI.
+/// * `password` - the password of the stored account, when `suri` is the name of an account.
+pub(crate) fn create_signer(
+	suri: &str,
+	scheme: Scheme,
+	password: Option<&str>,
+) -> Result<Keypair, Error> {
+	resolve_signer(suri, scheme, password, accounts_file().ok().as_deref())
+}
+
+/// Returns the stored account named by `suri`, which must be unlocked with its password, unless
+/// `suri` is a secret URI or a source of one.
+///
+/// # Arguments
+///
+/// * `suri` - the secret URI, account name or source of the secret URI.
+/// * `scheme` - the cryptographic scheme of the keypair created from a secret URI.
+pub fn find_account(suri: &str, scheme: Scheme) -> Result<Option<Account>, Error> {
+	find_stored_account(suri, scheme, accounts_file().ok().as_deref())
+}
+
+/// Reads a secret URI from its source: `env:VAR` to read it from an environment variable or
+/// `file:path` to read it from a file. Any other value is returned as the secret URI itself.
+///
+/// # Arguments
+///
+/// * `suri` - the secret URI or its source.
+pub fn read_suri(suri: &str) -> Result<String, Error> {
+	if let Some(var) = suri.strip_prefix("env:") {
+		let suri = env::var(var).map_err(|e| Error::ParseSecretURI(format!("{var}: {e}")))?;
+		return Ok(suri.trim().to_string());
+	}
+	if let Some(path) = suri.strip_prefix("file:") {
+		let suri =
+			fs::read_to_string(path).map_err(|e| Error::ParseSecretURI(format!("{path}: {e}")))?;
+		return Ok(suri.trim().to_string());
+	}
+	Ok(suri.to_string())
+}
+
+// Returns the account with the specified name from the account store, unless the secret URI is
+// valid or read from a source.
+fn find_stored_account(
+	suri: &str,
+	scheme: Scheme,
+	store: Option<&Path>,
+) -> Result<Option<Account>, Error> {
+	let secret = suri.starts_with("env:") ||
+		suri.starts_with("file:") ||
+		Keypair::from_uri(suri, scheme).is_ok();
+	match (secret, store) {
+		(false, Some(store)) => Ok(Accounts::load(store)?.get(suri).cloned()),
+		_ => Ok(None),
+	}
+}
+
+// Unlocks the stored account with the specified name, or otherwise creates the signer from the
+// secret URI.
+fn resolve_signer(
+	suri: &str,
+	scheme: Scheme,
+	password: Option<&str>,
+	store: Option<&Path>,
+) -> Result<Keypair, Error> {
+	match find_stored_account(suri, scheme, store)? {
+		Some(account) => account.unlock(password.ok_or_else(|| {
+			Error::Account(format!("the account `{suri}` is locked: its password is required"))
+		})?),
+		None => Keypair::from_uri(&read_suri(suri)?, scheme),
+	}
 }
 
 /// Parse hex encoded bytes.
@@ -17,3 +206,129 @@ pub fn parse_hex_bytes(input: &str) -> Result<Bytes, Error> {
 	let bytes = decode_hex(input).map_err(|e| Error::HexParsing(format!("{}", e)))?;
 	Ok(bytes.into())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use strum::VariantArray as _;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	#[test]
+	fn create_signer_from_uri_works() -> Result<()> {
+		assert_eq!(
+			create_signer("//Alice", Scheme::Sr25519, None)?.account_id().to_string(),
+			ALICE
+		);
+		assert!(matches!(
+			create_signer("not a secret", Scheme::Sr25519, None),
+			Err(Error::KeyPairCreation(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn keypair_schemes_work() -> Result<()> {
+		let payload = b"payload";
+		for scheme in Scheme::VARIANTS {
+			let keypair = Keypair::from_uri("//Alice", *scheme)?;
+			assert_eq!(keypair.scheme(), *scheme);
+			let verified = match (Signer::<DefaultConfig>::sign(&keypair, payload), &keypair) {
+				(MultiSignature::Sr25519(signature), Keypair::Sr25519(pair)) =>
+					sr25519::Pair::verify(
+						&sr25519::Signature::from_raw(signature),
+						payload,
+						&pair.public(),
+					),
+				(MultiSignature::Ed25519(signature), Keypair::Ed25519(pair)) =>
+					ed25519::Pair::verify(
+						&ed25519::Signature::from_raw(signature),
+						payload,
+						&pair.public(),
+					),
+				(MultiSignature::Ecdsa(signature), Keypair::Ecdsa(pair)) => ecdsa::Pair::verify(
+					&ecdsa::Signature::from_raw(signature),
+					payload,
+					&pair.public(),
+				),
+				_ => false,
+			};
+			assert!(verified, "invalid {scheme} signature");
+		}
+		// The accounts of the schemes differ, with ecdsa accounts being the hash of the public key.
+		let ed25519 = Keypair::from_uri("//Alice", Scheme::Ed25519)?;
+		assert_eq!(
+			ed25519.account_id().to_string(),
+			"5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu"
+		);
+		let ecdsa = Keypair::from_uri("//Alice", Scheme::Ecdsa)?;
+		assert_eq!(ecdsa.account_id().0, blake2_256(&ecdsa.public()));
+		assert_eq!("ED25519".parse::<Scheme>().ok(), None);
+		assert_eq!("ed25519".parse::<Scheme>()?, Scheme::Ed25519);
+		Ok(())
+	}
+
+	#[test]
+	fn create_signer_from_sources_works() -> Result<()> {
+		// Cargo provides the name of the package being tested.
+		assert_eq!(read_suri("env:CARGO_PKG_NAME")?, "pop-contracts");
+		assert!(matches!(
+			create_signer("env:POP_TEST_MISSING", Scheme::Sr25519, None),
+			Err(Error::ParseSecretURI(..))
+		));
+
+		let temp_dir = tempfile::tempdir()?;
+		let path = temp_dir.path().join("suri");
+		fs::write(&path, "//Alice\n")?;
+		assert_eq!(
+			create_signer(&format!("file:{}", path.display()), Scheme::Sr25519, None)?
+				.account_id()
+				.to_string(),
+			ALICE
+		);
+		assert!(matches!(
+			create_signer(
+				&format!("file:{}", temp_dir.path().join("missing").display()),
+				Scheme::Sr25519,
+				None
+			),
+			Err(Error::ParseSecretURI(..))
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn resolve_signer_unlocks_accounts() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let store = temp_dir.path().join("accounts.json");
+		let mut accounts = Accounts::load(&store)?;
+		let keypair = Keypair::from_uri("//Alice", Scheme::Ed25519)?;
+		accounts.add(Account::testing("alice", &keypair, "secret")?)?;
+		// An account named as a secret URI is never used in place of the secret URI.
+		accounts.add(Account::testing("//Bob", &keypair, "secret")?)?;
+		accounts.save()?;
+		let store = Some(store.as_path());
+
+		// The scheme of a stored account takes precedence.
+		let signer = resolve_signer("alice", Scheme::Sr25519, Some("secret"), store)?;
+		assert_eq!(signer.scheme(), Scheme::Ed25519);
+		assert_eq!(signer.account_id(), keypair.account_id());
+		assert!(matches!(
+			resolve_signer("alice", Scheme::Sr25519, None, store),
+			Err(Error::Account(..))
+		));
+		let signer = resolve_signer("//Alice", Scheme::Sr25519, None, store)?;
+		assert_eq!(signer.account_id().to_string(), ALICE);
+		assert!(find_stored_account("//Bob", Scheme::Sr25519, store)?.is_none());
+		assert_eq!(
+			find_stored_account("alice", Scheme::Sr25519, store)?.map(|a| a.address),
+			Some(keypair.account_id().to_string())
+		);
+		assert!(matches!(
+			resolve_signer("bob", Scheme::Sr25519, None, store),
+			Err(Error::KeyPairCreation(..))
+		));
+		Ok(())
+	}
+}
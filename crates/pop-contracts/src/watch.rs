@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::{errors::Error, utils::helpers::get_manifest_path};
+use contract_extrinsics::ContractArtifacts;
+use std::{
+	collections::BTreeMap,
+	io::ErrorKind,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+use walkdir::WalkDir;
+
+/// The interval at which the source files of a watched contract are checked for changes.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A snapshot of the source files of a contract project, used to detect changes to them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SourceSnapshot(BTreeMap<PathBuf, SystemTime>);
+
+impl SourceSnapshot {
+	/// Takes a snapshot of the source files of a contract project: its Rust sources and manifests,
+	/// excluding build artifacts and hidden directories. Files removed while the snapshot is taken
+	/// are omitted.
+	///
+	/// # Arguments
+	///
+	/// * `path` - location of the contract project, defaulting to the current directory.
+	pub fn take(path: &Option<PathBuf>) -> Result<Self, Error> {
+		let path = path.as_deref().unwrap_or(Path::new("./"));
+		let mut files = BTreeMap::new();
+		let entries = WalkDir::new(path).into_iter().filter_entry(|e| {
+			e.depth() == 0 ||
+				!(e.file_type().is_dir() &&
+					(e.file_name() == "target" ||
+						e.file_name().to_string_lossy().starts_with('.')))
+		});
+		let modified = |entry: &walkdir::DirEntry| -> std::io::Result<SystemTime> {
+			entry.metadata().map_err(std::io::Error::from)?.modified()
+		};
+		for entry in entries {
+			let entry = match entry.map_err(std::io::Error::from) {
+				Ok(entry) => entry,
+				Err(e) if e.kind() == ErrorKind::NotFound => continue,
+				Err(e) => return Err(e.into()),
+			};
+			if entry.file_type().is_file() && is_source(entry.path()) {
+				match modified(&entry) {
+					Ok(modified) => {
+						files.insert(entry.path().to_path_buf(), modified);
+					},
+					Err(e) if e.kind() == ErrorKind::NotFound => {},
+					Err(e) => return Err(e.into()),
+				}
+			}
+		}
+		Ok(Self(files))
+	}
+
+	/// The files added, modified or removed since an earlier snapshot.
+	///
+	/// # Arguments
+	///
+	/// * `earlier` - the earlier snapshot.
+	pub fn changes(&self, earlier: &SourceSnapshot) -> Vec<PathBuf> {
+		let mut changes: Vec<_> = self
+			.0
+			.iter()
+			.filter(|(path, modified)| earlier.0.get(*path) != Some(modified))
+			.map(|(path, _)| path.clone())
+			.collect();
+		changes.extend(earlier.0.keys().filter(|path| !self.0.contains_key(*path)).cloned());
+		changes.sort();
+		changes
+	}
+}
+
+// Whether a file is a source file of a contract project.
+fn is_source(path: &Path) -> bool {
+	path.extension().is_some_and(|extension| extension == "rs") ||
+		path.file_name().is_some_and(|name| name == "Cargo.toml")
+}
+
+/// Waits until the source files of a contract project change, returning the files changed. Once a
+/// change is detected, waits until the files are no longer changing, so that a save touching
+/// several files is reported as a single change.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract project, defaulting to the current directory.
+/// * `snapshot` - the snapshot the files are compared with, which is updated to their state once
+///   changed.
+/// * `interval` - the interval at which the files are checked for changes.
+pub async fn wait_for_changes(
+	path: &Option<PathBuf>,
+	snapshot: &mut SourceSnapshot,
+	interval: Duration,
+) -> Result<Vec<PathBuf>, Error> {
+	let earlier = snapshot.clone();
+	loop {
+		tokio::time::sleep(interval).await;
+		let current = SourceSnapshot::take(path)?;
+		if current != *snapshot {
+			*snapshot = current;
+		} else if current != earlier {
+			return Ok(current.changes(&earlier));
+		}
+	}
+}
+
+/// The hash of the code of a built contract, as recorded within its artifacts.
+///
+/// # Arguments
+///
+/// * `path` - location of the contract project, defaulting to the current directory.
+pub fn built_code_hash(path: &Option<PathBuf>) -> anyhow::Result<String> {
+	let manifest_path = get_manifest_path(path)?.as_ref().to_path_buf();
+	let artifacts = ContractArtifacts::from_manifest_or_file(Some(&manifest_path), None)?;
+	Ok(sp_core::bytes::to_hex(&artifacts.metadata()?.source.hash.0, false))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use std::fs;
+
+	fn touch(path: &Path, contents: &str) -> Result<()> {
+		fs::write(path, contents)?;
+		// Ensure the modification time differs from that of any earlier write.
+		let modified = fs::metadata(path)?.modified()? + Duration::from_secs(1);
+		fs::File::options().write(true).open(path)?.set_modified(modified)?;
+		Ok(())
+	}
+
+	#[test]
+	fn source_snapshot_detects_changes() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		fs::create_dir_all(temp_dir.path().join("target/ink"))?;
+		fs::create_dir_all(temp_dir.path().join(".git"))?;
+		touch(&temp_dir.path().join("Cargo.toml"), "[package]")?;
+		touch(&temp_dir.path().join("lib.rs"), "")?;
+		let snapshot = SourceSnapshot::take(&path)?;
+		assert_eq!(snapshot.0.len(), 2);
+
+		// Build artifacts, hidden directories and other files are ignored.
+		touch(&temp_dir.path().join("target/ink/lib.rs"), "")?;
+		touch(&temp_dir.path().join(".git/lib.rs"), "")?;
+		touch(&temp_dir.path().join("deployments.json"), "[]")?;
+		assert_eq!(SourceSnapshot::take(&path)?.changes(&snapshot), Vec::<PathBuf>::new());
+
+		touch(&temp_dir.path().join("lib.rs"), "// changed")?;
+		fs::remove_file(temp_dir.path().join("Cargo.toml"))?;
+		assert_eq!(
+			SourceSnapshot::take(&path)?.changes(&snapshot),
+			vec![temp_dir.path().join("Cargo.toml"), temp_dir.path().join("lib.rs")]
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn wait_for_changes_works() -> Result<()> {
+		let temp_dir = tempfile::tempdir()?;
+		let path = Some(temp_dir.path().to_path_buf());
+		touch(&temp_dir.path().join("lib.rs"), "")?;
+		let mut snapshot = SourceSnapshot::take(&path)?;
+
+		let file = temp_dir.path().join("lib.rs");
+		let writer = tokio::spawn(async move {
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			touch(&file, "// changed")
+		});
+		let changes = wait_for_changes(&path, &mut snapshot, Duration::from_millis(20)).await?;
+		writer.await??;
+		assert_eq!(changes, vec![temp_dir.path().join("lib.rs")]);
+		assert_eq!(snapshot, SourceSnapshot::take(&path)?);
+		Ok(())
+	}
+}
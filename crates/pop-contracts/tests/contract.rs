@@ -1,8 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0
 use anyhow::{Error, Result};
+use contract_extrinsics::Code;
 use pop_contracts::{
-	build_smart_contract, create_smart_contract, dry_run_gas_estimate_instantiate,
-	set_up_deployment, UpOpts,
+	build_smart_contract, create_smart_contract, dry_run_gas_estimate_instantiate, dry_run_upload,
+	set_up_deployment, set_up_upload, ContractTemplate, Scheme, UpOpts,
 };
 use std::fs;
 use tempfile::TempDir;
@@ -12,7 +13,11 @@ fn setup_test_environment() -> std::result::Result<tempfile::TempDir, Error> {
 	let temp_dir = tempfile::tempdir().expect("Could not create temp dir");
 	let temp_contract_dir = temp_dir.path().join("test_contract");
 	fs::create_dir(&temp_contract_dir)?;
-	create_smart_contract("test_contract", temp_contract_dir.as_path())?;
+	create_smart_contract(
+		"test_contract",
+		temp_contract_dir.as_path(),
+		&ContractTemplate::Standard,
+	)?;
 	Ok(temp_dir)
 }
 
@@ -40,17 +45,21 @@ fn test_contract_build() -> std::result::Result<(), Error> {
 	// Test building in release mode
 	let temp_contract_dir = setup_test_environment()?;
 
-	let formatted_result =
+	let artifacts =
 		build_smart_contract(&Some(temp_contract_dir.path().join("test_contract")), true)?;
-	assert!(formatted_result.contains("RELEASE"));
+	assert!(artifacts.release);
+	assert!(artifacts.code.is_some_and(|code| code.exists()));
+	assert!(artifacts.code_size.is_some_and(|size| size > 0));
+	assert!(artifacts.code_hash.is_some());
 
 	verify_build_files(temp_contract_dir)?;
 
 	let temp_debug_contract_dir = setup_test_environment()?;
 	// Test building in debug mode
-	let formatted_result_debug_mode =
+	let artifacts =
 		build_smart_contract(&Some(temp_debug_contract_dir.path().join("test_contract")), false)?;
-	assert!(formatted_result_debug_mode.contains("DEBUG"));
+	assert!(!artifacts.release);
+	assert!(artifacts.bundle.is_some_and(|bundle| bundle.exists()));
 
 	verify_build_files(temp_debug_contract_dir)?;
 
@@ -76,9 +85,13 @@ async fn test_set_up_deployment() -> std::result::Result<(), Error> {
 		value: "1000".to_string(),
 		gas_limit: None,
 		proof_size: None,
+		storage_deposit_limit: None,
 		url: Url::parse(CONTRACTS_NETWORK_URL)?,
 		suri: "//Alice".to_string(),
+		scheme: Scheme::Sr25519,
+		password: None,
 		salt: None,
+		code_hash: None,
 	};
 	let result = set_up_deployment(call_opts).await?;
 	assert_eq!(result.opts().url(), "wss://rococo-contracts-rpc.polkadot.io:443/");
@@ -97,9 +110,13 @@ async fn test_dry_run_gas_estimate_instantiate() -> std::result::Result<(), Erro
 		value: "0".to_string(),
 		gas_limit: None,
 		proof_size: None,
+		storage_deposit_limit: None,
 		url: Url::parse(CONTRACTS_NETWORK_URL)?,
 		suri: "//Alice".to_string(),
+		scheme: Scheme::Sr25519,
+		password: None,
 		salt: None,
+		code_hash: None,
 	};
 	let instantiate_exec = set_up_deployment(call_opts).await;
 
@@ -109,3 +126,57 @@ async fn test_dry_run_gas_estimate_instantiate() -> std::result::Result<(), Erro
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_set_up_upload() -> std::result::Result<(), Error> {
+	let temp_dir = setup_test_environment()?;
+	build_smart_contract_test_environment(&temp_dir)?;
+
+	let up_opts = UpOpts {
+		path: Some(temp_dir.path().join("test_contract")),
+		constructor: "new".to_string(),
+		args: ["false".to_string()].to_vec(),
+		value: "1000".to_string(),
+		gas_limit: None,
+		proof_size: None,
+		storage_deposit_limit: None,
+		url: Url::parse(CONTRACTS_NETWORK_URL)?,
+		suri: "//Alice".to_string(),
+		scheme: Scheme::Sr25519,
+		password: None,
+		salt: None,
+		code_hash: None,
+	};
+	let upload_exec = set_up_upload(up_opts).await?;
+	assert_eq!(upload_exec.opts().url(), "wss://rococo-contracts-rpc.polkadot.io:443/");
+
+	let upload_result = dry_run_upload(&upload_exec).await?;
+	assert_eq!(upload_result.code_hash.0, upload_exec.code().code_hash());
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_set_up_deployment_from_code_hash() -> std::result::Result<(), Error> {
+	let temp_dir = setup_test_environment()?;
+	build_smart_contract_test_environment(&temp_dir)?;
+
+	let code_hash = [1u8; 32];
+	let up_opts = UpOpts {
+		path: Some(temp_dir.path().join("test_contract")),
+		constructor: "new".to_string(),
+		args: ["false".to_string()].to_vec(),
+		value: "1000".to_string(),
+		gas_limit: None,
+		proof_size: None,
+		storage_deposit_limit: None,
+		url: Url::parse(CONTRACTS_NETWORK_URL)?,
+		suri: "//Alice".to_string(),
+		scheme: Scheme::Sr25519,
+		password: None,
+		salt: None,
+		code_hash: Some(code_hash),
+	};
+	let instantiate_exec = set_up_deployment(up_opts).await?;
+	assert!(matches!(instantiate_exec.args().code(), Code::Existing(hash) if hash.0 == code_hash));
+	Ok(())
+}
@@ -0,0 +1,58 @@
+;; A contract implementing the `new`, `default`, `flip` and `get` functions of `testing.json`, which
+;; reverts with `LangError::CouldNotReadInput` for any other message.
+(module
+	(import "seal0" "input" (func $input (param i32 i32)))
+	(import "seal0" "get_storage" (func $get_storage (param i32 i32 i32) (result i32)))
+	(import "seal0" "set_storage" (func $set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32): the storage key of the value, [32, 36): the length of the input,
+	;; [64, 128): the input, [128, 132): the length of the value read,
+	;; [159, 161): `Ok(value)`, [200, 202): `Err(LangError::CouldNotReadInput)`.
+	(data (i32.const 200) "\01\01")
+
+	;; Reads the input, returning its selector.
+	(func $selector (result i32)
+		(i32.store (i32.const 32) (i32.const 64))
+		(call $input (i32.const 64) (i32.const 32))
+		(i32.load (i32.const 64))
+	)
+
+	(func $store (param $value i32)
+		(i32.store8 (i32.const 160) (local.get $value))
+		(call $set_storage (i32.const 0) (i32.const 160) (i32.const 1))
+	)
+
+	(func $load (result i32)
+		(i32.store (i32.const 128) (i32.const 32))
+		(drop (call $get_storage (i32.const 0) (i32.const 160) (i32.const 128)))
+		(i32.load8_u (i32.const 160))
+	)
+
+	(func (export "deploy")
+		(if (i32.eq (call $selector) (i32.const 0x5e9dae9b))
+			(then (call $store (i32.load8_u (i32.const 68))))
+			(else (call $store (i32.const 0)))
+		)
+		(call $seal_return (i32.const 0) (i32.const 159) (i32.const 1))
+	)
+
+	(func (export "call")
+		(local $selector i32)
+		(local.set $selector (call $selector))
+		(if (i32.eq (local.get $selector) (i32.const 0x51a53a63))
+			(then
+				(call $store (i32.eqz (call $load)))
+				(call $seal_return (i32.const 0) (i32.const 159) (i32.const 1))
+			)
+		)
+		(if (i32.eq (local.get $selector) (i32.const 0xd95b862f))
+			(then
+				(drop (call $load))
+				(call $seal_return (i32.const 0) (i32.const 159) (i32.const 2))
+			)
+		)
+		(call $seal_return (i32.const 1) (i32.const 200) (i32.const 2))
+	)
+)
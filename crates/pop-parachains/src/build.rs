@@ -3,10 +3,18 @@ use duct::cmd;
 use std::path::PathBuf;
 
 /// Build the parachain located in the specified `path`.
-pub fn build_parachain(path: &Option<PathBuf>) -> anyhow::Result<()> {
-	cmd("cargo", vec!["build", "--release"])
-		.dir(path.clone().unwrap_or("./".into()))
-		.run()?;
+///
+/// # Arguments
+///
+/// * `path` - the path to the parachain [default: current directory].
+/// * `packages` - the packages to build, such as the node and runtime of a workspace which also
+///   has contracts [default: those selected by cargo].
+pub fn build_parachain(path: &Option<PathBuf>, packages: &[String]) -> anyhow::Result<()> {
+	let mut args = vec!["build".to_string(), "--release".to_string()];
+	for package in packages {
+		args.extend(["-p".to_string(), package.clone()]);
+	}
+	cmd("cargo", args).dir(path.clone().unwrap_or("./".into())).run()?;
 
 	Ok(())
 }
@@ -56,9 +56,6 @@ pub enum Error {
 	#[error("Failed to parse the endowment value")]
 	EndowmentError,
 
-	#[error("Unsupported platform: {arch} {os}")]
-	UnsupportedPlatform { arch: &'static str, os: &'static str },
-
-	#[error("Archive error: {0}")]
-	ArchiveError(String),
+	#[error("{0}")]
+	CommonError(#[from] pop_common::Error),
 }
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: GPL-3.0
+use crate::Error;
+use pop_common::{run_tests, Status, TestOpts, TestReport};
+use std::path::PathBuf;
+
+/// Run the tests of the parachain located in the specified `path`, returning the result of each
+/// test.
+///
+/// # Arguments
+///
+/// * `path` - location of the parachain.
+/// * `opts` - options for running the tests.
+/// * `status` - observer of the output of the tests.
+pub fn test_parachain(
+	path: &Option<PathBuf>,
+	opts: &TestOpts,
+	status: &impl Status,
+) -> Result<TestReport, Error> {
+	Ok(run_tests(&path.clone().unwrap_or("./".into()), opts, &[], status)?)
+}
@@ -1,12 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0
-use super::{
-	sourcing,
+use super::{Binary, Error};
+use pop_common::{
 	sourcing::{
+		self,
 		traits::{Source as _, *},
 		GitHub::ReleaseArchive,
 		Source,
 	},
-	target, Binary, Error,
+	target,
 };
 use std::path::Path;
 use strum::VariantArray as _;
@@ -39,11 +40,15 @@ impl TryInto for Parachain {
 	/// # Arguments
 	/// * `tag` - If applicable, a tag used to determine a specific release.
 	/// * `latest` - If applicable, some specifier used to determine the latest source.
-	fn try_into(&self, tag: Option<String>, latest: Option<String>) -> Result<Source, Error> {
+	fn try_into(
+		&self,
+		tag: Option<String>,
+		latest: Option<String>,
+	) -> Result<Source, pop_common::Error> {
 		Ok(match self {
 			Parachain::System | Parachain::Pop => {
 				// Source from GitHub release asset
-				let repo = crate::GitHub::parse(self.repository())?;
+				let repo = pop_common::GitHub::parse(self.repository())?;
 				Source::GitHub(ReleaseArchive {
 					owner: repo.org,
 					repository: repo.name,
@@ -52,6 +57,7 @@ impl TryInto for Parachain {
 					archive: format!("{}-{}.tar.gz", self.binary(), target()?),
 					contents: vec![self.binary()],
 					latest,
+					checksum: None,
 				})
 			},
 		})
@@ -156,6 +162,7 @@ mod tests {
 					archive: format!("{name}-{}.tar.gz", target()?),
 					contents: vec![expected.binary()],
 					latest: parachain.binary.latest().map(|l| l.to_string()),
+					checksum: None,
 				}) && cache == temp_dir.path()
 		));
 		Ok(())
@@ -181,6 +188,7 @@ mod tests {
 					archive: format!("{name}-{}.tar.gz", target()?),
 					contents: vec![expected.binary()],
 					latest: parachain.binary.latest().map(|l| l.to_string()),
+					checksum: None,
 				}) && cache == temp_dir.path()
 		));
 		Ok(())
@@ -205,6 +213,7 @@ mod tests {
 					archive: format!("{name}-{}.tar.gz", target()?),
 					contents: vec![expected.binary()],
 					latest: parachain.binary.latest().map(|l| l.to_string()),
+					checksum: None,
 				}) && cache == temp_dir.path()
 		));
 		Ok(())
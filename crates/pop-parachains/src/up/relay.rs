@@ -1,12 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0
-use super::{
-	sourcing,
+use super::{Binary, Error};
+use pop_common::{
 	sourcing::{
+		self,
 		traits::{Source as _, *},
 		GitHub::ReleaseArchive,
 		Source,
 	},
-	target, Binary, Error,
+	target,
 };
 use std::{iter::once, path::Path};
 use strum::VariantArray as _;
@@ -31,11 +32,15 @@ impl TryInto for &RelayChain {
 	/// # Arguments
 	/// * `tag` - If applicable, a tag used to determine a specific release.
 	/// * `latest` - If applicable, some specifier used to determine the latest source.
-	fn try_into(&self, tag: Option<String>, latest: Option<String>) -> Result<Source, Error> {
+	fn try_into(
+		&self,
+		tag: Option<String>,
+		latest: Option<String>,
+	) -> Result<Source, pop_common::Error> {
 		Ok(match self {
 			RelayChain::Polkadot => {
 				// Source from GitHub release asset
-				let repo = crate::GitHub::parse(self.repository())?;
+				let repo = pop_common::GitHub::parse(self.repository())?;
 				Source::GitHub(ReleaseArchive {
 					owner: repo.org,
 					repository: repo.name,
@@ -44,6 +49,7 @@ impl TryInto for &RelayChain {
 					archive: format!("{}-{}.tar.gz", self.binary(), target()?),
 					contents: once(self.binary()).chain(self.workers()).collect(),
 					latest,
+					checksum: None,
 				})
 			},
 		})
@@ -126,6 +132,7 @@ mod tests {
 					archive: format!("{name}-{}.tar.gz", target()?),
 					contents: vec!["polkadot", "polkadot-execute-worker", "polkadot-prepare-worker"],
 					latest: relay.binary.latest().map(|l| l.to_string()),
+					checksum: None,
 				}) && cache == temp_dir.path()
 		));
 		assert_eq!(relay.workers, expected.workers());
@@ -156,6 +163,7 @@ mod tests {
 					archive: format!("{name}-{}.tar.gz", target()?),
 					contents: vec!["polkadot", "polkadot-execute-worker", "polkadot-prepare-worker"],
 					latest: relay.binary.latest().map(|l| l.to_string()),
+					checksum: None,
 				}) && cache == temp_dir.path()
 		));
 		assert_eq!(relay.workers, expected.workers());